@@ -0,0 +1,105 @@
+//! Minimal CosmWasm pool contract that reports a fixed pool liquidity.
+//! It only answers the queries that the cosmwasmpool module makes,
+//! so that CosmWasm pools can be created in integration tests.
+
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw_storage_plus::Item;
+
+const POOL_LIQUIDITY: Item<Vec<Coin>> = Item::new("pool_liquidity");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub pool_liquidity: Vec<Coin>,
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    GetSwapFee {},
+    IsActive {},
+    GetTotalShares {},
+    GetTotalPoolLiquidity {},
+    SpotPrice {
+        quote_asset_denom: String,
+        base_asset_denom: String,
+    },
+}
+
+#[cw_serde]
+pub struct GetSwapFeeResponse {
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub struct IsActiveResponse {
+    pub is_active: bool,
+}
+
+#[cw_serde]
+pub struct GetTotalSharesResponse {
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct GetTotalPoolLiquidityResponse {
+    pub total_pool_liquidity: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SpotPriceResponse {
+    pub spot_price: Decimal,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    POOL_LIQUIDITY.save(deps.storage, &msg.pool_liquidity)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetSwapFee {} => to_json_binary(&GetSwapFeeResponse {
+            swap_fee: Decimal::zero(),
+        }),
+        QueryMsg::IsActive {} => to_json_binary(&IsActiveResponse { is_active: true }),
+        QueryMsg::GetTotalShares {} => to_json_binary(&GetTotalSharesResponse {
+            total_shares: Uint128::zero(),
+        }),
+        QueryMsg::GetTotalPoolLiquidity {} => to_json_binary(&GetTotalPoolLiquidityResponse {
+            total_pool_liquidity: POOL_LIQUIDITY.load(deps.storage)?,
+        }),
+        QueryMsg::SpotPrice {
+            quote_asset_denom,
+            base_asset_denom,
+        } => {
+            let pool_liquidity = POOL_LIQUIDITY.load(deps.storage)?;
+            let reserve_of = |denom: &str| {
+                pool_liquidity
+                    .iter()
+                    .find(|coin| coin.denom == denom)
+                    .map(|coin| coin.amount)
+                    .ok_or_else(|| StdError::not_found(denom))
+            };
+
+            let spot_price = Decimal::checked_from_ratio(
+                reserve_of(&quote_asset_denom)?,
+                reserve_of(&base_asset_denom)?,
+            )
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+            to_json_binary(&SpotPriceResponse { spot_price })
+        }
+    }
+}
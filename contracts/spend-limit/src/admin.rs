@@ -1,12 +1,44 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure, Addr};
+use cosmwasm_std::{ensure, Addr, Timestamp};
 
 use crate::ContractError;
 
+/// Roles that the admin, as the owner of the contract, can grant to other addresses.
+/// The owner holds all the roles and is the only one allowed to grant and revoke them.
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    /// Manages how prices are resolved, e.g. price resolution config, fixed prices and price profiles
+    PriceManager,
+
+    /// Manages the denoms that are priced, e.g. tracked denoms, aliases and exponents
+    DenomManager,
+
+    /// Pauses and unpauses authentication in emergencies, e.g. when pricing is compromised
+    Guardian,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::PriceManager => "price_manager",
+            Role::DenomManager => "denom_manager",
+            Role::Guardian => "guardian",
+        }
+    }
+}
+
 #[cw_serde]
 pub enum Admin {
     Settled(Addr),
-    Transferring { current: Addr, candidate: Addr },
+    Transferring {
+        current: Addr,
+        candidate: Addr,
+
+        /// Time after which the candidate can no longer claim the transfer, never expires if not set
+        #[serde(default)]
+        expires_at: Option<Timestamp>,
+    },
     None,
 }
 
@@ -45,6 +77,18 @@ impl Admin {
         }
     }
 
+    /// Revert the transfer to the current admin if the offer has expired at `time`
+    pub fn settle_expired_transfer(self, time: Timestamp) -> Self {
+        match self {
+            Admin::Transferring {
+                current,
+                expires_at: Some(expires_at),
+                ..
+            } if time > expires_at => Admin::Settled(current),
+            admin => admin,
+        }
+    }
+
     pub fn authorize_admin(&self, addr: &Addr) -> Result<(), ContractError> {
         ensure!(Some(addr) == self.admin(), ContractError::Unauthorized {});
         Ok(())
@@ -68,23 +112,41 @@ impl Admin {
         self,
         sender: &Addr,
         candidate: Addr,
+        expires_at: Option<Timestamp>,
     ) -> Result<Self, ContractError> {
         self.authorize_admin(sender)?;
 
         match self {
-            Admin::Settled(current) => Ok(Admin::Transferring { current, candidate }),
+            Admin::Settled(current) => Ok(Admin::Transferring {
+                current,
+                candidate,
+                expires_at,
+            }),
             Admin::Transferring {
                 current,
                 candidate: _old_candidate,
-            } => Ok(Admin::Transferring { current, candidate }),
+                expires_at: _old_expires_at,
+            } => Ok(Admin::Transferring {
+                current,
+                candidate,
+                expires_at,
+            }),
             Admin::None => Err(ContractError::Unauthorized {}),
         }
     }
 
-    pub fn authorized_claim_admin_transfer(self, sender: &Addr) -> Result<Self, ContractError> {
+    pub fn authorized_claim_admin_transfer(
+        self,
+        sender: &Addr,
+        time: Timestamp,
+    ) -> Result<Self, ContractError> {
         self.authorize_candidate(sender)?;
 
         match self {
+            Admin::Transferring {
+                expires_at: Some(expires_at),
+                ..
+            } if time > expires_at => Err(ContractError::AdminTransferExpired { expires_at }),
             Admin::Transferring { candidate, .. } => Ok(Admin::Settled(candidate)),
             _ => Err(ContractError::Unauthorized {}),
         }
@@ -112,7 +174,23 @@ impl Admin {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::Addr;
+    use cosmwasm_std::{from_json, to_json_vec, Addr};
+
+    #[test]
+    fn role_serialization() {
+        for (role, name) in [
+            (Role::PriceManager, "price_manager"),
+            (Role::DenomManager, "denom_manager"),
+            (Role::Guardian, "guardian"),
+        ] {
+            assert_eq!(role.as_str(), name);
+            assert_eq!(
+                to_json_vec(&role).unwrap(),
+                format!("\"{name}\"").into_bytes()
+            );
+            assert_eq!(from_json::<Role>(format!("\"{name}\"")).unwrap(), role);
+        }
+    }
 
     #[test]
     fn admin_retrieval() {
@@ -124,6 +202,7 @@ mod tests {
         let admin_transferring = Admin::Transferring {
             current: admin.clone(),
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert_eq!(admin_transferring.admin(), Some(&admin));
 
@@ -138,6 +217,7 @@ mod tests {
         let admin = Admin::Transferring {
             current: current.clone(),
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert_eq!(admin.candidate(), Some(&candidate));
 
@@ -167,6 +247,7 @@ mod tests {
         let admin = Admin::Transferring {
             current: current.clone(),
             candidate,
+            expires_at: None,
         };
         assert!(admin.authorize_admin(&other_addr).is_err());
 
@@ -181,6 +262,7 @@ mod tests {
         let admin = Admin::Transferring {
             current,
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert!(admin.authorize_candidate(&candidate).is_ok());
     }
@@ -193,6 +275,7 @@ mod tests {
         let admin_transferring = Admin::Transferring {
             current: current.clone(),
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert!(admin_transferring.authorize_candidate(&other_addr).is_err());
 
@@ -227,10 +310,11 @@ mod tests {
         let candidate = Addr::unchecked("candidate");
         let admin = Admin::Settled(addr.clone());
         assert!(matches!(
-            admin.authorized_transfer_admin(&addr, candidate.clone()),
+            admin.authorized_transfer_admin(&addr, candidate.clone(), None),
             Ok(Admin::Transferring {
                 current: _,
-                candidate: _
+                candidate: _,
+                expires_at: None
             })
         ));
     }
@@ -243,12 +327,14 @@ mod tests {
         let admin = Admin::Transferring {
             current: addr.clone(),
             candidate: prev_candidate,
+            expires_at: None,
         };
         assert_eq!(
-            admin.authorized_transfer_admin(&addr, candidate.clone()),
+            admin.authorized_transfer_admin(&addr, candidate.clone(), None),
             Ok(Admin::Transferring {
                 current: addr,
                 candidate,
+                expires_at: None,
             })
         );
     }
@@ -260,7 +346,7 @@ mod tests {
         let other_addr = Addr::unchecked("other");
         let admin = Admin::Settled(addr);
         assert!(admin
-            .authorized_transfer_admin(&other_addr, candidate)
+            .authorized_transfer_admin(&other_addr, candidate, None)
             .is_err());
     }
 
@@ -271,9 +357,10 @@ mod tests {
         let admin = Admin::Transferring {
             current,
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert!(matches!(
-            admin.authorized_claim_admin_transfer(&candidate),
+            admin.authorized_claim_admin_transfer(&candidate, Timestamp::from_seconds(1)),
             Ok(Admin::Settled(_))
         ));
     }
@@ -283,8 +370,67 @@ mod tests {
         let current = Addr::unchecked("current");
         let candidate = Addr::unchecked("candidate");
         let other_addr = Addr::unchecked("other");
-        let admin = Admin::Transferring { current, candidate };
-        assert!(admin.authorized_claim_admin_transfer(&other_addr).is_err());
+        let admin = Admin::Transferring {
+            current,
+            candidate,
+            expires_at: None,
+        };
+        assert!(admin
+            .authorized_claim_admin_transfer(&other_addr, Timestamp::from_seconds(1))
+            .is_err());
+    }
+
+    #[test]
+    fn claim_admin_expired() {
+        let current = Addr::unchecked("current");
+        let candidate = Addr::unchecked("candidate");
+        let expires_at = Timestamp::from_seconds(100);
+        let admin = Admin::Transferring {
+            current,
+            candidate: candidate.clone(),
+            expires_at: Some(expires_at),
+        };
+
+        assert_eq!(
+            admin
+                .clone()
+                .authorized_claim_admin_transfer(&candidate, expires_at),
+            Ok(Admin::Settled(candidate.clone()))
+        );
+        assert_eq!(
+            admin.authorized_claim_admin_transfer(&candidate, expires_at.plus_nanos(1)),
+            Err(ContractError::AdminTransferExpired { expires_at })
+        );
+    }
+
+    #[test]
+    fn settle_expired_transfer() {
+        let current = Addr::unchecked("current");
+        let candidate = Addr::unchecked("candidate");
+        let expires_at = Timestamp::from_seconds(100);
+        let transferring = |expires_at| Admin::Transferring {
+            current: current.clone(),
+            candidate: candidate.clone(),
+            expires_at,
+        };
+
+        assert_eq!(
+            transferring(Some(expires_at)).settle_expired_transfer(expires_at),
+            transferring(Some(expires_at))
+        );
+        assert_eq!(
+            transferring(Some(expires_at)).settle_expired_transfer(expires_at.plus_nanos(1)),
+            Admin::Settled(current.clone())
+        );
+        assert_eq!(
+            transferring(None)
+                .settle_expired_transfer(Timestamp::from_seconds(u64::MAX / 1_000_000_000)),
+            transferring(None)
+        );
+        assert_eq!(
+            Admin::Settled(current.clone()).settle_expired_transfer(expires_at.plus_nanos(1)),
+            Admin::Settled(current)
+        );
     }
 
     #[test]
@@ -294,6 +440,7 @@ mod tests {
         let admin = Admin::Transferring {
             current,
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert!(matches!(
             admin.authorized_reject_admin_transfer(&candidate),
@@ -306,7 +453,11 @@ mod tests {
         let current = Addr::unchecked("current");
         let candidate = Addr::unchecked("candidate");
         let other_addr = Addr::unchecked("other");
-        let admin = Admin::Transferring { current, candidate };
+        let admin = Admin::Transferring {
+            current,
+            candidate,
+            expires_at: None,
+        };
         assert!(admin.authorized_reject_admin_transfer(&other_addr).is_err());
     }
 
@@ -317,6 +468,7 @@ mod tests {
         let admin = Admin::Transferring {
             current: current.clone(),
             candidate: candidate.clone(),
+            expires_at: None,
         };
         assert_eq!(
             admin.authorized_cancel_admin_transfer(&current),
@@ -329,7 +481,11 @@ mod tests {
         let current = Addr::unchecked("current");
         let candidate = Addr::unchecked("candidate");
         let other_addr = Addr::unchecked("other");
-        let admin = Admin::Transferring { current, candidate };
+        let admin = Admin::Transferring {
+            current,
+            candidate,
+            expires_at: None,
+        };
         assert!(admin.authorized_cancel_admin_transfer(&other_addr).is_err());
     }
 }
@@ -0,0 +1,617 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::Map;
+
+use crate::{
+    msg::{DenomRemovalTarget, ExecuteMsg},
+    price::{PriceResolutionConfig, TrackedDenom},
+    price_profile::PriceProfile,
+};
+
+/// Append-only log of the admin operations, keyed by the sequence number of the entry.
+pub type AuditLogStore<'a> = Map<'a, u64, AuditLogEntry>;
+
+#[cw_serde]
+pub struct AuditLogEntry {
+    pub id: u64,
+
+    /// Address that performed the operation, not set if it's performed by chain governance
+    pub sender: Option<Addr>,
+
+    /// Name of the operation, same as the `action` attribute of its response
+    pub action: String,
+
+    pub block_time: Timestamp,
+
+    /// Changes to the price resolution config, tracked denoms and prices made by the operation
+    pub changes: Vec<AuditChange>,
+}
+
+#[cw_serde]
+pub enum AuditChange {
+    /// Price resolution config of the price profile, not set if the profile doesn't exist
+    PriceResolutionConfig {
+        price_profile: Option<String>,
+        previous: Option<PriceResolutionConfig>,
+        new: Option<PriceResolutionConfig>,
+    },
+
+    /// Tracking of the denom in the price profile, not set if the denom is not tracked
+    TrackedDenom {
+        price_profile: Option<String>,
+        denom: String,
+        previous: Option<TrackedDenom>,
+        new: Option<TrackedDenom>,
+    },
+
+    /// Cached price of the denom in the price profile, not set if the denom is not tracked
+    Price {
+        price_profile: Option<String>,
+        denom: String,
+        previous: Option<Decimal>,
+        new: Option<Decimal>,
+    },
+
+    /// Quote denom migration of the price profile,
+    /// with the conversion rate that its spendings are rescaled with
+    QuoteDenomMigration {
+        price_profile: Option<String>,
+        conversion_rate: Decimal,
+    },
+}
+
+/// What the admin operation is recorded with in the audit log.
+#[derive(Debug, PartialEq)]
+pub enum AuditScope {
+    /// Record the operation along with the changes to the price resolution config of the price profile
+    /// and to the tracking of the denoms the operation receives, so that the record stays bounded
+    /// regardless of how many denoms the price profile tracks
+    PriceProfile {
+        price_profile: Option<String>,
+        denoms: Vec<String>,
+    },
+
+    /// Record the operation along with the changes to the price resolution config of the price profile
+    /// and to the tracking of all its denoms, for the operations that go through all of them anyway
+    /// or that remove them, so that the removed swap routes and prices are recorded
+    WholePriceProfile { price_profile: Option<String> },
+
+    /// Record the operation only, it doesn't change any price profile
+    Operation,
+}
+
+/// Scope of the admin operation in the audit log, `None` if it's not recorded.
+/// Executing a proposed admin action is recorded as the action itself.
+/// Removing all tracked denoms, removing a price profile and migrating the quote denom
+/// go through every denom of the price profile, so all of them are recorded.
+pub fn audit_scope(msg: &ExecuteMsg) -> Option<AuditScope> {
+    let price_profile_scope = |price_profile: &Option<String>, denoms: Vec<String>| {
+        Some(AuditScope::PriceProfile {
+            price_profile: price_profile.clone(),
+            denoms,
+        })
+    };
+    let tracked = |tracked_denoms: &[TrackedDenom]| {
+        tracked_denoms
+            .iter()
+            .map(|tracked_denom| tracked_denom.denom.clone())
+            .collect()
+    };
+
+    match msg {
+        ExecuteMsg::SetPriceResolutionParams { price_profile, .. } => {
+            price_profile_scope(price_profile, vec![])
+        }
+        ExecuteMsg::MigrateQuoteDenom { price_profile, .. } => {
+            Some(AuditScope::WholePriceProfile {
+                price_profile: price_profile.clone(),
+            })
+        }
+        ExecuteMsg::SetTrackedDenoms {
+            price_profile,
+            tracked_denoms,
+        } => price_profile_scope(price_profile, tracked(tracked_denoms)),
+        ExecuteMsg::RemoveTrackedDenoms {
+            price_profile,
+            target,
+        } => match target {
+            DenomRemovalTarget::All => Some(AuditScope::WholePriceProfile {
+                price_profile: price_profile.clone(),
+            }),
+            DenomRemovalTarget::Partial(denoms) => {
+                price_profile_scope(price_profile, denoms.clone())
+            }
+        },
+        ExecuteMsg::AutoTrackDenom {
+            price_profile,
+            denom,
+            ..
+        } => price_profile_scope(price_profile, vec![denom.clone()]),
+        ExecuteMsg::SetFixedPrices {
+            price_profile,
+            prices,
+        } => price_profile_scope(
+            price_profile,
+            prices.iter().map(|(denom, _)| denom.clone()).collect(),
+        ),
+        ExecuteMsg::AcceptPrices {
+            price_profile,
+            denoms,
+        } => price_profile_scope(price_profile, denoms.clone()),
+        ExecuteMsg::CreatePriceProfile {
+            name,
+            tracked_denoms,
+            ..
+        } => price_profile_scope(&Some(name.clone()), tracked(tracked_denoms)),
+        ExecuteMsg::RemovePriceProfile { name } => Some(AuditScope::WholePriceProfile {
+            price_profile: Some(name.clone()),
+        }),
+        ExecuteMsg::RefreshPrices { .. }
+        | ExecuteMsg::Prune { .. }
+        | ExecuteMsg::ExecuteAdminAction { .. } => None,
+        _ => Some(AuditScope::Operation),
+    }
+}
+
+/// Price resolution config, tracking and price of the given denoms and the last quote denom migration
+/// of a price profile at a point in time, compared before and after the operation to record its changes.
+#[derive(Debug, PartialEq)]
+pub struct PriceProfileSnapshot {
+    config: Option<PriceResolutionConfig>,
+    tracked_denoms: BTreeMap<String, Option<(TrackedDenom, Decimal)>>,
+    last_quote_denom_migration: Option<(u64, Decimal)>,
+    whole: bool,
+}
+
+impl PriceProfileSnapshot {
+    /// Load the config and the tracking of the `denoms`,
+    /// along with the quote denom since migrating it tracks the previous one.
+    pub fn load(
+        storage: &dyn Storage,
+        price_profile: &PriceProfile,
+        denoms: impl IntoIterator<Item = String>,
+    ) -> StdResult<Self> {
+        Self::load_denoms(storage, price_profile, denoms, false)
+    }
+
+    /// Load the config and the tracking of all the denoms of the price profile
+    pub fn load_whole(storage: &dyn Storage, price_profile: &PriceProfile) -> StdResult<Self> {
+        Self::load_denoms(storage, price_profile, [], true)
+    }
+
+    /// Load the snapshot of the same denoms after the operation,
+    /// along with the denoms tracked since then if the whole price profile is snapshotted
+    pub fn reload(&self, storage: &dyn Storage, price_profile: &PriceProfile) -> StdResult<Self> {
+        Self::load_denoms(
+            storage,
+            price_profile,
+            self.tracked_denoms.keys().cloned(),
+            self.whole,
+        )
+    }
+
+    fn load_denoms(
+        storage: &dyn Storage,
+        price_profile: &PriceProfile,
+        denoms: impl IntoIterator<Item = String>,
+        whole: bool,
+    ) -> StdResult<Self> {
+        let config = price_profile.may_load_config(storage)?;
+        let price_infos = price_profile.price_infos();
+
+        let mut denoms = denoms
+            .into_iter()
+            .chain(config.as_ref().map(|conf| conf.quote_denom.clone()))
+            .collect::<BTreeSet<_>>();
+
+        if whole {
+            for denom in price_infos.keys(storage, None, None, Order::Ascending) {
+                denoms.insert(denom?);
+            }
+        }
+
+        let tracked_denoms = denoms
+            .into_iter()
+            .map(|denom| {
+                let tracked_denom = price_infos
+                    .may_load(storage, &denom)?
+                    .map(|price_info| (price_info.tracked_denom(&denom), price_info.price));
+                Ok((denom, tracked_denom))
+            })
+            .collect::<StdResult<_>>()?;
+
+        let last_quote_denom_migration = price_profile
+            .quote_denom_migrations()
+            .range(storage, None, None, Order::Descending)
+            .next()
+            .transpose()?;
+
+        Ok(Self {
+            config,
+            tracked_denoms,
+            last_quote_denom_migration,
+            whole,
+        })
+    }
+
+    /// Changes from this snapshot to the `new` one
+    pub fn changes(
+        mut self,
+        new: PriceProfileSnapshot,
+        price_profile: Option<&str>,
+    ) -> Vec<AuditChange> {
+        let mut changes = vec![];
+
+        if self.config != new.config {
+            changes.push(AuditChange::PriceResolutionConfig {
+                price_profile: price_profile.map(String::from),
+                previous: self.config,
+                new: new.config,
+            });
+        }
+
+        // the new snapshot is reloaded with all denoms of this one
+        for (denom, new) in new.tracked_denoms {
+            let (previous, previous_price) = self.tracked_denoms.remove(&denom).flatten().unzip();
+            let (new, new_price) = new.unzip();
+
+            if previous != new {
+                changes.push(AuditChange::TrackedDenom {
+                    price_profile: price_profile.map(String::from),
+                    denom: denom.clone(),
+                    previous,
+                    new,
+                });
+            }
+
+            if previous_price != new_price {
+                changes.push(AuditChange::Price {
+                    price_profile: price_profile.map(String::from),
+                    denom,
+                    previous: previous_price,
+                    new: new_price,
+                });
+            }
+        }
+
+        if let Some((_, conversion_rate)) = new
+            .last_quote_denom_migration
+            .filter(|migration| Some(migration) != self.last_quote_denom_migration.as_ref())
+        {
+            changes.push(AuditChange::QuoteDenomMigration {
+                price_profile: price_profile.map(String::from),
+                conversion_rate,
+            });
+        }
+
+        changes
+    }
+}
+
+/// Append the entry to the audit log with the next sequence number
+pub fn append_audit_log(
+    audit_log_store: &AuditLogStore,
+    storage: &mut dyn Storage,
+    sender: Option<Addr>,
+    action: String,
+    block_time: Timestamp,
+    changes: Vec<AuditChange>,
+) -> StdResult<u64> {
+    let id = audit_log_store
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last_id| last_id + 1);
+
+    audit_log_store.save(
+        storage,
+        id,
+        &AuditLogEntry {
+            id,
+            sender,
+            action,
+            block_time,
+            changes,
+        },
+    )?;
+
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_dependencies, Decimal, Uint64};
+    use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
+
+    use crate::price::PriceInfo;
+    use crate::state::PRICE_RESOLUTION_CONFIG;
+
+    use super::*;
+    use crate::test_helper::price::{price_info, price_resolution_config};
+
+    const AUDIT_LOG: AuditLogStore = Map::new("audit_log");
+
+    fn pool_price_info(pool_id: u64) -> PriceInfo {
+        price_info(
+            Decimal::one(),
+            Timestamp::from_seconds(1),
+            vec![SwapAmountInRoute {
+                pool_id,
+                token_out_denom: "uusdc".to_string(),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_price_profile_snapshot_changes() {
+        let mut deps = mock_dependencies();
+        let price_profile = PriceProfile::new(None);
+        let price_infos = price_profile.price_infos();
+
+        price_infos
+            .save(&mut deps.storage, "uatom", &pool_price_info(1))
+            .unwrap();
+        price_infos
+            .save(&mut deps.storage, "ujuno", &pool_price_info(5))
+            .unwrap();
+        price_infos
+            .save(&mut deps.storage, "uosmo", &pool_price_info(2))
+            .unwrap();
+
+        let denoms = ["uatom", "uion", "ujuno", "uosmo"].map(String::from);
+        let previous = PriceProfileSnapshot::load(&deps.storage, &price_profile, denoms).unwrap();
+
+        let conf = PriceResolutionConfig {
+            staleness_threshold: Uint64::from(3_600_000_000u64),
+            twap_duration: Uint64::from(3_600_000_000u64),
+            ..price_resolution_config("uusdc")
+        };
+        PRICE_RESOLUTION_CONFIG
+            .save(&mut deps.storage, &conf)
+            .unwrap();
+
+        // price only change is recorded without tracking change
+        price_infos
+            .save(
+                &mut deps.storage,
+                "ujuno",
+                &PriceInfo {
+                    price: Decimal::percent(200),
+                    ..pool_price_info(5)
+                },
+            )
+            .unwrap();
+        price_infos
+            .save(&mut deps.storage, "uosmo", &pool_price_info(3))
+            .unwrap();
+        price_infos.remove(&mut deps.storage, "uatom");
+        price_infos
+            .save(&mut deps.storage, "uion", &pool_price_info(4))
+            .unwrap();
+
+        // denoms not given to the snapshot are not recorded
+        price_infos
+            .save(&mut deps.storage, "uregen", &pool_price_info(6))
+            .unwrap();
+
+        let new = previous.reload(&deps.storage, &price_profile).unwrap();
+
+        assert_eq!(
+            previous.changes(new, None),
+            vec![
+                AuditChange::PriceResolutionConfig {
+                    price_profile: None,
+                    previous: None,
+                    new: Some(conf),
+                },
+                AuditChange::TrackedDenom {
+                    price_profile: None,
+                    denom: "uatom".to_string(),
+                    previous: Some(pool_price_info(1).tracked_denom("uatom")),
+                    new: None,
+                },
+                AuditChange::Price {
+                    price_profile: None,
+                    denom: "uatom".to_string(),
+                    previous: Some(Decimal::one()),
+                    new: None,
+                },
+                AuditChange::TrackedDenom {
+                    price_profile: None,
+                    denom: "uion".to_string(),
+                    previous: None,
+                    new: Some(pool_price_info(4).tracked_denom("uion")),
+                },
+                AuditChange::Price {
+                    price_profile: None,
+                    denom: "uion".to_string(),
+                    previous: None,
+                    new: Some(Decimal::one()),
+                },
+                AuditChange::Price {
+                    price_profile: None,
+                    denom: "ujuno".to_string(),
+                    previous: Some(Decimal::one()),
+                    new: Some(Decimal::percent(200)),
+                },
+                AuditChange::TrackedDenom {
+                    price_profile: None,
+                    denom: "uosmo".to_string(),
+                    previous: Some(pool_price_info(2).tracked_denom("uosmo")),
+                    new: Some(pool_price_info(3).tracked_denom("uosmo")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whole_price_profile_snapshot_changes() {
+        let mut deps = mock_dependencies();
+        let price_profile = PriceProfile::new(Some("osmo".to_string()));
+        let price_infos = price_profile.price_infos();
+
+        price_infos
+            .save(&mut deps.storage, "uatom", &pool_price_info(1))
+            .unwrap();
+        price_profile
+            .save_quote_denom_migration(&mut deps.storage, Decimal::percent(50))
+            .unwrap();
+
+        let previous = PriceProfileSnapshot::load_whole(&deps.storage, &price_profile).unwrap();
+
+        // denoms not known to the operation are recorded along with the quote denom migration
+        price_infos
+            .save(
+                &mut deps.storage,
+                "uatom",
+                &PriceInfo::fixed(Decimal::percent(99), Timestamp::from_seconds(2)),
+            )
+            .unwrap();
+        price_infos
+            .save(&mut deps.storage, "uion", &pool_price_info(2))
+            .unwrap();
+        price_profile
+            .save_quote_denom_migration(&mut deps.storage, Decimal::percent(99))
+            .unwrap();
+
+        let new = previous.reload(&deps.storage, &price_profile).unwrap();
+        let price_profile = Some("osmo".to_string());
+
+        assert_eq!(
+            previous.changes(new, price_profile.as_deref()),
+            vec![
+                AuditChange::TrackedDenom {
+                    price_profile: price_profile.clone(),
+                    denom: "uatom".to_string(),
+                    previous: Some(pool_price_info(1).tracked_denom("uatom")),
+                    new: Some(
+                        PriceInfo::fixed(Decimal::percent(99), Timestamp::from_seconds(2))
+                            .tracked_denom("uatom")
+                    ),
+                },
+                AuditChange::Price {
+                    price_profile: price_profile.clone(),
+                    denom: "uatom".to_string(),
+                    previous: Some(Decimal::one()),
+                    new: Some(Decimal::percent(99)),
+                },
+                AuditChange::TrackedDenom {
+                    price_profile: price_profile.clone(),
+                    denom: "uion".to_string(),
+                    previous: None,
+                    new: Some(pool_price_info(2).tracked_denom("uion")),
+                },
+                AuditChange::Price {
+                    price_profile: price_profile.clone(),
+                    denom: "uion".to_string(),
+                    previous: None,
+                    new: Some(Decimal::one()),
+                },
+                AuditChange::QuoteDenomMigration {
+                    price_profile,
+                    conversion_rate: Decimal::percent(99),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_audit_scope() {
+        let price_profile_scope = |denoms: &[&str]| {
+            Some(AuditScope::PriceProfile {
+                price_profile: None,
+                denoms: denoms.iter().map(|denom| denom.to_string()).collect(),
+            })
+        };
+
+        assert_eq!(
+            audit_scope(&ExecuteMsg::RemoveTrackedDenoms {
+                price_profile: None,
+                target: DenomRemovalTarget::Partial(vec!["uatom".to_string()]),
+            }),
+            price_profile_scope(&["uatom"])
+        );
+        assert_eq!(
+            audit_scope(&ExecuteMsg::RemoveTrackedDenoms {
+                price_profile: None,
+                target: DenomRemovalTarget::All,
+            }),
+            Some(AuditScope::WholePriceProfile {
+                price_profile: None
+            })
+        );
+        assert_eq!(
+            audit_scope(&ExecuteMsg::RemovePriceProfile {
+                name: "osmo".to_string(),
+            }),
+            Some(AuditScope::WholePriceProfile {
+                price_profile: Some("osmo".to_string())
+            })
+        );
+        assert_eq!(
+            audit_scope(&ExecuteMsg::SetFixedPrices {
+                price_profile: None,
+                prices: vec![("uusdt".to_string(), Decimal::one())],
+            }),
+            price_profile_scope(&["uusdt"])
+        );
+        assert_eq!(
+            audit_scope(&ExecuteMsg::AcceptPrices {
+                price_profile: None,
+                denoms: vec!["uosmo".to_string()],
+            }),
+            price_profile_scope(&["uosmo"])
+        );
+        assert_eq!(
+            audit_scope(&ExecuteMsg::MigrateQuoteDenom {
+                price_profile: None,
+                quote_denom: "unoble".to_string(),
+                conversion_rate: Decimal::one(),
+                tracked_denoms: vec![],
+            }),
+            Some(AuditScope::WholePriceProfile {
+                price_profile: None
+            })
+        );
+        assert_eq!(
+            audit_scope(&ExecuteMsg::RefreshPrices {
+                price_profile: None,
+                denoms: None,
+                start_after: None,
+                limit: None,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_append_audit_log() {
+        let mut deps = mock_dependencies();
+
+        for (expected_id, sender) in [(0, Some(Addr::unchecked("admin"))), (1, None)] {
+            let id = append_audit_log(
+                &AUDIT_LOG,
+                &mut deps.storage,
+                sender.clone(),
+                "set_tracked_denoms".to_string(),
+                Timestamp::from_seconds(expected_id),
+                vec![],
+            )
+            .unwrap();
+
+            assert_eq!(id, expected_id);
+            assert_eq!(
+                AUDIT_LOG.load(&deps.storage, id).unwrap(),
+                AuditLogEntry {
+                    id,
+                    sender,
+                    action: "set_tracked_denoms".to_string(),
+                    block_time: Timestamp::from_seconds(expected_id),
+                    changes: vec![],
+                }
+            );
+        }
+    }
+}
@@ -161,6 +161,7 @@ mod tests {
     use crate::{period::Period, spend_limit::SpendLimitParams};
 
     use super::*;
+    use crate::fee::FeePolicy;
     use cosmwasm_std::to_json_vec;
     use rstest::rstest;
 
@@ -198,8 +199,12 @@ mod tests {
     fn test_child_authenticator_data() {
         let params = SpendLimitParams {
             limit: 1000000u128.into(),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         };
         // no depth
         let target_data = CosmwasmAuthenticatorData {
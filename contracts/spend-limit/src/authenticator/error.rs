@@ -20,6 +20,12 @@ pub enum AuthenticatorError {
         src: StdError,
     },
 
+    #[error("Limit and display limit can't be set together")]
+    ConflictingLimits,
+
+    #[error("Display limit requires the exponent of quote denom {denom}, but it is unknown")]
+    UnknownQuoteDenomExponent { denom: String },
+
     #[error("Authenticator already exists for account {account} and authenticator id {authenticator_id}")]
     AuthenticatorAlreadyExists {
         account: Addr,
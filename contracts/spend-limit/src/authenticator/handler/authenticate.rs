@@ -3,8 +3,10 @@ use cw_authenticator::AuthenticationRequest;
 
 use crate::{
     fee::get_account_spending_fee,
-    spend_limit::update_and_check_spend_limit,
-    state::{PRICE_INFOS, PRICE_RESOLUTION_CONFIG, SPENDINGS, UNTRACKED_SPENT_FEES},
+    pause::ensure_authentication_allowed,
+    price_profile::PriceProfile,
+    spend_limit::{update_and_check_fee_limit, update_and_check_spend_limit},
+    state::{DENOM_ALIASES, PAUSE_STATE, SPENDINGS, UNTRACKED_SPENT_FEES},
     ContractError,
 };
 
@@ -15,9 +17,10 @@ pub fn authenticate(
     env: Env,
     auth_request: AuthenticationRequest,
 ) -> Result<Response, ContractError> {
+    let strict = ensure_authentication_allowed(&PAUSE_STATE, deps.storage)?;
     let params = validate_and_parse_params(auth_request.authenticator_params)?;
 
-    if let Some(time_limit) = params.time_limit {
+    if let Some(time_limit) = &params.time_limit {
         let start = time_limit.start.unwrap_or(Timestamp::from_nanos(0));
         let end = time_limit.end;
 
@@ -37,14 +40,18 @@ pub fn authenticate(
         auth_request.authenticator_id.as_str(),
     );
 
+    let price_profile = PriceProfile::new(params.price_profile.clone());
     let mut spending = SPENDINGS.load(deps.storage, key)?;
+    price_profile.migrate_spending(deps.storage, &mut spending)?;
+    let limit = params.limit_in_base_units(spending.display_limit_exponent)?;
     let untracked_spent_fee = UNTRACKED_SPENT_FEES
         .may_load(deps.storage, key)?
         .unwrap_or_default()
         .get_or_reset_accum_fee(&params.reset_period, env.block.time)?;
-    let conf = PRICE_RESOLUTION_CONFIG.load(deps.storage)?;
+    let conf = price_profile.load_config(deps.storage)?;
 
     let account_spending_fee = get_account_spending_fee(
+        params.fee_policy,
         &auth_request.account,
         &auth_request.fee_payer,
         auth_request.fee_granter.as_ref(),
@@ -54,37 +61,60 @@ pub fn authenticate(
     // check whether the fee spent + about to spend is within the limit
     // this will not be committed to the state
     let coins = [untracked_spent_fee, account_spending_fee].concat();
-    update_and_check_spend_limit(
-        deps,
-        &PRICE_INFOS,
-        &mut spending,
-        coins,
-        Coins::default(),
-        &conf,
-        params.limit,
-        &params.reset_period,
-        env.block.time,
-    )?;
-
-    Ok(Response::new().add_attribute("action", "authenticate"))
+    let events = match params.fee_limit {
+        Some(fee_limit) => update_and_check_fee_limit(
+            deps,
+            &price_profile.price_infos(),
+            &DENOM_ALIASES,
+            &mut spending,
+            coins,
+            &conf,
+            fee_limit,
+            &params.reset_period,
+            env.block.time,
+            strict,
+        )?,
+        None => update_and_check_spend_limit(
+            deps,
+            &price_profile.price_infos(),
+            &DENOM_ALIASES,
+            &mut spending,
+            coins,
+            Coins::default(),
+            &conf,
+            limit,
+            &params.reset_period,
+            env.block.time,
+            strict,
+        )?,
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "authenticate")
+        .add_events(events))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fee::UntrackedSpentFee;
+    use crate::fee::{FeePolicy, UntrackedSpentFee};
+    use crate::pause::{PauseMode, PauseState};
     use crate::period::Period;
-    use crate::price::{track_denom, PriceResolutionConfig};
+    use crate::price::{track_denom, BlockTimeBehindLastUpdate, PriceError, PriceResolutionConfig};
     use crate::spend_limit::{SpendLimitError, Spending};
     use crate::spend_limit::{SpendLimitParams, TimeLimit};
+    use crate::state::PRICE_RESOLUTION_CONFIG;
     use crate::test_helper::mock_stargate_querier::{
-        arithmetic_twap_to_now_query_handler, mock_dependencies_with_stargate_querier,
+        arithmetic_twap_to_now_query_handler, balancer_pools_query_handler, combine_query_handlers,
+        mock_dependencies_with_stargate_querier,
     };
+    use crate::test_helper::price::{price_resolution_config, tracked_denom};
 
     use crate::state::PRICE_INFOS;
     use cosmwasm_std::{
+        coin,
         testing::{mock_dependencies_with_balances, mock_env},
-        to_json_binary, Addr, Binary, Coin, ContractResult, Timestamp,
+        to_json_binary, Addr, Binary, Coin, ContractResult, Event, Timestamp,
     };
     use cw_authenticator::{Any, SignModeTxData, SignatureData, TxData};
     use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
@@ -116,14 +146,7 @@ mod tests {
             .unwrap();
 
         PRICE_RESOLUTION_CONFIG
-            .save(
-                deps.as_mut().storage,
-                &PriceResolutionConfig {
-                    quote_denom: "uusdc".to_string(),
-                    staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                    twap_duration: 3_600_000_000_000u64.into(),       // 1h
-                },
-            )
+            .save(deps.as_mut().storage, &price_resolution_config("uusdc"))
             .unwrap();
 
         let time_limit = time_limit.map(|(start, end)| TimeLimit {
@@ -140,8 +163,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: 1000u128.into(),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: time_limit.clone(),
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -271,18 +298,24 @@ mod tests {
 
         let mut deps = mock_dependencies_with_stargate_querier(
             &[],
-            arithmetic_twap_to_now_query_handler(Box::new(|req| {
-                let base_asset = req.base_asset.as_str();
-                let quote_asset = req.quote_asset.as_str();
-
-                let arithmetic_twap = match (base_asset, quote_asset) {
-                    ("uosmo", "uusdc") => "1.5",
-                    _ => return ContractResult::Err("Price not found".to_string()),
-                }
-                .to_string();
-
-                ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
-            })),
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    666,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", "uusdc") => "1.5",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
         );
 
         let key = (&Addr::unchecked("account"), "2");
@@ -291,11 +324,7 @@ mod tests {
             .save(&mut deps.storage, key, &Spending::default())
             .unwrap();
 
-        let conf = PriceResolutionConfig {
-            quote_denom: "uusdc".to_string(),
-            staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-            twap_duration: 3_600_000_000_000u64.into(),       // 1h
-        };
+        let conf = price_resolution_config("uusdc");
         PRICE_RESOLUTION_CONFIG
             .save(deps.as_mut().storage, &conf)
             .unwrap();
@@ -304,12 +333,14 @@ mod tests {
             &PRICE_INFOS,
             deps.as_mut(),
             &conf,
-            "uosmo",
             mock_env().block.time,
-            vec![SwapAmountInRoute {
-                pool_id: 666,
-                token_out_denom: "uusdc".to_string(),
-            }],
+            tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 666,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
         )
         .unwrap();
 
@@ -335,8 +366,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: 1_000_000_000u128.into(),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -369,4 +404,319 @@ mod tests {
 
         assert_eq!(response, result);
     }
+
+    #[rstest]
+    #[case::not_paused(None, vec![Coin::new(100, "untracked")], Ok(()))]
+    #[case::frozen(
+        Some(PauseMode::Frozen),
+        vec![Coin::new(100, "uusdc")],
+        Err(ContractError::ContractPaused { reason: "pricing compromised".to_string() })
+    )]
+    #[case::strict_tracked_fee(Some(PauseMode::Strict), vec![Coin::new(100, "uusdc")], Ok(()))]
+    #[case::strict_untracked_fee(
+        Some(PauseMode::Strict),
+        vec![Coin::new(100, "untracked")],
+        Err(SpendLimitError::UntrackedDenomSpent { denom: "untracked".to_string() }.into())
+    )]
+    fn test_authenticate_when_paused(
+        #[case] pause_mode: Option<PauseMode>,
+        #[case] fee: Vec<Coin>,
+        #[case] expected: Result<(), ContractError>,
+    ) {
+        let mut deps = mock_dependencies_with_balances(&[("account", &[])]);
+
+        let key = (&Addr::unchecked("account"), "2");
+
+        SPENDINGS
+            .save(&mut deps.storage, key, &Spending::default())
+            .unwrap();
+
+        PRICE_RESOLUTION_CONFIG
+            .save(deps.as_mut().storage, &price_resolution_config("uusdc"))
+            .unwrap();
+
+        if let Some(mode) = pause_mode {
+            PAUSE_STATE
+                .save(
+                    deps.as_mut().storage,
+                    &PauseState {
+                        mode,
+                        reason: "pricing compromised".to_string(),
+                        paused_by: Addr::unchecked("guardian"),
+                        paused_at: mock_env().block.time,
+                    },
+                )
+                .unwrap();
+        }
+
+        let request = AuthenticationRequest {
+            authenticator_id: "2".to_string(),
+            account: Addr::unchecked("account"),
+            fee_payer: Addr::unchecked("account"),
+            fee_granter: None,
+            fee,
+            authenticator_params: Some(
+                to_json_binary(&SpendLimitParams {
+                    limit: 1000u128.into(),
+                    display_limit: None,
+                    reset_period: Period::Day,
+                    time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
+                })
+                .unwrap(),
+            ),
+            msg: Any {
+                type_url: "".to_string(),
+                value: Binary::default(),
+            },
+            msg_index: 0,
+            signature: Binary::default(),
+            sign_mode_tx_data: SignModeTxData {
+                sign_mode_direct: Binary::default(),
+                sign_mode_textual: None,
+            },
+            tx_data: TxData {
+                chain_id: "osmosis-1".to_string(),
+                account_number: 0,
+                sequence: 0,
+                timeout_height: 0,
+                msgs: vec![],
+                memo: "".to_string(),
+            },
+            signature_data: SignatureData {
+                signers: vec![],
+                signatures: vec![],
+            },
+            simulate: false,
+        };
+
+        let response = authenticate(deps.as_mut(), mock_env(), request).map(|_| ());
+
+        assert_eq!(response, expected);
+    }
+
+    #[rstest]
+    #[case::no_fee_limit(None, vec![Coin::new(100, "uusdc")], Err(SpendLimitError::overspend(1000, 1400).into()))]
+    #[case::within_fee_limit(Some(500), vec![Coin::new(200, "uusdc")], Ok(()))]
+    #[case::over_fee_limit(
+        Some(500),
+        vec![Coin::new(201, "uusdc")],
+        Err(SpendLimitError::FeeOverspend { fee_limit: 500u128.into(), spent: 501u128.into() }.into())
+    )]
+    fn test_authenticate_with_fee_limit(
+        #[case] fee_limit: Option<u128>,
+        #[case] fee: Vec<Coin>,
+        #[case] expected: Result<(), ContractError>,
+    ) {
+        let mut deps = mock_dependencies_with_balances(&[("account", &[])]);
+
+        let key = (&Addr::unchecked("account"), "2");
+
+        // value spent is already at the limit
+        SPENDINGS
+            .save(
+                &mut deps.storage,
+                key,
+                &Spending {
+                    value_spent_in_period: 1000u128.into(),
+                    last_spent_at: mock_env().block.time,
+                    ..Spending::default()
+                },
+            )
+            .unwrap();
+
+        UNTRACKED_SPENT_FEES
+            .save(
+                &mut deps.storage,
+                key,
+                &UntrackedSpentFee {
+                    fee: vec![Coin::new(300, "uusdc")],
+                    updated_at: mock_env().block.time,
+                },
+            )
+            .unwrap();
+
+        PRICE_RESOLUTION_CONFIG
+            .save(deps.as_mut().storage, &price_resolution_config("uusdc"))
+            .unwrap();
+
+        let request = AuthenticationRequest {
+            authenticator_id: "2".to_string(),
+            account: Addr::unchecked("account"),
+            fee_payer: Addr::unchecked("account"),
+            fee_granter: None,
+            fee,
+            authenticator_params: Some(
+                to_json_binary(&SpendLimitParams {
+                    limit: 1000u128.into(),
+                    display_limit: None,
+                    reset_period: Period::Day,
+                    time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: fee_limit.map(Into::into),
+                })
+                .unwrap(),
+            ),
+            msg: Any {
+                type_url: "".to_string(),
+                value: Binary::default(),
+            },
+            msg_index: 0,
+            signature: Binary::default(),
+            sign_mode_tx_data: SignModeTxData {
+                sign_mode_direct: Binary::default(),
+                sign_mode_textual: None,
+            },
+            tx_data: TxData {
+                chain_id: "osmosis-1".to_string(),
+                account_number: 0,
+                sequence: 0,
+                timeout_height: 0,
+                msgs: vec![],
+                memo: "".to_string(),
+            },
+            signature_data: SignatureData {
+                signers: vec![],
+                signatures: vec![],
+            },
+            simulate: false,
+        };
+
+        let response = authenticate(deps.as_mut(), mock_env(), request).map(|_| ());
+
+        assert_eq!(response, expected);
+    }
+
+    #[rstest]
+    #[case::treat_as_fresh(BlockTimeBehindLastUpdate::TreatAsFresh, Ok(()))]
+    #[case::reject(
+        BlockTimeBehindLastUpdate::Reject,
+        Err(PriceError::current_block_time_behind_last_update(
+            mock_env().block.time.minus_seconds(1).nanos(),
+            mock_env().block.time.nanos(),
+        )
+        .into())
+    )]
+    fn test_authenticate_with_block_time_behind_last_update(
+        #[case] block_time_behind_last_update: BlockTimeBehindLastUpdate,
+        #[case] expected: Result<(), ContractError>,
+    ) {
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    666,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", "uusdc") => "1.5",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let key = (&Addr::unchecked("account"), "2");
+
+        SPENDINGS
+            .save(&mut deps.storage, key, &Spending::default())
+            .unwrap();
+
+        let conf = PriceResolutionConfig {
+            block_time_behind_last_update,
+            ..price_resolution_config("uusdc")
+        };
+        PRICE_RESOLUTION_CONFIG
+            .save(deps.as_mut().storage, &conf)
+            .unwrap();
+
+        // price is tracked at the latest block
+        track_denom(
+            &PRICE_INFOS,
+            deps.as_mut(),
+            &conf,
+            mock_env().block.time,
+            tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 666,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+        )
+        .unwrap();
+
+        let request = AuthenticationRequest {
+            authenticator_id: "2".to_string(),
+            account: Addr::unchecked("account"),
+            fee_payer: Addr::unchecked("account"),
+            fee_granter: None,
+            fee: vec![Coin::new(1_000, "uosmo")],
+            authenticator_params: Some(
+                to_json_binary(&SpendLimitParams {
+                    limit: 1_000_000_000u128.into(),
+                    display_limit: None,
+                    reset_period: Period::Day,
+                    time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
+                })
+                .unwrap(),
+            ),
+            msg: Any {
+                type_url: "".to_string(),
+                value: Binary::default(),
+            },
+            msg_index: 0,
+            signature: Binary::default(),
+            sign_mode_tx_data: SignModeTxData {
+                sign_mode_direct: Binary::default(),
+                sign_mode_textual: None,
+            },
+            tx_data: TxData {
+                chain_id: "osmosis-1".to_string(),
+                account_number: 0,
+                sequence: 0,
+                timeout_height: 0,
+                msgs: vec![],
+                memo: "".to_string(),
+            },
+            signature_data: SignatureData {
+                signers: vec![],
+                signatures: vec![],
+            },
+            simulate: true,
+        };
+
+        // authenticate at an older block, e.g. simulation against an older height
+        let mut env = mock_env();
+        env.block.time = env.block.time.minus_seconds(1);
+
+        let response = authenticate(deps.as_mut(), env.clone(), request);
+
+        match expected {
+            Ok(()) => assert_eq!(
+                response.unwrap().events,
+                vec![Event::new("price_block_time_behind_last_update")
+                    .add_attribute("denom", "uosmo")
+                    .add_attribute("block_time", env.block.time.nanos().to_string())
+                    .add_attribute(
+                        "last_updated_time",
+                        mock_env().block.time.nanos().to_string()
+                    )]
+            ),
+            Err(e) => assert_eq!(response.unwrap_err(), e),
+        }
+    }
 }
@@ -1,11 +1,14 @@
 use cosmwasm_std::{DepsMut, Env, Response};
 use cw_authenticator::ConfirmExecutionRequest;
 
+use crate::pause::is_strict;
+use crate::price_profile::PriceProfile;
 use crate::spend_limit::{
-    calculate_received_coins, calculate_spent_coins, update_and_check_spend_limit, SpendLimitParams,
+    calculate_received_coins, calculate_spent_coins, update_and_check_fee_limit,
+    update_and_check_spend_limit, SpendLimitParams,
 };
 use crate::state::{
-    PRE_EXEC_BALANCES, PRICE_INFOS, PRICE_RESOLUTION_CONFIG, SPENDINGS, UNTRACKED_SPENT_FEES,
+    DENOM_ALIASES, PAUSE_STATE, PRE_EXEC_BALANCES, SPENDINGS, UNTRACKED_SPENT_FEES,
 };
 use crate::ContractError;
 
@@ -42,30 +45,53 @@ pub fn confirm_execution(
         .unwrap_or_default()
         .fee;
 
-    // add all untracked spent fees to the spent coins.
+    // add all untracked spent fees to the spent coins, unless they have a separate limit.
     // These are fees that have been deducted on previous failed tx, but still
     // not counted on the spend limit. We add them here.
-    for fee in untracked_spent_fee {
-        spent_coins.add(fee)?;
+    if params.fee_limit.is_none() {
+        for fee in untracked_spent_fee.iter().cloned() {
+            spent_coins.add(fee)?;
+        }
     }
 
     let received_coins = calculate_received_coins(&pre_exec_balances, &post_exec_balances)?;
 
+    let price_profile = PriceProfile::new(params.price_profile.clone());
     let mut spending = SPENDINGS.load(deps.storage, spend_limit_key)?;
-    let conf = PRICE_RESOLUTION_CONFIG.load(deps.storage)?;
+    price_profile.migrate_spending(deps.storage, &mut spending)?;
+    let limit = params.limit_in_base_units(spending.display_limit_exponent)?;
+    let conf = price_profile.load_config(deps.storage)?;
+    let strict = is_strict(&PAUSE_STATE, deps.storage)?;
 
-    update_and_check_spend_limit(
+    let mut events = update_and_check_spend_limit(
         deps.branch(),
-        &PRICE_INFOS,
+        &price_profile.price_infos(),
+        &DENOM_ALIASES,
         &mut spending,
         spent_coins,
         received_coins,
         &conf,
-        params.limit,
+        limit,
         &params.reset_period,
         env.block.time,
+        strict,
     )?;
 
+    if let Some(fee_limit) = params.fee_limit {
+        events.extend(update_and_check_fee_limit(
+            deps.branch(),
+            &price_profile.price_infos(),
+            &DENOM_ALIASES,
+            &mut spending,
+            untracked_spent_fee,
+            &conf,
+            fee_limit,
+            &params.reset_period,
+            env.block.time,
+            strict,
+        )?);
+    }
+
     // save the updated spending
     SPENDINGS.save(deps.storage, spend_limit_key, &spending)?;
 
@@ -76,14 +102,23 @@ pub fn confirm_execution(
     // fee has already been captured as part of the balance difference
     UNTRACKED_SPENT_FEES.remove(deps.storage, spend_limit_key);
 
-    Ok(Response::new()
+    let mut response = Response::new()
         .add_attribute("action", "confirm_execution")
         .add_attribute("spent", spending.value_spent_in_period)
-        .add_attribute("limit", params.limit))
+        .add_attribute("limit", spending.limit_in_quote_denom(limit)?);
+
+    if let Some(fee_limit) = params.fee_limit {
+        response = response
+            .add_attribute("fee_spent", spending.fee_spent_in_period)
+            .add_attribute("fee_limit", spending.limit_in_quote_denom(fee_limit)?);
+    }
+
+    Ok(response.add_events(events))
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::state::PRICE_RESOLUTION_CONFIG;
     use cosmwasm_std::{
         testing::{mock_dependencies_with_balances, mock_env},
         to_json_binary, Addr, Binary, Coin, Response, Uint128,
@@ -93,12 +128,13 @@ mod tests {
 
     use crate::period::Period;
     use crate::{
-        price::PriceResolutionConfig,
         spend_limit::{SpendLimitError, SpendLimitParams, Spending},
         state::UNTRACKED_SPENT_FEES,
     };
 
     use super::*;
+    use crate::fee::FeePolicy;
+    use crate::test_helper::price::price_resolution_config;
 
     #[rstest]
     #[case::spend_at_limit(1000, 500, 500, vec![Coin::new(1_000_000_000, "uosmo")], Ok(Response::new()
@@ -152,14 +188,7 @@ mod tests {
             .unwrap();
 
         PRICE_RESOLUTION_CONFIG
-            .save(
-                deps.as_mut().storage,
-                &PriceResolutionConfig {
-                    quote_denom: "uusdc".to_string(),
-                    staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                    twap_duration: 3_600_000_000_000u64.into(),       // 1h
-                },
-            )
+            .save(deps.as_mut().storage, &price_resolution_config("uusdc"))
             .unwrap();
 
         // Confirm the execution
@@ -172,8 +201,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(limit),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -195,7 +228,12 @@ mod tests {
                     spending,
                     Spending {
                         value_spent_in_period: spent.into(),
-                        last_spent_at: mock_env().block.time
+                        fee_spent_in_period: Uint128::zero(),
+                        last_spent_at: mock_env().block.time,
+                        limit_conversion_rate: None,
+                        display_limit_exponent: None,
+                        price_profile: None,
+                        quote_denom_migrations_applied: 0,
                     }
                 );
 
@@ -221,4 +259,87 @@ mod tests {
             }
         }
     }
+
+    #[rstest]
+    #[case::no_fee_limit(None, Err(SpendLimitError::overspend(500, 800).into()))]
+    #[case::within_fee_limit(Some(300), Ok(Response::new()
+        .add_attribute("action", "confirm_execution")
+        .add_attribute("spent", "500")
+        .add_attribute("limit", "500")
+        .add_attribute("fee_spent", "300")
+        .add_attribute("fee_limit", "300")
+    ))]
+    #[case::over_fee_limit(
+        Some(299),
+        Err(SpendLimitError::FeeOverspend { fee_limit: Uint128::new(299), spent: Uint128::new(300) }.into())
+    )]
+    fn test_confirm_execution_with_fee_limit(
+        #[case] fee_limit: Option<u128>,
+        #[case] expected: Result<Response, ContractError>,
+    ) {
+        use crate::fee::UntrackedSpentFee;
+
+        let mut deps = mock_dependencies_with_balances(&[("account", &[Coin::new(500, "uusdc")])]);
+
+        let key = (&Addr::unchecked("account"), "2");
+
+        UNTRACKED_SPENT_FEES
+            .save(
+                &mut deps.storage,
+                key,
+                &UntrackedSpentFee {
+                    fee: vec![Coin::new(300, "uusdc")],
+                    updated_at: mock_env().block.time,
+                },
+            )
+            .unwrap();
+
+        PRE_EXEC_BALANCES
+            .save(deps.as_mut().storage, key, &vec![Coin::new(1000, "uusdc")])
+            .unwrap();
+
+        SPENDINGS
+            .save(&mut deps.storage, key, &Spending::default())
+            .unwrap();
+
+        PRICE_RESOLUTION_CONFIG
+            .save(deps.as_mut().storage, &price_resolution_config("uusdc"))
+            .unwrap();
+
+        let confirm_execution_request = ConfirmExecutionRequest {
+            authenticator_id: "2".to_string(),
+            account: Addr::unchecked("account"),
+            fee_payer: Addr::unchecked("account"),
+            fee_granter: None,
+            fee: vec![],
+            authenticator_params: Some(
+                to_json_binary(&SpendLimitParams {
+                    limit: Uint128::new(500),
+                    display_limit: None,
+                    reset_period: Period::Day,
+                    time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: fee_limit.map(Uint128::new),
+                })
+                .unwrap(),
+            ),
+            msg: cw_authenticator::Any {
+                type_url: "".to_string(),
+                value: Binary::default(),
+            },
+            msg_index: 0,
+        };
+
+        let res = confirm_execution(deps.as_mut(), mock_env(), confirm_execution_request);
+        let is_ok = res.is_ok();
+        assert_eq!(res, expected);
+
+        if is_ok {
+            // fee is counted separately from the value spent
+            let spending = SPENDINGS.load(deps.as_ref().storage, key).unwrap();
+            assert_eq!(spending.value_spent_in_period, Uint128::new(500));
+            assert_eq!(spending.fee_spent_in_period, Uint128::new(300));
+        }
+    }
 }
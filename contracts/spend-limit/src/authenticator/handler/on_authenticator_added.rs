@@ -3,8 +3,10 @@ use cw_authenticator::OnAuthenticatorAddedRequest;
 
 use crate::{
     authenticator::{handler::validate_and_parse_params, AuthenticatorError},
+    denom_metadata::{from_display_amount, get_denom_exponent},
+    price_profile::PriceProfile,
     spend_limit::Spending,
-    state::SPENDINGS,
+    state::{DENOM_EXPONENTS, SPENDINGS},
 };
 
 pub fn on_authenticator_added(
@@ -16,7 +18,12 @@ pub fn on_authenticator_added(
         authenticator_params,
     }: OnAuthenticatorAddedRequest,
 ) -> Result<Response, AuthenticatorError> {
-    let _ = validate_and_parse_params(authenticator_params)?;
+    let params = validate_and_parse_params(authenticator_params)?;
+
+    ensure!(
+        params.display_limit.is_none() || params.limit.is_zero(),
+        AuthenticatorError::ConflictingLimits
+    );
 
     // Make sure (account, authenticator_id) is not already present in the state
     let key = (&account, authenticator_id.as_str());
@@ -25,8 +32,40 @@ pub fn on_authenticator_added(
         AuthenticatorError::authenticator_already_exists(account, authenticator_id.as_str())
     );
 
-    // initialize the spending for this authenticator
-    SPENDINGS.save(deps.storage, key, &Spending::default())?;
+    // the default price profile always exists, named ones must be created by the admin first
+    let price_profile = PriceProfile::new(params.price_profile.clone());
+    if price_profile.name().is_some() {
+        price_profile.load_config(deps.storage)?;
+    }
+
+    // record the exponent of the current quote denom so that the display limit
+    // keeps its value in base units of that quote denom, even if the exponent changes later
+    let display_limit_exponent = match params.display_limit {
+        Some(display_limit) => {
+            let quote_denom = price_profile.load_config(deps.storage)?.quote_denom;
+            let exponent = get_denom_exponent(deps.as_ref(), &DENOM_EXPONENTS, &quote_denom)?
+                .ok_or(AuthenticatorError::UnknownQuoteDenomExponent { denom: quote_denom })?;
+
+            // make sure the display limit can be converted to base units
+            from_display_amount(display_limit, exponent)?;
+
+            Some(exponent)
+        }
+        None => None,
+    };
+
+    // initialize the spending for this authenticator, valued in the current quote denom
+    let quote_denom_migrations_applied = price_profile.quote_denom_migration_count(deps.storage)?;
+    SPENDINGS.save(
+        deps.storage,
+        key,
+        &Spending {
+            display_limit_exponent,
+            price_profile: params.price_profile,
+            quote_denom_migrations_applied,
+            ..Spending::default()
+        },
+    )?;
 
     Ok(Response::new().add_attribute("action", "on_authenticator_added"))
 }
@@ -34,13 +73,19 @@ pub fn on_authenticator_added(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fee::FeePolicy;
     use crate::period::Period;
+
     use crate::spend_limit::SpendLimitParams;
+    use crate::state::PRICE_RESOLUTION_CONFIG;
+    use crate::test_helper::price::price_resolution_config;
     use cosmwasm_std::{
-        testing::{mock_dependencies_with_balances, mock_env},
+        testing::{mock_dependencies, mock_dependencies_with_balances, mock_env},
         to_json_binary, Addr, Coin, StdError, Uint128,
     };
 
+    use rstest::rstest;
+
     const USDC: &str = "ibc/498A0751C798A0D9A389AA3691123DADA57DAA4FE165D5C75894505B876BA6E4";
 
     #[test]
@@ -80,8 +125,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -106,8 +155,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Month,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -118,4 +171,66 @@ mod tests {
             AuthenticatorError::authenticator_already_exists(Addr::unchecked("addr"), "2")
         );
     }
+
+    #[rstest]
+    #[case::stored_exponent(0, Some("1.5"), Some(6), Ok(Some(6)))]
+    #[case::limit_only(1_500_000, None, None, Ok(None))]
+    #[case::unknown_exponent(
+        0,
+        Some("1.5"),
+        None,
+        Err(AuthenticatorError::UnknownQuoteDenomExponent {
+            denom: "uusdc".to_string()
+        })
+    )]
+    #[case::conflicting_limits(
+        1_500_000,
+        Some("1.5"),
+        Some(6),
+        Err(AuthenticatorError::ConflictingLimits)
+    )]
+    fn test_on_authenticator_added_with_display_limit(
+        #[case] limit: u128,
+        #[case] display_limit: Option<&str>,
+        #[case] quote_denom_exponent: Option<u32>,
+        #[case] expected: Result<Option<u32>, AuthenticatorError>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        PRICE_RESOLUTION_CONFIG
+            .save(deps.as_mut().storage, &price_resolution_config("uusdc"))
+            .unwrap();
+
+        if let Some(exponent) = quote_denom_exponent {
+            DENOM_EXPONENTS
+                .save(deps.as_mut().storage, "uusdc", &exponent)
+                .unwrap();
+        }
+
+        let request = OnAuthenticatorAddedRequest {
+            authenticator_id: "2".to_string(),
+            account: Addr::unchecked("addr"),
+            authenticator_params: Some(
+                to_json_binary(&SpendLimitParams {
+                    limit: Uint128::new(limit),
+                    display_limit: display_limit.map(|d| d.parse().unwrap()),
+                    reset_period: Period::Day,
+                    time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
+                })
+                .unwrap(),
+            ),
+        };
+
+        let res = on_authenticator_added(deps.as_mut(), mock_env(), request).map(|_| {
+            SPENDINGS
+                .load(deps.as_ref().storage, (&Addr::unchecked("addr"), "2"))
+                .unwrap()
+                .display_limit_exponent
+        });
+
+        assert_eq!(res, expected);
+    }
 }
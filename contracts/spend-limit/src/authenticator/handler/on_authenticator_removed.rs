@@ -1,7 +1,11 @@
-use cosmwasm_std::{DepsMut, Env, Response};
+use cosmwasm_std::{DepsMut, Env, Response, Storage};
 use cw_authenticator::OnAuthenticatorRemovedRequest;
 
-use crate::{authenticator::AuthenticatorError, state::SPENDINGS};
+use crate::{
+    authenticator::AuthenticatorError,
+    spend_limit::SpendingKey,
+    state::{PRE_EXEC_BALANCES, SPENDINGS, UNTRACKED_SPENT_FEES},
+};
 
 pub fn on_authenticator_removed(
     deps: DepsMut,
@@ -12,23 +16,31 @@ pub fn on_authenticator_removed(
         ..
     }: OnAuthenticatorRemovedRequest,
 ) -> Result<Response, AuthenticatorError> {
-    // clean up the spending
-    SPENDINGS.remove(deps.storage, (&account, authenticator_id.as_str()));
+    clear_spend_limit_state(deps.storage, (&account, authenticator_id.as_str()));
 
     Ok(Response::new().add_attribute("action", "on_authenticator_removed"))
 }
 
+/// Remove all the state kept for the spend limit, so that nothing is left behind
+/// to affect the accounting of an authenticator added later with the same key.
+pub fn clear_spend_limit_state(storage: &mut dyn Storage, key: SpendingKey) {
+    SPENDINGS.remove(storage, key);
+    PRE_EXEC_BALANCES.remove(storage, key);
+    UNTRACKED_SPENT_FEES.remove(storage, key);
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::{
         testing::{mock_dependencies, mock_env},
-        to_json_binary, Addr,
+        to_json_binary, Addr, Coin,
     };
 
     use crate::period::Period;
     use crate::spend_limit::{SpendLimitParams, Spending};
 
     use super::*;
+    use crate::fee::{FeePolicy, UntrackedSpentFee};
 
     #[test]
     fn test_on_authenticator_removed() {
@@ -39,6 +51,16 @@ mod tests {
         SPENDINGS
             .save(deps.as_mut().storage, key, &Spending::default())
             .unwrap();
+        PRE_EXEC_BALANCES
+            .save(deps.as_mut().storage, key, &vec![Coin::new(100, "uusdc")])
+            .unwrap();
+        UNTRACKED_SPENT_FEES
+            .save(
+                deps.as_mut().storage,
+                key,
+                &UntrackedSpentFee::new(mock_env().block.time),
+            )
+            .unwrap();
         assert!(SPENDINGS.has(deps.as_ref().storage, key));
 
         let msg = OnAuthenticatorRemovedRequest {
@@ -47,8 +69,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: 1000u128.into(),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -56,5 +82,7 @@ mod tests {
 
         on_authenticator_removed(deps.as_mut(), mock_env(), msg).unwrap();
         assert!(!SPENDINGS.has(deps.as_ref().storage, key));
+        assert!(!PRE_EXEC_BALANCES.has(deps.as_ref().storage, key));
+        assert!(!UNTRACKED_SPENT_FEES.has(deps.as_ref().storage, key));
     }
 }
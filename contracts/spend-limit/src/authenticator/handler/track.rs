@@ -28,7 +28,13 @@ pub fn track(
         .may_load(deps.storage, key)?
         .unwrap_or_default()
         .accum(
-            get_account_spending_fee(&account, &fee_payer, fee_granter.as_ref(), fee),
+            get_account_spending_fee(
+                params.fee_policy,
+                &account,
+                &fee_payer,
+                fee_granter.as_ref(),
+                fee,
+            ),
             &params.reset_period,
             env.block.time,
         )?;
@@ -46,7 +52,7 @@ pub fn track(
 mod tests {
     use super::*;
 
-    use crate::fee::UntrackedSpentFee;
+    use crate::fee::{FeePolicy, UntrackedSpentFee};
     use crate::period::Period;
     use crate::{spend_limit::SpendLimitParams, state::UNTRACKED_SPENT_FEES};
     use cosmwasm_std::{
@@ -68,8 +74,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -121,8 +131,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -182,8 +196,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -246,8 +264,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
@@ -310,8 +332,12 @@ mod tests {
             authenticator_params: Some(
                 to_json_binary(&SpendLimitParams {
                     limit: Uint128::new(500_000_000),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 })
                 .unwrap(),
             ),
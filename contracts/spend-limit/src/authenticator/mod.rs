@@ -12,6 +12,6 @@ pub use {
     confirm_execution::confirm_execution,
     error::AuthenticatorError,
     on_authenticator_added::on_authenticator_added,
-    on_authenticator_removed::on_authenticator_removed,
+    on_authenticator_removed::{clear_spend_limit_state, on_authenticator_removed},
     track::track,
 };
@@ -1,21 +1,37 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
-use crate::admin::Admin;
+use crate::admin::{Admin, Role};
+use crate::audit::{append_audit_log, audit_scope, AuditScope, PriceProfileSnapshot};
 use crate::authenticator::{self};
+use crate::denom_metadata::{get_denom_exponent, to_display_amount, MAX_DENOM_EXPONENT};
 use crate::msg::{
-    AdminCandidateResponse, AdminResponse, DenomRemovalTarget, ExecuteMsg, InstantiateMsg,
-    PriceResolutionConfigResponse, QueryMsg, SpendingResponse, SpendingsByAccountResponse, SudoMsg,
-    TrackedDenom, TrackedDenomsResponse,
+    AdminActionDelayResponse, AdminCandidateResponse, AdminResponse, AuditLogResponse,
+    DenomAliasesResponse, DenomExponentResponse, DenomRemovalTarget, ExecuteMsg, InstantiateMsg,
+    OptionalUpdate, PauseStateResponse, PendingAdminActionsResponse, PriceProfilesResponse,
+    PriceRefreshStatus, PriceResolutionConfigResponse, PruneResponse, QueryMsg,
+    RefreshPricesResponse, RoleHoldersResponse, SpendingResponse, SpendingsByAccountResponse,
+    SudoMsg, TrackedDenom, TrackedDenomsResponse,
 };
-use crate::price::{track_denom, PriceResolutionConfig};
-use crate::spend_limit::{updated_spending, SpendLimitError};
-use crate::state::{ADMIN, PRICE_INFOS, PRICE_RESOLUTION_CONFIG, SPENDINGS, UNTRACKED_SPENT_FEES};
+use crate::pause::{PauseMode, PauseState};
+use crate::price::{
+    aliases_of, discover_swap_routes, get_and_cache_price, remove_denom_alias, save_denom_alias,
+    track_denom, validate_denom_alias, BlockTimeBehindLastUpdate, DenomAlias, PriceInfoStore,
+    PriceResolutionConfig, SpotPriceFallback, TwapKind, MAX_DISCOVERED_SWAP_ROUTES_HOPS,
+};
+use crate::price_profile::{validate_price_profile_name, PriceProfile};
+use crate::spend_limit::{may_get_spend_limit_params, updated_spending, SpendLimitError, Spending};
+use crate::state::{
+    ADMIN, ADMIN_ACTION_DELAY, AUDIT_LOG, CANONICAL_DENOM_ALIASES, DENOM_ALIASES, DENOM_EXPONENTS,
+    NEXT_ADMIN_ACTION_ID, PAUSE_STATE, PENDING_ADMIN_ACTIONS, PRE_EXEC_BALANCES, PRICE_INFOS,
+    PRICE_PROFILES, PRICE_RESOLUTION_CONFIG, ROLES, SPENDINGS, UNTRACKED_SPENT_FEES,
+};
+use crate::timelock::{is_timelocked, proposer_role, PendingAdminAction, MAX_ADMIN_ACTION_DELAY};
 use crate::ContractError;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult, Storage, Timestamp, Uint64,
+    ensure, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Timestamp, Uint128, Uint64,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
@@ -42,20 +58,27 @@ pub fn instantiate(
 
     let conf = msg.price_resolution_config;
 
-    let supply = deps.querier.query_supply(conf.quote_denom.clone())?;
-    // make sure the quote_denom has a non-zero supply
+    ensure_quote_denom_supply(deps.as_ref(), &conf.quote_denom)?;
+
+    PRICE_RESOLUTION_CONFIG.save(deps.storage, &conf)?;
+
+    track_all_denoms(deps, env, &PRICE_INFOS, &conf, msg.tracked_denoms)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+/// Make sure the quote denom has a non-zero supply
+fn ensure_quote_denom_supply(deps: Deps, quote_denom: &str) -> Result<(), ContractError> {
+    let supply = deps.querier.query_supply(quote_denom)?;
+
     ensure!(
         !supply.amount.is_zero(),
         ContractError::InvalidDenom {
-            denom: conf.quote_denom
+            denom: quote_denom.to_string()
         }
     );
 
-    PRICE_RESOLUTION_CONFIG.save(deps.storage, &conf)?;
-
-    track_all_denoms(deps, env, &conf, msg.tracked_denoms)?;
-
-    Ok(Response::new().add_attribute("action", "instantiate"))
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -64,56 +87,423 @@ pub fn execute(
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let admin_action_delay = ADMIN_ACTION_DELAY
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    ensure!(
+        !is_timelocked(&msg, admin_action_delay),
+        ContractError::AdminActionTimelocked {}
+    );
+
+    execute_action(deps, env, info, msg)
+}
+
+/// Execute the message without checking the admin action delay,
+/// used for the messages sent directly and the proposed admin actions that are due.
+/// Admin operations are recorded in the audit log.
+fn execute_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match audit_scope(&msg) {
+        Some(scope) => audited(
+            deps,
+            env.block.time,
+            Some(info.sender.clone()),
+            scope,
+            |deps| dispatch_action(deps, env, info, msg),
+        ),
+        None => dispatch_action(deps, env, info, msg),
+    }
+}
+
+/// Run the admin operation and append it to the audit log,
+/// along with the changes to the price profile in its scope.
+fn audited(
+    mut deps: DepsMut,
+    block_time: Timestamp,
+    sender: Option<Addr>,
+    scope: AuditScope,
+    operation: impl FnOnce(DepsMut) -> Result<Response, ContractError>,
+) -> Result<Response, ContractError> {
+    let previous = match scope {
+        AuditScope::PriceProfile {
+            price_profile,
+            denoms,
+        } => {
+            let price_profile = PriceProfile::new(price_profile);
+            let snapshot = PriceProfileSnapshot::load(deps.storage, &price_profile, denoms)?;
+            Some((price_profile, snapshot))
+        }
+        AuditScope::WholePriceProfile { price_profile } => {
+            let price_profile = PriceProfile::new(price_profile);
+            let snapshot = PriceProfileSnapshot::load_whole(deps.storage, &price_profile)?;
+            Some((price_profile, snapshot))
+        }
+        AuditScope::Operation => None,
+    };
+
+    let res = operation(deps.branch())?;
+
+    let changes = match previous {
+        Some((price_profile, previous)) => {
+            let new = previous.reload(deps.storage, &price_profile)?;
+            previous.changes(new, price_profile.name())
+        }
+        None => vec![],
+    };
+    let action = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "action")
+        .map(|attr| attr.value.clone())
+        .unwrap_or_default();
+
+    append_audit_log(
+        &AUDIT_LOG,
+        deps.storage,
+        sender,
+        action,
+        block_time,
+        changes,
+    )?;
+
+    Ok(res)
+}
+
+fn dispatch_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::SetPriceResolutionParams {
+            price_profile,
             staleness_threshold,
             twap_duration,
-        } => set_price_resolution_config(deps, info, staleness_threshold, twap_duration),
-        ExecuteMsg::RemoveTrackedDenoms { target } => remove_tracked_denoms(deps, info, target),
-        ExecuteMsg::SetTrackedDenoms { tracked_denoms } => {
-            set_tracked_denoms(deps, env, info, tracked_denoms)
-        }
-        ExecuteMsg::TransferAdmin { address } => transfer_admin(deps, info, address),
-        ExecuteMsg::ClaimAdminTransfer {} => claim_admin_transfer(deps, info),
-        ExecuteMsg::RejectAdminTransfer {} => reject_admin_transfer(deps, info),
-        ExecuteMsg::CancelAdminTransfer {} => cancel_admin_transfer(deps, info),
-        ExecuteMsg::RevokeAdmin {} => revoke_admin(deps, info),
+            twap_kind,
+            max_price_change_per_refresh,
+            block_time_behind_last_update,
+            min_pool_liquidity,
+            spot_price_fallback,
+            max_price_age,
+        } => set_price_resolution_config(
+            deps,
+            info,
+            price_profile,
+            staleness_threshold,
+            twap_duration,
+            twap_kind,
+            max_price_change_per_refresh,
+            block_time_behind_last_update,
+            min_pool_liquidity,
+            spot_price_fallback,
+            max_price_age,
+        ),
+        ExecuteMsg::MigrateQuoteDenom {
+            price_profile,
+            quote_denom,
+            conversion_rate,
+            tracked_denoms,
+        } => migrate_quote_denom(
+            deps,
+            env,
+            info,
+            price_profile,
+            quote_denom,
+            conversion_rate,
+            tracked_denoms,
+        ),
+        ExecuteMsg::CreatePriceProfile {
+            name,
+            price_resolution_config,
+            tracked_denoms,
+        } => create_price_profile(
+            deps,
+            env,
+            info,
+            name,
+            price_resolution_config,
+            tracked_denoms,
+        ),
+        ExecuteMsg::RemovePriceProfile { name } => remove_price_profile(deps, info, name),
+        ExecuteMsg::RemoveTrackedDenoms {
+            price_profile,
+            target,
+        } => remove_tracked_denoms(deps, info, price_profile, target),
+        ExecuteMsg::SetTrackedDenoms {
+            price_profile,
+            tracked_denoms,
+        } => set_tracked_denoms(deps, env, info, price_profile, tracked_denoms),
+        ExecuteMsg::AutoTrackDenom {
+            price_profile,
+            denom,
+            max_hops,
+        } => auto_track_denom(deps, env, info, price_profile, denom, max_hops),
+        ExecuteMsg::SetFixedPrices {
+            price_profile,
+            prices,
+        } => set_fixed_prices(deps, env, info, price_profile, prices),
+        ExecuteMsg::SetDenomExponents { exponents } => set_denom_exponents(deps, info, exponents),
+        ExecuteMsg::SetDenomAliases { aliases } => set_denom_aliases(deps, info, aliases),
+        ExecuteMsg::RemoveDenomAliases { denoms } => remove_denom_aliases(deps, info, denoms),
+        ExecuteMsg::AcceptPrices {
+            price_profile,
+            denoms,
+        } => accept_prices(deps, env, info, price_profile, denoms),
+        ExecuteMsg::RefreshPrices {
+            price_profile,
+            denoms,
+            start_after,
+            limit,
+        } => refresh_prices(deps, env, price_profile, denoms, start_after, limit),
+        ExecuteMsg::Prune { start_after, limit } => prune(deps, env, start_after, limit),
+        ExecuteMsg::SetAdminActionDelay { delay } => set_admin_action_delay(deps, info, delay),
+        ExecuteMsg::ProposeAdminAction { action } => propose_admin_action(deps, env, info, *action),
+        ExecuteMsg::ExecuteAdminAction { id } => execute_admin_action(deps, env, info, id),
+        ExecuteMsg::CancelAdminAction { id } => cancel_admin_action(deps, info, id),
+        ExecuteMsg::Pause { mode, reason } => pause(deps, env, info, mode, reason),
+        ExecuteMsg::Unpause {} => unpause(deps, info),
+        ExecuteMsg::GrantRole { role, address } => grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => revoke_role(deps, info, role, address),
+        ExecuteMsg::TransferAdmin {
+            address,
+            expires_at,
+        } => transfer_admin(deps, env, info, address, expires_at),
+        ExecuteMsg::ClaimAdminTransfer {} => claim_admin_transfer(deps, env, info),
+        ExecuteMsg::RejectAdminTransfer {} => reject_admin_transfer(deps, env, info),
+        ExecuteMsg::CancelAdminTransfer {} => cancel_admin_transfer(deps, env, info),
+        ExecuteMsg::RevokeAdmin {} => revoke_admin(deps, env, info),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn set_price_resolution_config(
     deps: DepsMut,
     info: MessageInfo,
-    staleness_threshold: Uint64,
-    twap_duration: Uint64,
+    price_profile: Option<String>,
+    staleness_threshold: Option<Uint64>,
+    twap_duration: Option<Uint64>,
+    twap_kind: Option<TwapKind>,
+    max_price_change_per_refresh: Option<OptionalUpdate<Decimal>>,
+    block_time_behind_last_update: Option<BlockTimeBehindLastUpdate>,
+    min_pool_liquidity: Option<OptionalUpdate<Uint128>>,
+    spot_price_fallback: Option<OptionalUpdate<SpotPriceFallback>>,
+    max_price_age: Option<OptionalUpdate<Uint64>>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::PriceManager)?;
+
+    let price_profile = PriceProfile::new(price_profile);
+    let conf = price_profile.load_config(deps.storage)?;
+
+    price_profile.save_config(
+        deps.storage,
+        &PriceResolutionConfig {
+            staleness_threshold: staleness_threshold.unwrap_or(conf.staleness_threshold),
+            twap_duration: twap_duration.unwrap_or(conf.twap_duration),
+            twap_kind: twap_kind.unwrap_or(conf.twap_kind),
+            max_price_change_per_refresh: OptionalUpdate::apply(
+                max_price_change_per_refresh,
+                conf.max_price_change_per_refresh,
+            ),
+            block_time_behind_last_update: block_time_behind_last_update
+                .unwrap_or(conf.block_time_behind_last_update),
+            min_pool_liquidity: OptionalUpdate::apply(min_pool_liquidity, conf.min_pool_liquidity),
+            spot_price_fallback: OptionalUpdate::apply(
+                spot_price_fallback,
+                conf.spot_price_fallback,
+            ),
+            max_price_age: OptionalUpdate::apply(max_price_age, conf.max_price_age),
+            ..conf
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "set_price_resolution_config"))
+}
+
+fn migrate_quote_denom(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_profile: Option<String>,
+    quote_denom: String,
+    conversion_rate: Decimal,
+    tracked_denoms: Vec<TrackedDenom>,
 ) -> Result<Response, ContractError> {
     authorize_admin(deps.storage, &info.sender)?;
 
-    PRICE_RESOLUTION_CONFIG.update(deps.storage, |conf| -> Result<_, ContractError> {
-        Ok(PriceResolutionConfig {
-            quote_denom: conf.quote_denom,
-            staleness_threshold,
-            twap_duration,
+    ensure!(
+        !conversion_rate.is_zero(),
+        ContractError::ZeroConversionRate {}
+    );
+    ensure_quote_denom_supply(deps.as_ref(), &quote_denom)?;
+
+    let price_profile = PriceProfile::new(price_profile);
+    let price_infos = price_profile.price_infos();
+
+    let mut conf = price_profile.load_config(deps.storage)?;
+    ensure!(
+        conf.quote_denom != quote_denom,
+        ContractError::QuoteDenomUnchanged {
+            quote_denom: quote_denom.clone()
+        }
+    );
+
+    let prev_quote_denom = std::mem::replace(&mut conf.quote_denom, quote_denom.clone());
+    price_profile.save_config(deps.storage, &conf)?;
+
+    // keep tracking the current denoms unless overridden, the new quote denom no longer needs tracking
+    let overridden_denoms = tracked_denoms
+        .iter()
+        .map(|tracked_denom| tracked_denom.denom.as_str())
+        .chain([quote_denom.as_str(), prev_quote_denom.as_str()])
+        .collect::<HashSet<_>>();
+
+    let retained_tracked_denoms = price_infos
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(denom, _)| {
+                !overridden_denoms.contains(denom.as_str())
+            })
         })
-    })?;
+        .map(|item| -> Result<TrackedDenom, ContractError> {
+            let (denom, info) = item?;
+            let mut tracked_denom = info.tracked_denom(denom.as_str());
+
+            // fixed prices are quoted in the current quote denom
+            if let Some(fixed_price) = tracked_denom.fixed_price {
+                tracked_denom.fixed_price = Some(
+                    fixed_price
+                        .checked_mul(conversion_rate)
+                        .map_err(StdError::from)?,
+                );
+            }
 
-    Ok(Response::new().add_attribute("action", "set_price_resolution_config"))
+            Ok(tracked_denom)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // swap routes of the retained denoms priced by twap end with the previous quote denom
+    let denoms_missing_swap_routes = retained_tracked_denoms
+        .iter()
+        .filter(|tracked_denom| !tracked_denom.swap_routes.is_empty())
+        .map(|tracked_denom| tracked_denom.denom.clone())
+        .collect::<Vec<_>>();
+    ensure!(
+        denoms_missing_swap_routes.is_empty(),
+        ContractError::MissingSwapRoutesToQuoteDenom {
+            quote_denom,
+            denoms: denoms_missing_swap_routes,
+        }
+    );
+
+    // keep counting the spending of the previous quote denom at the conversion rate unless overridden
+    let prev_quote_denom_tracking = tracked_denoms
+        .iter()
+        .all(|tracked_denom| tracked_denom.denom != prev_quote_denom)
+        .then(|| TrackedDenom::fixed(prev_quote_denom, conversion_rate));
+
+    price_infos.clear(deps.storage);
+    track_all_denoms(
+        deps.branch(),
+        env,
+        &price_infos,
+        &conf,
+        retained_tracked_denoms
+            .into_iter()
+            .chain(prev_quote_denom_tracking)
+            .chain(tracked_denoms)
+            .collect(),
+    )?;
+
+    // spendings valued by the price profile are rescaled to the new quote denom when they are loaded next,
+    // since rescaling all of them here grows with the number of accounts
+    price_profile.save_quote_denom_migration(deps.storage, conversion_rate)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate_quote_denom")
+        .add_attribute("quote_denom", quote_denom)
+        .add_attribute("conversion_rate", conversion_rate.to_string()))
 }
 
-fn remove_tracked_denoms(
+fn create_price_profile(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    conf: PriceResolutionConfig,
+    tracked_denoms: Vec<TrackedDenom>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::PriceManager)?;
+
+    validate_price_profile_name(&name)?;
+    ensure!(
+        !PRICE_PROFILES.has(deps.storage, &name),
+        ContractError::PriceProfileAlreadyExists { name }
+    );
+
+    ensure_quote_denom_supply(deps.as_ref(), &conf.quote_denom)?;
+
+    let price_profile = PriceProfile::new(Some(name.clone()));
+    price_profile.save_config(deps.storage, &conf)?;
+
+    track_all_denoms(
+        deps,
+        env,
+        &price_profile.price_infos(),
+        &conf,
+        tracked_denoms,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_price_profile")
+        .add_attribute("name", name))
+}
+
+fn remove_price_profile(
     deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::PriceManager)?;
 
+    let price_profile = PriceProfile::new(Some(name.clone()));
+
+    // make sure the profile exists
+    price_profile.load_config(deps.storage)?;
+
+    // authenticators selecting the removed profile fail to resolve prices until it is created again
+    price_profile.clear(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_price_profile")
+        .add_attribute("name", name))
+}
+
+fn remove_tracked_denoms(
+    deps: DepsMut,
     info: MessageInfo,
+    price_profile: Option<String>,
     target: DenomRemovalTarget,
 ) -> Result<Response, ContractError> {
-    authorize_admin(deps.storage, &info.sender)?;
+    authorize_role(deps.storage, &info.sender, Role::DenomManager)?;
+
+    let price_profile = PriceProfile::new(price_profile);
+    let price_infos = price_profile.price_infos();
+
+    // make sure the profile exists
+    price_profile.load_config(deps.storage)?;
 
     match target {
-        DenomRemovalTarget::All => PRICE_INFOS.clear(deps.storage),
+        DenomRemovalTarget::All => price_infos.clear(deps.storage),
         DenomRemovalTarget::Partial(denoms) => {
             for denom in denoms {
-                PRICE_INFOS.remove(deps.storage, &denom);
+                price_infos.remove(deps.storage, &denom);
             }
         }
     }
@@ -125,20 +515,368 @@ fn set_tracked_denoms(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    price_profile: Option<String>,
     tracked_denoms: Vec<TrackedDenom>,
 ) -> Result<Response, ContractError> {
-    authorize_admin(deps.storage, &info.sender)?;
+    authorize_role(deps.storage, &info.sender, Role::DenomManager)?;
 
-    let conf = PRICE_RESOLUTION_CONFIG.load(deps.storage)?;
+    let price_profile = PriceProfile::new(price_profile);
+    let conf = price_profile.load_config(deps.storage)?;
 
-    track_all_denoms(deps, env, &conf, tracked_denoms)?;
+    track_all_denoms(
+        deps,
+        env,
+        &price_profile.price_infos(),
+        &conf,
+        tracked_denoms,
+    )?;
 
     Ok(Response::new().add_attribute("action", "set_tracked_denoms"))
 }
 
+fn auto_track_denom(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_profile: Option<String>,
+    denom: String,
+    max_hops: Option<u32>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::DenomManager)?;
+
+    let price_profile = PriceProfile::new(price_profile);
+    let conf = price_profile.load_config(deps.storage)?;
+
+    let swap_routes = discover_swap_routes(
+        deps.as_ref(),
+        &conf,
+        env.block.time,
+        &denom,
+        max_hops.unwrap_or(MAX_DISCOVERED_SWAP_ROUTES_HOPS),
+    )?;
+    let pool_ids = swap_routes
+        .iter()
+        .map(|route| route.pool_id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    track_all_denoms(
+        deps,
+        env,
+        &price_profile.price_infos(),
+        &conf,
+        vec![TrackedDenom::twap(denom.clone(), swap_routes)],
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "auto_track_denom")
+        .add_attribute("denom", denom)
+        .add_attribute("pool_ids", pool_ids))
+}
+
+fn set_fixed_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_profile: Option<String>,
+    prices: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::PriceManager)?;
+
+    let price_profile = PriceProfile::new(price_profile);
+    let conf = price_profile.load_config(deps.storage)?;
+
+    let tracked_denoms = prices
+        .into_iter()
+        .map(|(denom, price)| TrackedDenom::fixed(denom, price))
+        .collect();
+
+    track_all_denoms(
+        deps,
+        env,
+        &price_profile.price_infos(),
+        &conf,
+        tracked_denoms,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "set_fixed_prices"))
+}
+
+fn set_denom_exponents(
+    deps: DepsMut,
+    info: MessageInfo,
+    exponents: Vec<(String, u32)>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::DenomManager)?;
+
+    for (denom, exponent) in exponents {
+        ensure!(
+            exponent <= MAX_DENOM_EXPONENT,
+            ContractError::InvalidDenomExponent { denom, exponent }
+        );
+
+        DENOM_EXPONENTS.save(deps.storage, denom.as_str(), &exponent)?;
+    }
+
+    Ok(Response::new().add_attribute("action", "set_denom_exponents"))
+}
+
+fn set_denom_aliases(
+    deps: DepsMut,
+    info: MessageInfo,
+    aliases: Vec<(String, DenomAlias)>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::DenomManager)?;
+
+    // validated one by one so that aliases in the same batch can't be chained either
+    for (denom, alias) in aliases {
+        validate_denom_alias(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            deps.storage,
+            &denom,
+            &alias,
+        )?;
+        save_denom_alias(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            deps.storage,
+            &denom,
+            &alias,
+        )?;
+    }
+
+    Ok(Response::new().add_attribute("action", "set_denom_aliases"))
+}
+
+fn remove_denom_aliases(
+    deps: DepsMut,
+    info: MessageInfo,
+    denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::DenomManager)?;
+
+    for denom in denoms {
+        remove_denom_alias(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            deps.storage,
+            &denom,
+        )?;
+    }
+
+    Ok(Response::new().add_attribute("action", "remove_denom_aliases"))
+}
+
+fn accept_prices(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    price_profile: Option<String>,
+    denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::PriceManager)?;
+
+    let price_profile = PriceProfile::new(price_profile);
+    let price_infos = price_profile.price_infos();
+    let conf = price_profile.load_config(deps.storage)?;
+
+    for denom in denoms {
+        let Some(price_info) = price_infos.may_load(deps.storage, denom.as_str())? else {
+            return Err(ContractError::InvalidDenom { denom });
+        };
+
+        // re-tracking the denom fetches and caches the current price without comparing it to the cached one
+        track_denom(
+            &price_infos,
+            deps.branch(),
+            &conf,
+            env.block.time,
+            price_info.tracked_denom(denom.as_str()),
+        )?;
+    }
+
+    Ok(Response::new().add_attribute("action", "accept_prices"))
+}
+
+fn refresh_prices(
+    mut deps: DepsMut,
+    env: Env,
+    price_profile: Option<String>,
+    denoms: Option<Vec<String>>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let price_profile = PriceProfile::new(price_profile);
+    let price_infos = price_profile.price_infos();
+    let conf = price_profile.load_config(deps.storage)?;
+
+    let (denoms, next_start_after) = match denoms {
+        Some(denoms) => (denoms, None),
+        None => {
+            let min = start_after.as_deref().map(Bound::exclusive);
+            let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+            let denoms = price_infos
+                .keys(deps.storage, min, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+            let next_start_after = if denoms.len() == limit {
+                denoms.last().cloned()
+            } else {
+                None
+            };
+
+            (denoms, next_start_after)
+        }
+    };
+
+    let mut results = vec![];
+
+    for denom in denoms {
+        let Some(cached_price_info) = price_infos.may_load(deps.storage, denom.as_str())? else {
+            results.push((denom, PriceRefreshStatus::NotTracked {}));
+            continue;
+        };
+
+        // price store is only updated on success, so failure of one denom doesn't affect the others
+        let status = match get_and_cache_price(
+            &price_infos,
+            &DENOM_ALIASES,
+            deps.branch(),
+            &conf,
+            env.block.time,
+            denom.as_str(),
+        ) {
+            Ok(Some(price_info))
+                if price_info.last_updated_time != cached_price_info.last_updated_time =>
+            {
+                PriceRefreshStatus::Refreshed {
+                    price: price_info.price,
+                }
+            }
+            // the cached price is kept within max price age even though refreshing it failed
+            Ok(Some(price_info))
+                if !price_info.fixed
+                    && price_info
+                        .has_expired(env.block.time, conf.staleness_threshold)
+                        .unwrap_or(false) =>
+            {
+                PriceRefreshStatus::Stale {
+                    price: price_info.price,
+                }
+            }
+            Ok(Some(price_info)) => PriceRefreshStatus::Fresh {
+                price: price_info.price,
+            },
+            Ok(None) => PriceRefreshStatus::NotTracked {},
+            Err(err) => PriceRefreshStatus::Failed {
+                error: err.to_string(),
+            },
+        };
+
+        results.push((denom, status));
+    }
+
+    let count =
+        |f: fn(&PriceRefreshStatus) -> bool| results.iter().filter(|(_, status)| f(status)).count();
+    let refreshed = count(|status| matches!(status, PriceRefreshStatus::Refreshed { .. }));
+    let stale = count(|status| matches!(status, PriceRefreshStatus::Stale { .. }));
+    let failed = count(|status| matches!(status, PriceRefreshStatus::Failed { .. }));
+
+    Ok(Response::new()
+        .add_attribute("action", "refresh_prices")
+        .add_attribute("refreshed", refreshed.to_string())
+        .add_attribute("stale", stale.to_string())
+        .add_attribute("failed", failed.to_string())
+        .set_data(to_json_binary(&RefreshPricesResponse {
+            results,
+            next_start_after,
+        })?))
+}
+
+fn prune(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let start_after = start_after
+        .map(|(account, authenticator_id)| {
+            Ok::<_, ContractError>((deps.api.addr_validate(&account)?, authenticator_id))
+        })
+        .transpose()?;
+    let min = start_after
+        .as_ref()
+        .map(|(account, authenticator_id)| Bound::exclusive((account, authenticator_id.as_str())));
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    // pre-exec balances and untracked spent fees can be left without a spending by the removals
+    // before the spend limit state was cleared on removal, so the keys of all of them are checked.
+    // The first `limit` keys of the union are among the first `limit` keys of each map.
+    let mut keys = BTreeSet::new();
+    keys.extend(
+        SPENDINGS
+            .keys(deps.storage, min.clone(), None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    );
+    keys.extend(
+        PRE_EXEC_BALANCES
+            .keys(deps.storage, min.clone(), None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    );
+    keys.extend(
+        UNTRACKED_SPENT_FEES
+            .keys(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    );
+    let keys = keys.into_iter().take(limit).collect::<Vec<_>>();
+
+    let mut pruned = vec![];
+
+    for (account, authenticator_id) in keys.iter() {
+        // keep the spend limit if the authenticator can't be queried, pruning it would reset the spending
+        let Ok(params) = may_get_spend_limit_params(deps.as_ref(), account, authenticator_id)
+        else {
+            continue;
+        };
+
+        let is_prunable = match params {
+            None => true,
+            Some(params) => params
+                .time_limit
+                .is_some_and(|time_limit| time_limit.end < env.block.time),
+        };
+
+        if is_prunable {
+            authenticator::clear_spend_limit_state(deps.storage, (account, authenticator_id));
+            pruned.push((account.to_string(), authenticator_id.clone()));
+        }
+    }
+
+    let next_start_after = if keys.len() == limit {
+        keys.last()
+            .map(|(account, authenticator_id)| (account.to_string(), authenticator_id.clone()))
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "prune")
+        .add_attribute("pruned", pruned.len().to_string())
+        .set_data(to_json_binary(&PruneResponse {
+            pruned,
+            next_start_after,
+        })?))
+}
+
 fn track_all_denoms(
     mut deps: DepsMut,
     env: Env,
+    price_info_store: &PriceInfoStore,
     conf: &PriceResolutionConfig,
     tracked_denoms: Vec<TrackedDenom>,
 ) -> Result<(), ContractError> {
@@ -146,82 +884,306 @@ fn track_all_denoms(
     denoms.insert(conf.quote_denom.to_owned());
 
     for tracked_denom in tracked_denoms {
-        let denom = tracked_denom.denom;
+        let denom = tracked_denom.denom.as_str();
 
         // check for duplicated denoms
-        if denoms.contains(denom.as_str()) {
-            return Err(ContractError::DuplicatedDenom { denom });
+        if denoms.contains(denom) {
+            return Err(ContractError::DuplicatedDenom {
+                denom: denom.to_string(),
+            });
         } else {
             denoms.insert(denom.to_owned());
         }
 
-        let swap_routes = tracked_denom.swap_routes;
         track_denom(
-            &PRICE_INFOS,
+            price_info_store,
             deps.branch(),
             conf,
-            denom.as_str(),
             env.block.time,
-            swap_routes,
+            tracked_denom,
         )?;
     }
 
     Ok(())
 }
 
-fn transfer_admin(
+fn set_admin_action_delay(
     deps: DepsMut,
     info: MessageInfo,
-    address: String,
+    delay: Uint64,
 ) -> Result<Response, ContractError> {
-    let candidate = deps.api.addr_validate(&address)?;
+    authorize_admin(deps.storage, &info.sender)?;
+    ensure!(
+        delay <= MAX_ADMIN_ACTION_DELAY,
+        ContractError::AdminActionDelayTooLong {
+            delay,
+            max_delay: MAX_ADMIN_ACTION_DELAY,
+        }
+    );
 
-    update_admin(deps.storage, |admin| {
-        admin.authorized_transfer_admin(&info.sender, candidate)
-    })?;
+    ADMIN_ACTION_DELAY.save(deps.storage, &delay)?;
 
-    Ok(Response::new().add_attribute("action", "transfer_admin"))
+    Ok(Response::new()
+        .add_attribute("action", "set_admin_action_delay")
+        .add_attribute("delay", delay))
 }
 
-fn claim_admin_transfer(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    update_admin(deps.storage, |admin| {
-        admin.authorized_claim_admin_transfer(&info.sender)
-    })?;
+fn propose_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let delay = ADMIN_ACTION_DELAY
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    ensure!(
+        is_timelocked(&action, delay),
+        ContractError::AdminActionNotTimelocked {}
+    );
 
-    Ok(Response::new().add_attribute("action", "claim_admin"))
-}
+    match proposer_role(&action) {
+        Some(role) => authorize_role(deps.storage, &info.sender, role)?,
+        None => authorize_admin(deps.storage, &info.sender)?,
+    }
 
-fn reject_admin_transfer(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    update_admin(deps.storage, |admin| {
-        admin.authorized_reject_admin_transfer(&info.sender)
-    })?;
+    let id = NEXT_ADMIN_ACTION_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_ADMIN_ACTION_ID.save(deps.storage, &(id + 1))?;
 
-    Ok(Response::new().add_attribute("action", "reject_admin"))
+    let executable_at = Timestamp::from_nanos(
+        Uint64::from(env.block.time.nanos())
+            .checked_add(delay)
+            .map_err(StdError::from)?
+            .u64(),
+    );
+    PENDING_ADMIN_ACTIONS.save(
+        deps.storage,
+        id,
+        &PendingAdminAction {
+            id,
+            action,
+            proposer: info.sender,
+            proposed_at: env.block.time,
+            executable_at,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_admin_action")
+        .add_attribute("id", id.to_string())
+        .add_attribute("executable_at", executable_at.to_string()))
 }
 
-fn cancel_admin_transfer(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    update_admin(deps.storage, |admin| {
-        admin.authorized_cancel_admin_transfer(&info.sender)
-    })?;
+fn execute_admin_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let pending_action = load_pending_admin_action(deps.storage, &info.sender, id)?;
+
+    ensure!(
+        env.block.time >= pending_action.executable_at,
+        ContractError::AdminActionNotExecutable {
+            id,
+            executable_at: pending_action.executable_at
+        }
+    );
+
+    PENDING_ADMIN_ACTIONS.remove(deps.storage, id);
+
+    // executed on behalf of the proposer, so that revoking its role also revokes its proposals
+    let proposer_info = MessageInfo {
+        sender: pending_action.proposer,
+        funds: vec![],
+    };
+    let res = execute_action(deps, env, proposer_info, pending_action.action)?;
+
+    Ok(res.add_attribute("admin_action_id", id.to_string()))
+}
+
+fn cancel_admin_action(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    load_pending_admin_action(deps.storage, &info.sender, id)?;
+
+    PENDING_ADMIN_ACTIONS.remove(deps.storage, id);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_admin_action")
+        .add_attribute("id", id.to_string()))
+}
+
+/// Load the pending admin action, authorizing the sender as its proposer or the admin
+fn load_pending_admin_action(
+    store: &mut dyn Storage,
+    sender: &Addr,
+    id: u64,
+) -> Result<PendingAdminAction, ContractError> {
+    let pending_action = PENDING_ADMIN_ACTIONS
+        .may_load(store, id)?
+        .ok_or(ContractError::AdminActionNotFound { id })?;
+
+    if pending_action.proposer != sender {
+        authorize_admin(store, sender)?;
+    }
+
+    Ok(pending_action)
+}
+
+fn pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mode: PauseMode,
+    reason: String,
+) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::Guardian)?;
+
+    PAUSE_STATE.save(
+        deps.storage,
+        &PauseState {
+            mode,
+            reason: reason.clone(),
+            paused_by: info.sender,
+            paused_at: env.block.time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("mode", mode.as_str())
+        .add_attribute("reason", reason))
+}
+
+fn unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    authorize_role(deps.storage, &info.sender, Role::Guardian)?;
+
+    PAUSE_STATE.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
+fn grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    authorize_admin(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, (role.as_str(), &address), &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address))
+}
+
+fn revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    authorize_admin(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.remove(deps.storage, (role.as_str(), &address));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address))
+}
+
+fn transfer_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expires_at: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let candidate = deps.api.addr_validate(&address)?;
+
+    if let Some(expires_at) = expires_at {
+        ensure!(
+            expires_at > env.block.time,
+            ContractError::AdminTransferExpired { expires_at }
+        );
+    }
+
+    update_admin(deps.storage, env.block.time, |admin| {
+        admin.authorized_transfer_admin(&info.sender, candidate, expires_at)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "transfer_admin"))
+}
+
+fn claim_admin_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    update_admin(deps.storage, env.block.time, |admin| {
+        admin.authorized_claim_admin_transfer(&info.sender, env.block.time)
+    })?;
+
+    // roles are granted on behalf of the previous admin
+    ROLES.clear(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "claim_admin"))
+}
+
+fn reject_admin_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    update_admin(deps.storage, env.block.time, |admin| {
+        admin.authorized_reject_admin_transfer(&info.sender)
+    })?;
+
+    Ok(Response::new().add_attribute("action", "reject_admin"))
+}
+
+fn cancel_admin_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    update_admin(deps.storage, env.block.time, |admin| {
+        admin.authorized_cancel_admin_transfer(&info.sender)
+    })?;
 
     Ok(Response::new().add_attribute("action", "cancel_admin"))
 }
 
-fn revoke_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    update_admin(deps.storage, |admin| {
+fn revoke_admin(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    update_admin(deps.storage, env.block.time, |admin| {
         admin.authorized_revoke_admin(&info.sender)
     })?;
 
+    // roles are granted on behalf of the admin, so they are revoked along with it
+    ROLES.clear(deps.storage);
+
     Ok(Response::new().add_attribute("action", "revoke_admin"))
 }
 
+/// Update the admin with the action, expired transfer offers are reverted along the way
 fn update_admin(
     store: &mut dyn Storage,
+    time: Timestamp,
     action: impl FnOnce(Admin) -> Result<Admin, ContractError>,
 ) -> Result<(), ContractError> {
     let admin = ADMIN.may_load(store)?.unwrap_or(Admin::None);
 
-    ADMIN.save(store, &action(admin)?)?;
+    ADMIN.save(store, &action(admin)?.settle_expired_transfer(time))?;
 
     Ok(())
 }
@@ -231,6 +1193,15 @@ fn authorize_admin(store: &mut dyn Storage, sender: &Addr) -> Result<(), Contrac
     admin.authorize_admin(sender)
 }
 
+/// Authorize the sender if it's granted the role or it's the admin, who holds all the roles.
+fn authorize_role(store: &mut dyn Storage, sender: &Addr, role: Role) -> Result<(), ContractError> {
+    if ROLES.has(store, (role.as_str(), sender)) {
+        return Ok(());
+    }
+
+    authorize_admin(store, sender)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
@@ -251,17 +1222,128 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractE
         SudoMsg::ConfirmExecution(confirm_execution_request) => {
             authenticator::confirm_execution(deps, env, confirm_execution_request)
         }
+        SudoMsg::SetTrackedDenoms {
+            price_profile,
+            tracked_denoms,
+        } => audited(
+            deps,
+            env.block.time,
+            None,
+            AuditScope::PriceProfile {
+                price_profile: price_profile.clone(),
+                denoms: tracked_denoms
+                    .iter()
+                    .map(|tracked_denom| tracked_denom.denom.clone())
+                    .collect(),
+            },
+            |deps| sudo_set_tracked_denoms(deps, env, price_profile, tracked_denoms),
+        ),
+        SudoMsg::SetPriceResolutionConfig {
+            price_profile,
+            price_resolution_config,
+        } => audited(
+            deps,
+            env.block.time,
+            None,
+            AuditScope::PriceProfile {
+                price_profile: price_profile.clone(),
+                denoms: vec![],
+            },
+            |deps| sudo_set_price_resolution_config(deps, price_profile, price_resolution_config),
+        ),
+        SudoMsg::SetAdmin { address } => {
+            audited(deps, env.block.time, None, AuditScope::Operation, |deps| {
+                sudo_set_admin(deps, address)
+            })
+        }
     }
 }
 
+fn sudo_set_tracked_denoms(
+    deps: DepsMut,
+    env: Env,
+    price_profile: Option<String>,
+    tracked_denoms: Vec<TrackedDenom>,
+) -> Result<Response, ContractError> {
+    let price_profile = PriceProfile::new(price_profile);
+    let conf = price_profile.load_config(deps.storage)?;
+
+    track_all_denoms(
+        deps,
+        env,
+        &price_profile.price_infos(),
+        &conf,
+        tracked_denoms,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_tracked_denoms")
+        .add_attribute("authority", "governance"))
+}
+
+fn sudo_set_price_resolution_config(
+    deps: DepsMut,
+    price_profile: Option<String>,
+    price_resolution_config: PriceResolutionConfig,
+) -> Result<Response, ContractError> {
+    let price_profile = PriceProfile::new(price_profile);
+    let conf = price_profile.load_config(deps.storage)?;
+
+    ensure!(
+        conf.quote_denom == price_resolution_config.quote_denom,
+        ContractError::QuoteDenomChanged {
+            quote_denom: conf.quote_denom
+        }
+    );
+
+    price_profile.save_config(deps.storage, &price_resolution_config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_price_resolution_config")
+        .add_attribute("authority", "governance"))
+}
+
+fn sudo_set_admin(deps: DepsMut, address: Option<String>) -> Result<Response, ContractError> {
+    let admin = match &address {
+        Some(address) => Admin::new(deps.api.addr_validate(address)?),
+        None => Admin::None,
+    };
+
+    ADMIN.save(deps.storage, &admin)?;
+
+    // roles are granted on behalf of the previous admin
+    ROLES.clear(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "set_admin")
+        .add_attribute("authority", "governance")
+        .add_attribute("admin", address.unwrap_or_default()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::PriceResolutionConfig {} => to_json_binary(&PriceResolutionConfigResponse {
-            price_resolution_config: PRICE_RESOLUTION_CONFIG.load(deps.storage)?,
-        }),
-        QueryMsg::TrackedDenoms { start_after, limit } => {
-            to_json_binary(&query_tracked_denoms(deps, start_after, limit)?)
+        QueryMsg::PriceResolutionConfig { price_profile } => {
+            to_json_binary(&PriceResolutionConfigResponse {
+                price_resolution_config: PriceProfile::new(price_profile)
+                    .load_config(deps.storage)?,
+            })
+        }
+        QueryMsg::TrackedDenoms {
+            price_profile,
+            start_after,
+            limit,
+        } => to_json_binary(&query_tracked_denoms(
+            deps,
+            price_profile,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::DenomAliases { start_after, limit } => {
+            to_json_binary(&query_denom_aliases(deps, start_after, limit)?)
+        }
+        QueryMsg::PriceProfiles { start_after, limit } => {
+            to_json_binary(&query_price_profiles(deps, start_after, limit)?)
         }
         QueryMsg::Spending {
             account,
@@ -279,22 +1361,110 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             let account = deps.api.addr_validate(&account)?;
             to_json_binary(&query_spendings_by_account(deps, account, env.block.time)?)
         }
+        QueryMsg::DenomExponent { denom } => to_json_binary(&DenomExponentResponse {
+            exponent: get_denom_exponent(deps, &DENOM_EXPONENTS, &denom)?,
+        }),
         QueryMsg::Admin {} => to_json_binary(&AdminResponse {
             admin: ADMIN
                 .may_load(deps.storage)?
                 .and_then(|a| a.admin_once())
                 .map(|a| a.to_string()),
         }),
-        QueryMsg::AdminCandidate {} => to_json_binary(&AdminCandidateResponse {
-            candidate: ADMIN
+        QueryMsg::AdminCandidate {} => {
+            let admin = ADMIN
                 .may_load(deps.storage)?
-                .and_then(|a| a.candidate_once())
-                .map(|a| a.to_string()),
+                .map(|a| a.settle_expired_transfer(env.block.time));
+            let expires_at = match &admin {
+                Some(Admin::Transferring { expires_at, .. }) => *expires_at,
+                _ => None,
+            };
+
+            to_json_binary(&AdminCandidateResponse {
+                candidate: admin
+                    .and_then(|a| a.candidate_once())
+                    .map(|a| a.to_string()),
+                expires_at,
+            })
+        }
+        QueryMsg::AdminActionDelay {} => to_json_binary(&AdminActionDelayResponse {
+            delay: ADMIN_ACTION_DELAY
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::PendingAdminActions { start_after, limit } => {
+            to_json_binary(&query_pending_admin_actions(deps, start_after, limit)?)
+        }
+        QueryMsg::PauseState {} => to_json_binary(&PauseStateResponse {
+            pause_state: PAUSE_STATE.may_load(deps.storage)?,
         }),
+        QueryMsg::AuditLog { start_after, limit } => {
+            to_json_binary(&query_audit_log(deps, start_after, limit)?)
+        }
+        QueryMsg::RoleHolders {
+            role,
+            start_after,
+            limit,
+        } => to_json_binary(&query_role_holders(deps, role, start_after, limit)?),
     }
     .map_err(ContractError::from)
 }
 
+pub fn query_pending_admin_actions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<PendingAdminActionsResponse, ContractError> {
+    let min = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    let actions = PENDING_ADMIN_ACTIONS
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, action)| action))
+        .collect::<StdResult<_>>()?;
+
+    Ok(PendingAdminActionsResponse { actions })
+}
+
+pub fn query_audit_log(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<AuditLogResponse, ContractError> {
+    let min = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    let entries = AUDIT_LOG
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<_>>()?;
+
+    Ok(AuditLogResponse { entries })
+}
+
+pub fn query_role_holders(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<RoleHoldersResponse, ContractError> {
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    let holders = ROLES
+        .prefix(role.as_str())
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|holder| holder.map(|holder| holder.to_string()))
+        .collect::<StdResult<_>>()?;
+
+    Ok(RoleHoldersResponse { holders })
+}
+
 pub fn query_spending(
     deps: Deps,
     account: Addr,
@@ -302,18 +1472,26 @@ pub fn query_spending(
     at: Timestamp,
 ) -> Result<SpendingResponse, ContractError> {
     match SPENDINGS.may_load(deps.storage, (&account, authenticator_id.as_str()))? {
-        Some(spending) => Ok(SpendingResponse {
-            spending: updated_spending(
+        Some(mut spending) => {
+            let price_profile = PriceProfile::new(spending.price_profile.clone());
+            price_profile.migrate_spending(deps.storage, &mut spending)?;
+            let conf = price_profile.load_config(deps.storage)?;
+            let quote_denom_exponent =
+                get_denom_exponent(deps, &DENOM_EXPONENTS, &conf.quote_denom)?;
+            let spending = updated_spending(
                 deps,
-                &PRICE_INFOS,
+                &price_profile.price_infos(),
+                &DENOM_ALIASES,
                 &UNTRACKED_SPENT_FEES,
-                &PRICE_RESOLUTION_CONFIG.load(deps.storage)?,
+                &conf,
                 &account,
                 &authenticator_id,
                 at,
                 spending,
-            )?,
-        }),
+            )?;
+
+            Ok(spending_response(spending, quote_denom_exponent)?)
+        }
         None => Err(SpendLimitError::SpendLimitNotFound {
             address: account,
             authenticator_id,
@@ -324,25 +1502,76 @@ pub fn query_spending(
 
 pub fn query_tracked_denoms(
     deps: Deps,
+    price_profile: Option<String>,
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> Result<TrackedDenomsResponse, ContractError> {
     let min = start_after.as_ref().map(|s| Bound::exclusive(s.as_str()));
     let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
 
-    let tracked_denoms = PRICE_INFOS
+    let price_profile = PriceProfile::new(price_profile);
+
+    // make sure the profile exists
+    price_profile.load_config(deps.storage)?;
+
+    let tracked_denoms: Vec<TrackedDenom> = price_profile
+        .price_infos()
         .range(deps.storage, min, None, Order::Ascending)
         .take(limit)
         .map(|item| -> StdResult<TrackedDenom> {
             let (denom, info) = item?;
-            Ok(TrackedDenom {
-                denom: denom.to_string(),
-                swap_routes: info.swap_routes,
-            })
+            Ok(info.tracked_denom(denom.as_str()))
         })
         .collect::<StdResult<_>>()?;
 
-    Ok(TrackedDenomsResponse { tracked_denoms })
+    // only the aliases of the denoms in this page are looked up
+    let mut aliases = vec![];
+    for tracked_denom in tracked_denoms.iter() {
+        aliases.extend(aliases_of(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            deps.storage,
+            &tracked_denom.denom,
+        )?);
+    }
+    aliases.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(TrackedDenomsResponse {
+        tracked_denoms,
+        aliases,
+    })
+}
+
+pub fn query_denom_aliases(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<DenomAliasesResponse, ContractError> {
+    let min = start_after.as_ref().map(|s| Bound::exclusive(s.as_str()));
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    let aliases = DENOM_ALIASES
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    Ok(DenomAliasesResponse { aliases })
+}
+
+pub fn query_price_profiles(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<PriceProfilesResponse, ContractError> {
+    let min = start_after.as_ref().map(|s| Bound::exclusive(s.as_str()));
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+
+    let price_profiles = PRICE_PROFILES
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    Ok(PriceProfilesResponse { price_profiles })
 }
 
 pub fn query_spendings_by_account(
@@ -350,26 +1579,65 @@ pub fn query_spendings_by_account(
     account: Addr,
     at: Timestamp,
 ) -> Result<SpendingsByAccountResponse, ContractError> {
-    let spendings = SPENDINGS
+    let mut spendings = vec![];
+    let mut values_spent_in_period_display = vec![];
+
+    for item in SPENDINGS
         .prefix(&account)
         .range(deps.storage, None, None, Order::Ascending)
-        .map(|item| {
-            let (authenticator_id, spending) = item?;
-            let conf = PRICE_RESOLUTION_CONFIG.load(deps.storage)?;
-            let spending = updated_spending(
-                deps,
-                &PRICE_INFOS,
-                &UNTRACKED_SPENT_FEES,
-                &conf,
-                &account,
-                &authenticator_id,
-                at,
-                spending,
-            )?;
-            Ok((authenticator_id, spending))
-        })
-        .collect::<Result<Vec<_>, ContractError>>()?;
-    Ok(SpendingsByAccountResponse { spendings })
+    {
+        let (authenticator_id, mut spending) = item?;
+        let price_profile = PriceProfile::new(spending.price_profile.clone());
+        price_profile.migrate_spending(deps.storage, &mut spending)?;
+
+        // the price profile has been removed, so the spending can't be valued
+        let Some(conf) = price_profile.may_load_config(deps.storage)? else {
+            spendings.push((authenticator_id, spending));
+            continue;
+        };
+        let quote_denom_exponent = get_denom_exponent(deps, &DENOM_EXPONENTS, &conf.quote_denom)?;
+        let spending = updated_spending(
+            deps,
+            &price_profile.price_infos(),
+            &DENOM_ALIASES,
+            &UNTRACKED_SPENT_FEES,
+            &conf,
+            &account,
+            &authenticator_id,
+            at,
+            spending,
+        )?;
+
+        let SpendingResponse {
+            spending,
+            value_spent_in_period_display,
+        } = spending_response(spending, quote_denom_exponent)?;
+
+        if let Some(value) = value_spent_in_period_display {
+            values_spent_in_period_display.push((authenticator_id.clone(), value));
+        }
+        spendings.push((authenticator_id, spending));
+    }
+
+    Ok(SpendingsByAccountResponse {
+        spendings,
+        values_spent_in_period_display,
+    })
+}
+
+/// Respond with the spending along with its value in display units of the quote denom if the exponent is known.
+fn spending_response(
+    spending: Spending,
+    quote_denom_exponent: Option<u32>,
+) -> StdResult<SpendingResponse> {
+    let value_spent_in_period_display = quote_denom_exponent
+        .map(|exponent| to_display_amount(spending.value_spent_in_period, exponent))
+        .transpose()?;
+
+    Ok(SpendingResponse {
+        spending,
+        value_spent_in_period_display,
+    })
 }
 
 #[cfg(test)]
@@ -378,7 +1646,7 @@ mod tests {
 
     use authenticator::SubAuthenticatorData;
     use cosmwasm_std::{
-        from_json,
+        attr, coin, from_json,
         testing::{mock_dependencies, mock_dependencies_with_balances, mock_env, mock_info},
         to_json_vec, BlockInfo, Coin, ContractResult, Uint128, Uint64,
     };
@@ -390,27 +1658,33 @@ mod tests {
         cosmos::bank::v1beta1::MsgSend,
         osmosis::{
             poolmanager::v1beta1::SwapAmountInRoute,
-            smartaccount::v1beta1::{AccountAuthenticator, GetAuthenticatorResponse},
+            smartaccount::v1beta1::{
+                AccountAuthenticator, GetAuthenticatorResponse, GetAuthenticatorsResponse,
+            },
             twap::v1beta1::ArithmeticTwapToNowResponse,
         },
     };
 
     use crate::{
+        audit::{AuditChange, AuditLogEntry},
         authenticator::CosmwasmAuthenticatorData,
         fee::UntrackedSpentFee,
         period::Period,
-        state::UNTRACKED_SPENT_FEES,
+        state::{PRE_EXEC_BALANCES, UNTRACKED_SPENT_FEES},
         test_helper::mock_stargate_querier::{
-            arithmetic_twap_to_now_query_handler, get_authenticator_query_handler,
-            mock_dependencies_with_stargate_querier,
+            arithmetic_twap_to_now_query_handler, balancer_pools_query_handler,
+            combine_query_handlers, get_authenticator_query_handler,
+            get_authenticators_query_handler, mock_dependencies_with_stargate_querier,
         },
     };
     use crate::{
-        price::PriceResolutionConfig,
-        spend_limit::{SpendLimitParams, Spending},
+        price::{get_price, PriceError, PriceInfo, PriceResolutionConfig, TwapKind},
+        spend_limit::{SpendLimitParams, Spending, TimeLimit},
     };
 
     use super::*;
+    use crate::fee::FeePolicy;
+    use crate::test_helper::price::{price_resolution_config, tracked_denom};
 
     const UUSDC: &str = "ibc/498A0751C798A0D9A389AA3691123DADA57DAA4FE165D5C75894505B876BA6E4";
 
@@ -418,8 +1692,12 @@ mod tests {
     fn test_happy_path() {
         let params = SpendLimitParams {
             limit: Uint128::from(1_000_000u128),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         };
 
         let params_for_querier_setup = params.clone();
@@ -450,9 +1728,9 @@ mod tests {
         );
         let msg = InstantiateMsg {
             price_resolution_config: PriceResolutionConfig {
-                quote_denom: UUSDC.to_string(),
                 staleness_threshold: Uint64::from(3_600_000_000u64),
                 twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
             },
             tracked_denoms: vec![],
             admin: None,
@@ -559,6 +1837,10 @@ mod tests {
         )
         .unwrap();
 
+        DENOM_EXPONENTS
+            .save(deps.as_mut().storage, UUSDC, &6)
+            .unwrap();
+
         // query spending
         let spending = from_json::<SpendingResponse>(
             &query(
@@ -578,8 +1860,14 @@ mod tests {
             SpendingResponse {
                 spending: Spending {
                     value_spent_in_period: Uint128::from(999_999u128),
-                    last_spent_at: mock_env().block.time
-                }
+                    fee_spent_in_period: Uint128::zero(),
+                    last_spent_at: mock_env().block.time,
+                    limit_conversion_rate: None,
+                    display_limit_exponent: None,
+                    price_profile: None,
+                    quote_denom_migrations_applied: 0,
+                },
+                value_spent_in_period_display: Some("0.999999".parse().unwrap()),
             }
         );
 
@@ -602,9 +1890,18 @@ mod tests {
                     "2".to_string(),
                     Spending {
                         value_spent_in_period: Uint128::from(999_999u128),
-                        last_spent_at: mock_env().block.time
+                        fee_spent_in_period: Uint128::zero(),
+                        last_spent_at: mock_env().block.time,
+                        limit_conversion_rate: None,
+                        display_limit_exponent: None,
+                        price_profile: None,
+                        quote_denom_migrations_applied: 0,
                     }
-                )]
+                )],
+                values_spent_in_period_display: vec![(
+                    "2".to_string(),
+                    "0.999999".parse().unwrap()
+                )],
             }
         );
 
@@ -652,7 +1949,13 @@ mod tests {
             .unwrap(),
         )
         .unwrap();
-        assert_eq!(spendings, SpendingsByAccountResponse { spendings: vec![] });
+        assert_eq!(
+            spendings,
+            SpendingsByAccountResponse {
+                spendings: vec![],
+                values_spent_in_period_display: vec![],
+            }
+        );
     }
 
     #[test]
@@ -660,9 +1963,9 @@ mod tests {
         let mut deps = mock_dependencies();
         let msg = InstantiateMsg {
             price_resolution_config: PriceResolutionConfig {
-                quote_denom: "uinvalid".to_string(),
                 staleness_threshold: Uint64::from(3_600_000_000u64),
                 twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config("uinvalid")
             },
             tracked_denoms: vec![],
             admin: None,
@@ -681,11 +1984,17 @@ mod tests {
     fn test_set_tracked_denom() {
         let mut deps = mock_dependencies_with_stargate_querier(
             &[],
-            arithmetic_twap_to_now_query_handler(Box::new(|_| {
-                ContractResult::Ok(ArithmeticTwapToNowResponse {
-                    arithmetic_twap: "1".to_string(),
-                })
-            })),
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "udup"), coin(1_000_000, UUSDC)],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|_| {
+                    ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: "1".to_string(),
+                    })
+                })),
+            ]),
         );
 
         let admin = deps.api.addr_make("admin");
@@ -697,9 +2006,9 @@ mod tests {
 
         let msg = InstantiateMsg {
             price_resolution_config: PriceResolutionConfig {
-                quote_denom: UUSDC.to_string(),
                 staleness_threshold: Uint64::from(3_600_000_000u64),
                 twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
             },
             tracked_denoms: vec![],
             admin: Some(admin.to_string()),
@@ -708,21 +2017,22 @@ mod tests {
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         let msg = ExecuteMsg::SetTrackedDenoms {
+            price_profile: None,
             tracked_denoms: vec![
-                TrackedDenom {
-                    denom: "udup".to_string(),
-                    swap_routes: vec![SwapAmountInRoute {
+                tracked_denom(
+                    "udup",
+                    vec![SwapAmountInRoute {
                         pool_id: 1u64,
                         token_out_denom: UUSDC.to_string(),
                     }],
-                },
-                TrackedDenom {
-                    denom: "udup".to_string(),
-                    swap_routes: vec![SwapAmountInRoute {
+                ),
+                tracked_denom(
+                    "udup",
+                    vec![SwapAmountInRoute {
                         pool_id: 2u64,
                         token_out_denom: UUSDC.to_string(),
                     }],
-                },
+                ),
             ],
         };
 
@@ -738,13 +2048,14 @@ mod tests {
 
         // quote denom also counted as duplicated
         let msg = ExecuteMsg::SetTrackedDenoms {
-            tracked_denoms: vec![TrackedDenom {
-                denom: UUSDC.to_string(),
-                swap_routes: vec![SwapAmountInRoute {
+            price_profile: None,
+            tracked_denoms: vec![tracked_denom(
+                UUSDC,
+                vec![SwapAmountInRoute {
                     pool_id: 100u64,
                     token_out_denom: UUSDC.to_string(),
                 }],
-            }],
+            )],
         };
 
         let info = mock_info(admin.as_str(), &[]);
@@ -762,36 +2073,42 @@ mod tests {
     fn test_initialize_with_duplicated_denom() {
         let mut deps = mock_dependencies_with_stargate_querier(
             &[],
-            arithmetic_twap_to_now_query_handler(Box::new(|_| {
-                ContractResult::Ok(ArithmeticTwapToNowResponse {
-                    arithmetic_twap: "1".to_string(),
-                })
-            })),
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "udup"), coin(1_000_000, "uvalid")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|_| {
+                    ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: "1".to_string(),
+                    })
+                })),
+            ]),
         );
         deps.querier
             .update_balance("creator", vec![Coin::new(1_000_000, "uvalid")]);
 
         let msg = InstantiateMsg {
             price_resolution_config: PriceResolutionConfig {
-                quote_denom: "uvalid".to_string(),
                 staleness_threshold: Uint64::from(3_600_000_000u64),
                 twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config("uvalid")
             },
             tracked_denoms: vec![
-                TrackedDenom {
-                    denom: "udup".to_string(),
-                    swap_routes: vec![SwapAmountInRoute {
+                tracked_denom(
+                    "udup",
+                    vec![SwapAmountInRoute {
                         pool_id: 1u64,
                         token_out_denom: "uvalid".to_string(),
                     }],
-                },
-                TrackedDenom {
-                    denom: "udup".to_string(),
-                    swap_routes: vec![SwapAmountInRoute {
+                ),
+                tracked_denom(
+                    "udup",
+                    vec![SwapAmountInRoute {
                         pool_id: 2u64,
                         token_out_denom: "uvalid".to_string(),
                     }],
-                },
+                ),
             ],
             admin: None,
         };
@@ -812,24 +2129,36 @@ mod tests {
                 ("addr_a", "1"),
                 SpendLimitParams {
                     limit: Uint128::from(1_000_000u128),
+                    display_limit: None,
                     reset_period: Period::Day,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 },
             ),
             (
                 ("addr_a", "2.1.0"),
                 SpendLimitParams {
                     limit: Uint128::from(2_000_000u128),
+                    display_limit: None,
                     reset_period: Period::Week,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 },
             ),
             (
                 ("addr_b", "66"),
                 SpendLimitParams {
                     limit: Uint128::from(1_000_000u128),
+                    display_limit: None,
                     reset_period: Period::Month,
                     time_limit: None,
+                    price_profile: None,
+                    fee_policy: FeePolicy::default(),
+                    fee_limit: None,
                 },
             ),
         ]
@@ -906,9 +2235,9 @@ mod tests {
             .save(
                 &mut deps.storage,
                 &PriceResolutionConfig {
-                    quote_denom: "uosmo".to_string(),
                     staleness_threshold: Uint64::from(3_600_000_000u64),
                     twap_duration: Uint64::from(3_600_000_000u64),
+                    ..price_resolution_config("uosmo")
                 },
             )
             .unwrap();
@@ -916,7 +2245,12 @@ mod tests {
         // setup states that correspond to the query hanlders
         let mock_spending = Spending {
             value_spent_in_period: 999_999u128.into(),
+            fee_spent_in_period: Uint128::zero(),
             last_spent_at: mock_env().block.time,
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: None,
+            quote_denom_migrations_applied: 0,
         };
         for ((account, authenticator_id), _) in params_map {
             SPENDINGS
@@ -930,7 +2264,7 @@ mod tests {
 
         // test query with both single and per account
 
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
@@ -947,7 +2281,7 @@ mod tests {
 
         // test reset
         // after 1 day "a, 1" reset
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(1),
@@ -962,13 +2296,18 @@ mod tests {
 
         let reset_spending = Spending {
             value_spent_in_period: 0u128.into(),
+            fee_spent_in_period: Uint128::zero(),
             last_spent_at: mock_env().block.time,
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: None,
+            quote_denom_migrations_applied: 0,
         };
 
         assert_eq!(spending, reset_spending,);
 
         // "a, 2.1.0" not reset
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(1),
@@ -985,7 +2324,7 @@ mod tests {
 
         // add a week in
         // after 1 week "a, 2.1.0" reset
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(7),
@@ -1001,7 +2340,7 @@ mod tests {
         assert_eq!(spending, reset_spending);
 
         // "b, 66" not reset
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(7),
@@ -1018,7 +2357,7 @@ mod tests {
 
         // add a month in
         // after 1 month "b, 66" reset
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(30),
@@ -1034,7 +2373,7 @@ mod tests {
         assert_eq!(spending, reset_spending);
 
         // query for account
-        let SpendingsByAccountResponse { spendings } = from_json(
+        let SpendingsByAccountResponse { spendings, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
@@ -1056,7 +2395,7 @@ mod tests {
 
         // add day in
         // after 1 day "a, 1" reset
-        let SpendingsByAccountResponse { spendings } = from_json(
+        let SpendingsByAccountResponse { spendings, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(1),
@@ -1104,11 +2443,16 @@ mod tests {
 
         let mock_spending_with_fee = Spending {
             value_spent_in_period: mock_spending.value_spent_in_period + Uint128::from(100u128),
+            fee_spent_in_period: Uint128::zero(),
             last_spent_at: mock_env().block.time,
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: None,
+            quote_denom_migrations_applied: 0,
         };
 
         // query spending
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
@@ -1124,7 +2468,7 @@ mod tests {
         assert_eq!(spending, mock_spending_with_fee);
 
         // reset after a day in
-        let SpendingResponse { spending } = from_json(
+        let SpendingResponse { spending, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env_with_additional_days(1),
@@ -1157,9 +2501,9 @@ mod tests {
         let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
 
         let init_config = PriceResolutionConfig {
-            quote_denom: UUSDC.to_string(),
             staleness_threshold: Uint64::from(3_600_000_000u64),
             twap_duration: Uint64::from(3_600_000_000u64),
+            ..price_resolution_config(UUSDC)
         };
         let msg = InstantiateMsg {
             price_resolution_config: init_config.clone(),
@@ -1175,7 +2519,9 @@ mod tests {
             query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::PriceResolutionConfig {},
+                QueryMsg::PriceResolutionConfig {
+                    price_profile: None,
+                },
             )
             .unwrap(),
         )
@@ -1187,10 +2533,28 @@ mod tests {
             quote_denom: UUSDC.to_string(),
             staleness_threshold: Uint64::from(7_200_000_000u64),
             twap_duration: Uint64::from(7_200_000_000u64),
+            twap_kind: TwapKind::Geometric,
+            max_price_change_per_refresh: Some(Decimal::percent(20)),
+            block_time_behind_last_update: BlockTimeBehindLastUpdate::Reject,
+            min_pool_liquidity: Some(Uint128::new(1_000_000)),
+            spot_price_fallback: Some(SpotPriceFallback {
+                max_deviation: Decimal::percent(10),
+                markup: Decimal::percent(5),
+            }),
+            max_price_age: Some(Uint64::from(86_400_000_000_000u64)),
         };
         let msg = ExecuteMsg::SetPriceResolutionParams {
-            staleness_threshold: new_config.staleness_threshold,
-            twap_duration: new_config.twap_duration,
+            price_profile: None,
+            staleness_threshold: Some(new_config.staleness_threshold),
+            twap_duration: Some(new_config.twap_duration),
+            twap_kind: Some(new_config.twap_kind),
+            max_price_change_per_refresh: new_config
+                .max_price_change_per_refresh
+                .map(OptionalUpdate::Set),
+            block_time_behind_last_update: Some(new_config.block_time_behind_last_update),
+            min_pool_liquidity: new_config.min_pool_liquidity.map(OptionalUpdate::Set),
+            spot_price_fallback: new_config.spot_price_fallback.map(OptionalUpdate::Set),
+            max_price_age: new_config.max_price_age.map(OptionalUpdate::Set),
         };
         let info = mock_info("non_admin", &[]);
         let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
@@ -1205,7 +2569,9 @@ mod tests {
             query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::PriceResolutionConfig {},
+                QueryMsg::PriceResolutionConfig {
+                    price_profile: None,
+                },
             )
             .unwrap(),
         )
@@ -1215,220 +2581,543 @@ mod tests {
     }
 
     #[test]
-    fn test_no_admin() {
-        let mut deps =
-            mock_dependencies_with_balances(&[("creator", &[Coin::new(100000000, UUSDC)])]);
+    fn test_set_price_resolution_config_keeps_params_not_given() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
 
+        let init_config = PriceResolutionConfig {
+            twap_kind: TwapKind::Geometric,
+            max_price_change_per_refresh: Some(Decimal::percent(20)),
+            block_time_behind_last_update: BlockTimeBehindLastUpdate::Reject,
+            min_pool_liquidity: Some(Uint128::new(1_000_000)),
+            spot_price_fallback: Some(SpotPriceFallback {
+                max_deviation: Decimal::percent(10),
+                markup: Decimal::percent(5),
+            }),
+            max_price_age: Some(Uint64::from(86_400_000_000_000u64)),
+            ..price_resolution_config(UUSDC)
+        };
         let msg = InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: UUSDC.to_string(),
-                staleness_threshold: Uint64::from(3_600_000_000u64),
-                twap_duration: Uint64::from(3_600_000_000u64),
-            },
+            price_resolution_config: init_config.clone(),
             tracked_denoms: vec![],
-            admin: None,
+            admin: Some(String::from("admin")),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let query_config = |deps: Deps| {
+            let PriceResolutionConfigResponse {
+                price_resolution_config,
+            } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::PriceResolutionConfig {
+                        price_profile: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            price_resolution_config
         };
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        // only updating the staleness threshold keeps the circuit breaker and the other params
+        let set_params = ExecuteMsg::SetPriceResolutionParams {
+            price_profile: None,
+            staleness_threshold: Some(Uint64::from(7_200_000_000_000u64)),
+            twap_duration: None,
+            twap_kind: None,
+            max_price_change_per_refresh: None,
+            block_time_behind_last_update: None,
+            min_pool_liquidity: None,
+            spot_price_fallback: None,
+            max_price_age: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            set_params,
+        )
+        .unwrap();
 
-        assert_eq!(admin, None);
-        assert_eq!(candidate, None);
+        assert_eq!(
+            query_config(deps.as_ref()),
+            PriceResolutionConfig {
+                staleness_threshold: Uint64::from(7_200_000_000_000u64),
+                ..init_config.clone()
+            }
+        );
+
+        // unsetting the circuit breaker only removes the circuit breaker
+        let set_params = ExecuteMsg::SetPriceResolutionParams {
+            price_profile: None,
+            staleness_threshold: None,
+            twap_duration: None,
+            twap_kind: None,
+            max_price_change_per_refresh: Some(OptionalUpdate::Unset),
+            block_time_behind_last_update: None,
+            min_pool_liquidity: None,
+            spot_price_fallback: None,
+            max_price_age: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            set_params,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query_config(deps.as_ref()),
+            PriceResolutionConfig {
+                staleness_threshold: Uint64::from(7_200_000_000_000u64),
+                max_price_change_per_refresh: None,
+                ..init_config
+            }
+        );
     }
 
     #[test]
-    fn test_admin() {
-        let mut deps =
-            mock_dependencies_with_balances(&[("creator", &[Coin::new(100000000, UUSDC)])]);
+    fn test_set_denom_exponents() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
 
         let msg = InstantiateMsg {
             price_resolution_config: PriceResolutionConfig {
-                quote_denom: UUSDC.to_string(),
                 staleness_threshold: Uint64::from(3_600_000_000u64),
                 twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
             },
             tracked_denoms: vec![],
-            admin: Some("admin".to_string()),
+            admin: Some(String::from("admin")),
         };
         let info = mock_info("creator", &[]);
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-
-        assert_eq!(admin, Some("admin".to_string()));
-        assert_eq!(candidate, None);
-
-        // non admin can't transfer admin
-        let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::TransferAdmin {
-            address: "new_admin".to_string(),
+        let query_exponent = |deps: Deps, denom: &str| {
+            let DenomExponentResponse { exponent } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::DenomExponent {
+                        denom: denom.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            exponent
         };
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        // no exponent set and no bank denom metadata
+        assert_eq!(query_exponent(deps.as_ref(), UUSDC), None);
 
-        // admin can transfer admin
-        let info = mock_info("admin", &[]);
-        let msg = ExecuteMsg::TransferAdmin {
-            address: "new_admin".to_string(),
+        let msg = ExecuteMsg::SetDenomExponents {
+            exponents: vec![(UUSDC.to_string(), 6), ("uosmo".to_string(), 6)],
         };
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("admin", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "set_denom_exponents")]);
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, Some("admin".to_string()));
-        assert_eq!(candidate, Some("new_admin".to_string()));
+        assert_eq!(query_exponent(deps.as_ref(), UUSDC), Some(6));
+        assert_eq!(query_exponent(deps.as_ref(), "uosmo"), Some(6));
 
-        // non candidate can't claim admin
-        let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::ClaimAdminTransfer {};
+        // exponent must fit in decimal places
+        let msg = ExecuteMsg::SetDenomExponents {
+            exponents: vec![("wei".to_string(), 19)],
+        };
+        let info = mock_info("admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDenomExponent {
+                denom: "wei".to_string(),
+                exponent: 19
+            }
+        );
+        assert_eq!(query_exponent(deps.as_ref(), "wei"), None);
+    }
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+    #[test]
+    fn test_denom_aliases() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
 
-        // candidate can claim admin
-        let info = mock_info("new_admin", &[]);
-        let msg = ExecuteMsg::ClaimAdminTransfer {};
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+        let msg = ExecuteMsg::SetFixedPrices {
+            price_profile: None,
+            prices: vec![("uosmo".to_string(), Decimal::percent(150))],
+        };
+        let info = mock_info("admin", &[]);
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, Some("new_admin".to_string()));
-        assert_eq!(candidate, None);
-
-        // transfer again
-        let info = mock_info("new_admin", &[]);
-        let msg = ExecuteMsg::TransferAdmin {
-            address: "new_admin_2".to_string(),
+        let alias = |canonical_denom: &str, ratio: Option<Decimal>| DenomAlias {
+            canonical_denom: canonical_denom.to_string(),
+            ratio,
         };
 
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, Some("new_admin".to_string()));
-        assert_eq!(candidate, Some("new_admin_2".to_string()));
+        let msg = ExecuteMsg::SetDenomAliases {
+            aliases: vec![
+                (
+                    "uosmo.axl".to_string(),
+                    alias("uosmo", Some(Decimal::percent(50))),
+                ),
+                ("uusdc.axl".to_string(), alias(UUSDC, None)),
+            ],
+        };
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
-        // only candidate can reject admin transfer
-        let info = mock_info("new_admin", &[]);
-        let msg = ExecuteMsg::RejectAdminTransfer {};
+        let info = mock_info("admin", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes, vec![attr("action", "set_denom_aliases")]);
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        // aliases can't be chained
+        let msg = ExecuteMsg::SetDenomAliases {
+            aliases: vec![("uosmo.eth".to_string(), alias("uosmo.axl", None))],
+        };
+        let info = mock_info("admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriceResolutionError(PriceError::InvalidDenomAlias {
+                denom: "uosmo.eth".to_string(),
+                reason: "canonical denom uosmo.axl is an alias itself".to_string(),
+            })
+        );
 
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        // alias resolves to the price of its canonical denom
+        let conf = PRICE_RESOLUTION_CONFIG.load(&deps.storage).unwrap();
+        let price_info = get_and_cache_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_mut(),
+            &conf,
+            mock_env().block.time,
+            "uosmo.axl",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(price_info.price, Decimal::percent(75));
 
-        // candidate can reject admin transfer
-        let info = mock_info("new_admin_2", &[]);
-        let msg = ExecuteMsg::RejectAdminTransfer {};
+        // tracked denoms include the aliases priced by them
+        let TrackedDenomsResponse {
+            tracked_denoms,
+            aliases,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            tracked_denoms
+                .into_iter()
+                .map(|tracked_denom| tracked_denom.denom)
+                .collect::<Vec<_>>(),
+            vec!["uosmo".to_string()]
+        );
+        assert_eq!(
+            aliases,
+            vec![(
+                "uosmo.axl".to_string(),
+                alias("uosmo", Some(Decimal::percent(50)))
+            )]
+        );
 
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let query_aliases = |deps: Deps| {
+            let DenomAliasesResponse { aliases } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::DenomAliases {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            aliases
+        };
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, Some("new_admin".to_string()));
-        assert_eq!(candidate, None);
+        assert_eq!(
+            query_aliases(deps.as_ref()),
+            vec![
+                (
+                    "uosmo.axl".to_string(),
+                    alias("uosmo", Some(Decimal::percent(50)))
+                ),
+                ("uusdc.axl".to_string(), alias(UUSDC, None)),
+            ]
+        );
 
-        // transfer again
-        let info = mock_info("new_admin", &[]);
-        let msg = ExecuteMsg::TransferAdmin {
-            address: "new_admin_2".to_string(),
+        let msg = ExecuteMsg::RemoveDenomAliases {
+            denoms: vec!["uosmo.axl".to_string()],
         };
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
+        let info = mock_info("admin", &[]);
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, Some("new_admin".to_string()));
-        assert_eq!(candidate, Some("new_admin_2".to_string()));
-
-        // only admin can cancel admin transfer
-        let info = mock_info("new_admin_2", &[]);
-        let msg = ExecuteMsg::CancelAdminTransfer {};
+        assert_eq!(
+            query_aliases(deps.as_ref()),
+            vec![("uusdc.axl".to_string(), alias(UUSDC, None))]
+        );
+    }
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+    #[test]
+    fn test_denom_alias_in_price_profile_without_canonical_denom() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
 
-        // admin can cancel admin transfer
-        let info = mock_info("new_admin", &[]);
-        let msg = ExecuteMsg::CancelAdminTransfer {};
+        let msg = InstantiateMsg {
+            price_resolution_config: price_resolution_config(UUSDC),
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
+        let msg = ExecuteMsg::SetDenomAliases {
+            aliases: vec![(
+                "uosmo.axl".to_string(),
+                DenomAlias {
+                    canonical_denom: "uosmo".to_string(),
+                    ratio: Some(Decimal::percent(50)),
+                },
+            )],
+        };
+        let info = mock_info("admin", &[]);
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, Some("new_admin".to_string()));
-        assert_eq!(candidate, None);
-
-        // only admin can revoke admin
-        let info = mock_info("new_admin_2", &[]);
-        let msg = ExecuteMsg::RevokeAdmin {};
+        // the axl profile tracks the alias but not its canonical denom
+        let msg = ExecuteMsg::CreatePriceProfile {
+            name: "axl".to_string(),
+            price_resolution_config: price_resolution_config(UUSDC),
+            tracked_denoms: vec![],
+        };
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let set_fixed_price = |deps: DepsMut, denom: &str, price: Decimal| {
+            let msg = ExecuteMsg::SetFixedPrices {
+                price_profile: Some("axl".to_string()),
+                prices: vec![(denom.to_string(), price)],
+            };
+            let info = mock_info("admin", &[]);
+            execute(deps, mock_env(), info, msg).unwrap();
+        };
 
-        // admin can revoke admin
-        let info = mock_info("new_admin", &[]);
-        let msg = ExecuteMsg::RevokeAdmin {};
+        set_fixed_price(deps.as_mut(), "uosmo.axl", Decimal::percent(120));
 
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let price_profile = PriceProfile::new(Some("axl".to_string()));
+        let conf = price_profile.load_config(&deps.storage).unwrap();
+        let alias_price = |deps: DepsMut| {
+            let price = get_price(
+                &price_profile.price_infos(),
+                &DENOM_ALIASES,
+                deps.as_ref(),
+                &conf,
+                mock_env().block.time,
+                "uosmo.axl",
+            )
+            .unwrap()
+            .map(|price_info| price_info.price);
 
-        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
-        assert_eq!(admin, None);
-        assert_eq!(candidate, None);
-    }
+            let cached_price = get_and_cache_price(
+                &price_profile.price_infos(),
+                &DENOM_ALIASES,
+                deps,
+                &conf,
+                mock_env().block.time,
+                "uosmo.axl",
+            )
+            .unwrap()
+            .map(|price_info| price_info.price);
 
-    fn query_admin_and_candidate(deps: Deps) -> (Option<String>, Option<String>) {
-        let AdminResponse { admin } =
-            from_json(query(deps, mock_env(), QueryMsg::Admin {}).unwrap()).unwrap();
+            assert_eq!(price, cached_price);
+            price
+        };
 
-        let AdminCandidateResponse { candidate } =
-            from_json(query(deps, mock_env(), QueryMsg::AdminCandidate {}).unwrap()).unwrap();
+        // alias is priced on its own instead of being treated as untracked
+        assert_eq!(alias_price(deps.as_mut()), Some(Decimal::percent(120)));
 
-        (admin, candidate)
+        // once the canonical denom is tracked, the alias resolves to its price
+        set_fixed_price(deps.as_mut(), "uosmo", Decimal::percent(150));
+        assert_eq!(alias_price(deps.as_mut()), Some(Decimal::percent(75)));
     }
 
-    // TODO: test set tracked denoms
-    // test admin can set tracked denoms
-    // need to mock stargate query
-
     #[test]
-    fn test_set_and_remove_tracked_denoms() {
-        let mut deps = mock_dependencies_with_stargate_querier(
-            &[("creator", &[Coin::new(100000, "uusdc")])],
-            arithmetic_twap_to_now_query_handler(Box::new(|req| {
-                let base_asset = req.base_asset.as_str();
-                let quote_asset = req.quote_asset.as_str();
-
-                let arithmetic_twap = match (base_asset, quote_asset) {
-                    ("uosmo", "uusdc") => "1.5",
-                    ("uatom", "uusdc") => "2.5",
-                    ("uion", "uusdc") => "3.5",
-                    ("ustars", "uusdc") => "4.5",
-                    ("uosmo", "uatom") => "0.5",
-                    _ => return ContractResult::Err("Price not found".to_string()),
-                }
-                .to_string();
-
-                ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+    fn test_price_profiles() {
+        let params = SpendLimitParams {
+            limit: Uint128::from(1_000_000u128),
+            display_limit: None,
+            reset_period: Period::Day,
+            time_limit: None,
+            price_profile: Some("osmo".to_string()),
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
+        };
+
+        let params_for_querier_setup = params.clone();
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[
+                (
+                    "creator",
+                    &[Coin::new(1000, UUSDC), Coin::new(1000, "uosmo")],
+                ),
+                ("limited_account", &[Coin::new(2_000_000, UUSDC)]),
+            ],
+            get_authenticator_query_handler(Box::new(move |req| {
+                match (req.account.as_str(), req.authenticator_id) {
+                    ("limited_account", 2) => ContractResult::Ok(GetAuthenticatorResponse {
+                        account_authenticator: Some(AccountAuthenticator {
+                            id: 2,
+                            r#type: "CosmWasmAuthenticatorV1".to_string(),
+                            config: to_json_vec(&CosmwasmAuthenticatorData {
+                                contract: mock_env().contract.address.to_string(),
+                                params: to_json_vec(&params_for_querier_setup).unwrap(),
+                            })
+                            .unwrap(),
+                        }),
+                    }),
+                    _ => ContractResult::Err("not found".to_string()),
+                }
             })),
         );
 
+        let conf = |quote_denom: &str| PriceResolutionConfig {
+            staleness_threshold: Uint64::from(3_600_000_000u64),
+            twap_duration: Uint64::from(3_600_000_000u64),
+            ..price_resolution_config(quote_denom)
+        };
+
         let msg = InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: "uusdc".to_string(),
-                staleness_threshold: Uint64::from(3_600_000_000u64),
-                twap_duration: Uint64::from(3_600_000_000u64),
-            },
+            price_resolution_config: conf(UUSDC),
             tracked_denoms: vec![],
-            admin: Some("admin".to_string()),
+            admin: Some(String::from("admin")),
         };
-
         let info = mock_info("creator", &[]);
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query tracked denoms
-        let TrackedDenomsResponse { tracked_denoms } = from_json(
+        let create_msg = |name: &str| ExecuteMsg::CreatePriceProfile {
+            name: name.to_string(),
+            price_resolution_config: conf("uosmo"),
+            tracked_denoms: vec![],
+        };
+
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, create_msg("osmo")).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, create_msg("osmo:usd")).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPriceProfileName {
+                name: "osmo:usd".to_string()
+            }
+        );
+
+        let info = mock_info("admin", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, create_msg("osmo")).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "create_price_profile"), attr("name", "osmo")]
+        );
+
+        let info = mock_info("admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, create_msg("osmo")).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriceProfileAlreadyExists {
+                name: "osmo".to_string()
+            }
+        );
+
+        // 1 uusdc is worth 2 uosmo in the osmo profile
+        let msg = ExecuteMsg::SetFixedPrices {
+            price_profile: Some("osmo".to_string()),
+            prices: vec![(UUSDC.to_string(), Decimal::percent(200))],
+        };
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let query_tracked_denoms = |deps: Deps, price_profile: Option<&str>| {
             query(
-                deps.as_ref(),
+                deps,
                 mock_env(),
                 QueryMsg::TrackedDenoms {
+                    price_profile: price_profile.map(String::from),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .map(|res| {
+                from_json::<TrackedDenomsResponse>(res)
+                    .unwrap()
+                    .tracked_denoms
+                    .into_iter()
+                    .map(|tracked_denom| tracked_denom.denom)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // tracked denoms of the profiles are separated
+        assert_eq!(query_tracked_denoms(deps.as_ref(), None), Ok(vec![]));
+        assert_eq!(
+            query_tracked_denoms(deps.as_ref(), Some("osmo")),
+            Ok(vec![UUSDC.to_string()])
+        );
+
+        let query_price_profiles = |deps: Deps, start_after: Option<&str>, limit: Option<u32>| {
+            let PriceProfilesResponse { price_profiles } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::PriceProfiles {
+                        start_after: start_after.map(String::from),
+                        limit,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            price_profiles
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+
+        let PriceProfilesResponse { price_profiles } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::PriceProfiles {
                     start_after: None,
                     limit: None,
                 },
@@ -1436,64 +3125,178 @@ mod tests {
             .unwrap(),
         )
         .unwrap();
+        assert_eq!(price_profiles, vec![("osmo".to_string(), conf("uosmo"))]);
 
-        assert_eq!(tracked_denoms, vec![]);
+        // price profiles are paginated by name
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, create_msg("usd")).unwrap();
+        assert_eq!(
+            query_price_profiles(deps.as_ref(), None, Some(1)),
+            vec!["osmo".to_string()]
+        );
+        assert_eq!(
+            query_price_profiles(deps.as_ref(), Some("osmo"), None),
+            vec!["usd".to_string()]
+        );
+        let msg = ExecuteMsg::RemovePriceProfile {
+            name: "usd".to_string(),
+        };
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // set tracked denoms
-        let tracked_denoms = vec![
-            TrackedDenom {
-                denom: "uatom".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 2,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-            TrackedDenom {
-                denom: "uion".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 3,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-            TrackedDenom {
-                denom: "uosmo".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 1,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-            TrackedDenom {
-                denom: "ustars".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 4,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-        ];
-        let info = mock_info("non_admin", &[]);
-        let msg = ExecuteMsg::SetTrackedDenoms {
-            tracked_denoms: tracked_denoms.clone(),
+        // spend with an authenticator selecting the osmo profile
+        let authenticator_params = to_json_binary(&params).unwrap();
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::OnAuthenticatorAdded(OnAuthenticatorAddedRequest {
+                account: Addr::unchecked("limited_account"),
+                authenticator_id: "2".to_string(),
+                authenticator_params: Some(authenticator_params.clone()),
+            }),
+        )
+        .unwrap();
+
+        let msg = Any {
+            type_url: MsgSend::TYPE_URL.to_string(),
+            value: Binary::from(
+                MsgSend {
+                    from_address: "limited_account".to_string(),
+                    to_address: "recipient".to_string(),
+                    amount: vec![Coin::new(400_000, UUSDC).into()],
+                }
+                .to_proto_bytes(),
+            ),
         };
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::Track(TrackRequest {
+                account: Addr::unchecked("limited_account"),
+                fee_payer: Addr::unchecked("limited_account"),
+                fee_granter: None,
+                fee: vec![],
+                authenticator_id: "2".to_string(),
+                msg: msg.clone(),
+                msg_index: 0,
+                authenticator_params: Some(authenticator_params.clone()),
+            }),
+        )
+        .unwrap();
 
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        deps.querier
+            .update_balance("limited_account", vec![Coin::new(1_600_000, UUSDC)]);
+
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::ConfirmExecution(ConfirmExecutionRequest {
+                authenticator_id: "2".to_string(),
+                account: Addr::unchecked("limited_account"),
+                fee_payer: Addr::unchecked("limited_account"),
+                fee_granter: None,
+                fee: vec![],
+                msg,
+                msg_index: 0,
+                authenticator_params: Some(authenticator_params),
+            }),
+        )
+        .unwrap();
+
+        let query_spending = |deps: Deps| {
+            let SpendingResponse { spending, .. } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::Spending {
+                        account: "limited_account".to_string(),
+                        authenticator_id: "2".to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            spending
+        };
+
+        let expected_spending = Spending {
+            value_spent_in_period: Uint128::new(800_000),
+            fee_spent_in_period: Uint128::zero(),
+            last_spent_at: mock_env().block.time,
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: Some("osmo".to_string()),
+            quote_denom_migrations_applied: 0,
+        };
+        assert_eq!(query_spending(deps.as_ref()), expected_spending);
 
+        // migrating the quote denom of the default profile doesn't rescale spendings of other profiles
+        let msg = ExecuteMsg::MigrateQuoteDenom {
+            price_profile: None,
+            quote_denom: "uosmo".to_string(),
+            conversion_rate: Decimal::percent(200),
+            tracked_denoms: vec![],
+        };
         let info = mock_info("admin", &[]);
-        let msg = ExecuteMsg::SetTrackedDenoms {
-            tracked_denoms: tracked_denoms.clone(),
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(query_spending(deps.as_ref()), expected_spending);
+
+        // authenticator can't select a profile that doesn't exist
+        let err = sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::OnAuthenticatorAdded(OnAuthenticatorAddedRequest {
+                account: Addr::unchecked("limited_account"),
+                authenticator_id: "3".to_string(),
+                authenticator_params: Some(
+                    to_json_binary(&SpendLimitParams {
+                        price_profile: Some("atom".to_string()),
+                        fee_policy: FeePolicy::default(),
+                        fee_limit: None,
+                        ..params
+                    })
+                    .unwrap(),
+                ),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AuthenticatorError(StdError::not_found("Price profile atom").into())
+        );
+
+        // migrate the quote denom of the osmo profile before removing it
+        let msg = ExecuteMsg::MigrateQuoteDenom {
+            price_profile: Some("osmo".to_string()),
+            quote_denom: UUSDC.to_string(),
+            conversion_rate: Decimal::percent(50),
+            tracked_denoms: vec![],
+        };
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let osmo_profile = PriceProfile::new(Some("osmo".to_string()));
+        assert_eq!(
+            osmo_profile.quote_denom_migration_count(&deps.storage),
+            Ok(1)
+        );
+
+        let msg = ExecuteMsg::RemovePriceProfile {
+            name: "osmo".to_string(),
         };
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
+        let info = mock_info("admin", &[]);
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query tracked denoms
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
+        let PriceProfilesResponse { price_profiles } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::TrackedDenoms {
+                QueryMsg::PriceProfiles {
                     start_after: None,
                     limit: None,
                 },
@@ -1501,104 +3304,2492 @@ mod tests {
             .unwrap(),
         )
         .unwrap();
+        assert_eq!(price_profiles, vec![]);
+        assert_eq!(
+            query_tracked_denoms(deps.as_ref(), Some("osmo")),
+            Err(StdError::not_found("Price profile osmo").into())
+        );
 
-        assert_eq!(tracked_denoms_response, tracked_denoms);
+        // spendings selecting the removed profile are still listed, without a valuation
+        let SpendingsByAccountResponse {
+            spendings,
+            values_spent_in_period_display,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SpendingsByAccount {
+                    account: "limited_account".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(spendings, vec![("2".to_string(), expected_spending)]);
+        assert_eq!(values_spent_in_period_display, vec![]);
 
-        // remove tracked denoms
-        let info = mock_info("non_admin", &[]);
-        let msg = ExecuteMsg::RemoveTrackedDenoms {
-            target: DenomRemovalTarget::Partial(vec!["uatom".to_string(), "ustars".to_string()]),
-        };
+        // tracked denoms and quote denom migrations of the removed profile are cleared
+        assert_eq!(
+            osmo_profile
+                .price_infos()
+                .may_load(&deps.storage, "uosmo")
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            osmo_profile
+                .quote_denom_migrations()
+                .keys(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            0
+        );
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+        // profile created again with the same name doesn't inherit the migrations,
+        // but keeps numbering them after the removed ones
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, create_msg("osmo")).unwrap();
 
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let mut spending = Spending {
+            price_profile: Some("osmo".to_string()),
+            value_spent_in_period: Uint128::new(1_000),
+            quote_denom_migrations_applied: 1,
+            ..Spending::default()
+        };
+        let unchanged = spending.clone();
+        osmo_profile
+            .migrate_spending(&deps.storage, &mut spending)
+            .unwrap();
+        assert_eq!(spending, unchanged);
 
+        let msg = ExecuteMsg::MigrateQuoteDenom {
+            price_profile: Some("osmo".to_string()),
+            quote_denom: UUSDC.to_string(),
+            conversion_rate: Decimal::percent(200),
+            tracked_denoms: vec![],
+        };
         let info = mock_info("admin", &[]);
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            osmo_profile.quote_denom_migration_count(&deps.storage),
+            Ok(2)
+        );
+
+        // spending that applied the migrations of the removed profile is rescaled with the new one
+        osmo_profile
+            .migrate_spending(&deps.storage, &mut spending)
+            .unwrap();
+        assert_eq!(
+            spending,
+            Spending {
+                value_spent_in_period: Uint128::new(2_000),
+                limit_conversion_rate: Some(Decimal::percent(200)),
+                quote_denom_migrations_applied: 2,
+                ..unchanged
+            }
+        );
+    }
+
+    #[test]
+    fn test_roles() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let grant_role = |role: Role, address: &str| ExecuteMsg::GrantRole {
+            role,
+            address: address.to_string(),
+        };
+        let set_fixed_prices = ExecuteMsg::SetFixedPrices {
+            price_profile: None,
+            prices: vec![("uosmo".to_string(), Decimal::percent(150))],
+        };
+        let set_denom_aliases = ExecuteMsg::SetDenomAliases {
+            aliases: vec![(
+                "uosmo.axl".to_string(),
+                DenomAlias {
+                    canonical_denom: "uosmo".to_string(),
+                    ratio: None,
+                },
+            )],
+        };
+        let query_holders = |deps: Deps, role: Role| {
+            let RoleHoldersResponse { holders } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::RoleHolders {
+                        role,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            holders
+        };
+
+        // only the admin can grant roles
+        let info = mock_info("non_admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            grant_role(Role::PriceManager, "non_admin"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            grant_role(Role::PriceManager, "price_manager"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "grant_role"),
+                attr("role", "price_manager"),
+                attr("address", "price_manager")
+            ]
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            grant_role(Role::DenomManager, "denom_manager"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            query_holders(deps.as_ref(), Role::PriceManager),
+            vec!["price_manager".to_string()]
+        );
+        assert_eq!(
+            query_holders(deps.as_ref(), Role::DenomManager),
+            vec!["denom_manager".to_string()]
+        );
+
+        // each role is only authorized for its own messages
+        let info = mock_info("price_manager", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            set_fixed_prices.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            set_denom_aliases.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            grant_role(Role::DenomManager, "price_manager"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let info = mock_info("denom_manager", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            set_denom_aliases.clone(),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            set_fixed_prices.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::TransferAdmin {
+                address: "denom_manager".to_string(),
+                expires_at: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // revoked role is no longer authorized
+        let info = mock_info("admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::RevokeRole {
+                role: Role::PriceManager,
+                address: "price_manager".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "revoke_role"),
+                attr("role", "price_manager"),
+                attr("address", "price_manager")
+            ]
+        );
+        assert_eq!(
+            query_holders(deps.as_ref(), Role::PriceManager),
+            Vec::<String>::new()
+        );
+
+        let info = mock_info("price_manager", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, set_fixed_prices).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // transferring the admin revokes all the roles once the new admin claims it
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::TransferAdmin {
+                address: "new_admin".to_string(),
+                expires_at: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            query_holders(deps.as_ref(), Role::DenomManager),
+            vec!["denom_manager".to_string()]
+        );
+
+        let info = mock_info("new_admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::ClaimAdminTransfer {},
+        )
+        .unwrap();
+        assert_eq!(
+            query_holders(deps.as_ref(), Role::DenomManager),
+            Vec::<String>::new()
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("denom_manager", &[]),
+            set_denom_aliases.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            grant_role(Role::DenomManager, "denom_manager"),
+        )
+        .unwrap();
+
+        // revoking the admin revokes all the roles
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::RevokeAdmin {}).unwrap();
+        assert_eq!(
+            query_holders(deps.as_ref(), Role::DenomManager),
+            Vec::<String>::new()
+        );
+
+        let info = mock_info("denom_manager", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, set_denom_aliases).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_admin_action_timelock() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let admin_info = mock_info("admin", &[]);
+        let denom_manager_info = mock_info("denom_manager", &[]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::GrantRole {
+                role: Role::DenomManager,
+                address: "denom_manager".to_string(),
+            },
+        )
+        .unwrap();
+
+        // admin actions are executed directly without the delay
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::SetFixedPrices {
+                price_profile: None,
+                prices: vec![("uosmo".to_string(), Decimal::percent(150))],
+            },
+        )
+        .unwrap();
+
+        let delay = Uint64::from(3_600_000_000_000u64);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::SetAdminActionDelay { delay },
+        )
+        .unwrap();
+
+        let AdminActionDelayResponse {
+            delay: queried_delay,
+        } = from_json(query(deps.as_ref(), mock_env(), QueryMsg::AdminActionDelay {}).unwrap())
+            .unwrap();
+        assert_eq!(queried_delay, delay);
+
+        // the delay is bounded so that proposing can't overflow the executable time
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            ExecuteMsg::SetAdminActionDelay {
+                delay: MAX_ADMIN_ACTION_DELAY + Uint64::one(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AdminActionDelayTooLong {
+                delay: MAX_ADMIN_ACTION_DELAY + Uint64::one(),
+                max_delay: MAX_ADMIN_ACTION_DELAY,
+            }
+        );
+
+        let remove_all = ExecuteMsg::RemoveTrackedDenoms {
+            price_profile: None,
+            target: DenomRemovalTarget::All,
+        };
+        let propose = |action: ExecuteMsg| ExecuteMsg::ProposeAdminAction {
+            action: Box::new(action),
+        };
+        let query_pending = |deps: Deps| {
+            let PendingAdminActionsResponse { actions } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::PendingAdminActions {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            actions
+        };
+
+        // timelocked actions can't be executed directly
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            denom_manager_info.clone(),
+            remove_all.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AdminActionTimelocked {});
+
+        // so can't handing out the permissions to loosen the spend limits
+        for action in [
+            ExecuteMsg::GrantRole {
+                role: Role::DenomManager,
+                address: "new_denom_manager".to_string(),
+            },
+            ExecuteMsg::TransferAdmin {
+                address: "new_admin".to_string(),
+                expires_at: None,
+            },
+        ] {
+            let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), action).unwrap_err();
+            assert_eq!(err, ContractError::AdminActionTimelocked {});
+        }
+
+        // non timelocked actions can't be proposed
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            admin_info.clone(),
+            propose(ExecuteMsg::RefreshPrices {
+                price_profile: None,
+                denoms: None,
+                start_after: None,
+                limit: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AdminActionNotTimelocked {});
+
+        // proposer must be authorized for the action
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("non_admin", &[]),
+            propose(remove_all.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            denom_manager_info.clone(),
+            propose(remove_all.clone()),
+        )
+        .unwrap();
+        let executable_at = mock_env().block.time.plus_nanos(delay.u64());
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "propose_admin_action"),
+                attr("id", "0"),
+                attr("executable_at", executable_at.to_string())
+            ]
+        );
+        assert_eq!(
+            query_pending(deps.as_ref()),
+            vec![PendingAdminAction {
+                id: 0,
+                action: remove_all,
+                proposer: Addr::unchecked("denom_manager"),
+                proposed_at: mock_env().block.time,
+                executable_at,
+            }]
+        );
+
+        // only the proposer or the admin can execute the action, once the delay has passed
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("non_admin", &[]),
+            ExecuteMsg::ExecuteAdminAction { id: 0 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            denom_manager_info.clone(),
+            ExecuteMsg::ExecuteAdminAction { id: 0 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AdminActionNotExecutable {
+                id: 0,
+                executable_at
+            }
+        );
+
+        let mut env = mock_env();
+        env.block.time = executable_at;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::ExecuteAdminAction { id: 0 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "remove_tracked_denoms"),
+                attr("admin_action_id", "0")
+            ]
+        );
+        assert!(query_pending(deps.as_ref()).is_empty());
+        assert_eq!(
+            PRICE_INFOS
+                .keys(&deps.storage, None, None, Order::Ascending)
+                .count(),
+            0
+        );
+
+        // decreasing the delay is timelocked and can only be proposed by the admin
+        let decrease_delay = ExecuteMsg::SetAdminActionDelay {
+            delay: Uint64::zero(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            decrease_delay.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AdminActionTimelocked {});
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            denom_manager_info,
+            propose(decrease_delay.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            propose(decrease_delay),
+        )
+        .unwrap();
+        assert_eq!(query_pending(deps.as_ref())[0].id, 1);
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::CancelAdminAction { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![attr("action", "cancel_admin_action"), attr("id", "1")]
+        );
+        assert!(query_pending(deps.as_ref()).is_empty());
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            admin_info,
+            ExecuteMsg::ExecuteAdminAction { id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AdminActionNotFound { id: 1 });
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::GrantRole {
+                role: Role::Guardian,
+                address: "guardian".to_string(),
+            },
+        )
+        .unwrap();
+
+        let query_pause_state = |deps: Deps| {
+            let PauseStateResponse { pause_state } =
+                from_json(query(deps, mock_env(), QueryMsg::PauseState {}).unwrap()).unwrap();
+            pause_state
+        };
+        assert_eq!(query_pause_state(deps.as_ref()), None);
+
+        let pause = ExecuteMsg::Pause {
+            mode: PauseMode::Strict,
+            reason: "pricing compromised".to_string(),
+        };
+
+        // only the guardians or the admin can pause
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("non_guardian", &[]),
+            pause.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), pause).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "pause"),
+                attr("mode", "strict"),
+                attr("reason", "pricing compromised")
+            ]
+        );
+        assert_eq!(
+            query_pause_state(deps.as_ref()),
+            Some(PauseState {
+                mode: PauseMode::Strict,
+                reason: "pricing compromised".to_string(),
+                paused_by: Addr::unchecked("guardian"),
+                paused_at: mock_env().block.time,
+            })
+        );
+
+        // mode defaults to frozen
+        let pause: ExecuteMsg = from_json(r#"{"pause":{"reason":"pricing compromised"}}"#).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), pause).unwrap();
+        assert_eq!(
+            query_pause_state(deps.as_ref()).map(|pause_state| pause_state.mode),
+            Some(PauseMode::Frozen)
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("non_guardian", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        assert_eq!(query_pause_state(deps.as_ref()), None);
+    }
+
+    #[test]
+    fn test_governance_sudo() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
+
+        let conf = PriceResolutionConfig {
+            staleness_threshold: Uint64::from(3_600_000_000u64),
+            twap_duration: Uint64::from(3_600_000_000u64),
+            ..price_resolution_config(UUSDC)
+        };
+        let msg = InstantiateMsg {
+            price_resolution_config: conf.clone(),
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::RevokeAdmin {},
+        )
+        .unwrap();
+
+        // authenticator sudo messages are still accepted as is
+        let track_request = TrackRequest {
+            account: Addr::unchecked("account"),
+            fee_payer: Addr::unchecked("account"),
+            fee_granter: None,
+            fee: vec![],
+            authenticator_id: "2".to_string(),
+            msg: Any {
+                type_url: "".to_string(),
+                value: Binary::default(),
+            },
+            msg_index: 0,
+            authenticator_params: None,
+        };
+        assert_eq!(
+            from_json::<SudoMsg>(
+                to_json_vec(&cw_authenticator::AuthenticatorSudoMsg::Track(
+                    track_request.clone()
+                ))
+                .unwrap()
+            )
+            .unwrap(),
+            SudoMsg::Track(track_request)
+        );
+
+        // governance manages the contract without the admin
+        let res = sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetTrackedDenoms {
+                price_profile: None,
+                tracked_denoms: vec![TrackedDenom::fixed("uosmo", Decimal::percent(150))],
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_tracked_denoms"),
+                attr("authority", "governance")
+            ]
+        );
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uosmo").unwrap().price,
+            Decimal::percent(150)
+        );
+
+        let err = sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetPriceResolutionConfig {
+                price_profile: None,
+                price_resolution_config: PriceResolutionConfig {
+                    quote_denom: "uosmo".to_string(),
+                    ..conf.clone()
+                },
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::QuoteDenomChanged {
+                quote_denom: UUSDC.to_string()
+            }
+        );
+
+        let new_conf = PriceResolutionConfig {
+            staleness_threshold: Uint64::from(7_200_000_000u64),
+            ..conf
+        };
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetPriceResolutionConfig {
+                price_profile: None,
+                price_resolution_config: new_conf.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            PRICE_RESOLUTION_CONFIG.load(&deps.storage).unwrap(),
+            new_conf
+        );
+
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetAdmin {
+                address: Some("new_admin".to_string()),
+            },
+        )
+        .unwrap();
+        let AdminResponse { admin } =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Admin {}).unwrap()).unwrap();
+        assert_eq!(admin, Some("new_admin".to_string()));
+
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetAdmin { address: None },
+        )
+        .unwrap();
+        let AdminResponse { admin } =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Admin {}).unwrap()).unwrap();
+        assert_eq!(admin, None);
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
+
+        let conf = PriceResolutionConfig {
+            staleness_threshold: Uint64::from(3_600_000_000u64),
+            twap_duration: Uint64::from(3_600_000_000u64),
+            ..price_resolution_config(UUSDC)
+        };
+        let msg = InstantiateMsg {
+            price_resolution_config: conf.clone(),
+            tracked_denoms: vec![],
+            admin: Some(String::from("admin")),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::SetFixedPrices {
+                price_profile: None,
+                prices: vec![("uosmo".to_string(), Decimal::percent(150))],
+            },
+        )
+        .unwrap();
+
+        // permissionless operations are not recorded
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::RefreshPrices {
+                price_profile: None,
+                denoms: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::GrantRole {
+                role: Role::PriceManager,
+                address: "price_manager".to_string(),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("price_manager", &[]),
+            ExecuteMsg::SetPriceResolutionParams {
+                price_profile: None,
+                staleness_threshold: Some(Uint64::from(7_200_000_000u64)),
+                twap_duration: None,
+                twap_kind: None,
+                max_price_change_per_refresh: None,
+                block_time_behind_last_update: None,
+                min_pool_liquidity: None,
+                spot_price_fallback: None,
+                max_price_age: None,
+            },
+        )
+        .unwrap();
+
+        // removing all the tracked denoms records what's removed
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::RemoveTrackedDenoms {
+                price_profile: None,
+                target: DenomRemovalTarget::All,
+            },
+        )
+        .unwrap();
+
+        sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::SetAdmin {
+                address: Some("new_admin".to_string()),
+            },
+        )
+        .unwrap();
+
+        let query_audit_log = |deps: Deps, start_after: Option<u64>, limit: Option<u32>| {
+            let AuditLogResponse { entries } = from_json(
+                query(deps, mock_env(), QueryMsg::AuditLog { start_after, limit }).unwrap(),
+            )
+            .unwrap();
+            entries
+        };
+
+        let entry = |id: u64, sender: Option<&str>, action: &str, changes: Vec<AuditChange>| {
+            AuditLogEntry {
+                id,
+                sender: sender.map(Addr::unchecked),
+                action: action.to_string(),
+                block_time: mock_env().block.time,
+                changes,
+            }
+        };
+
+        assert_eq!(
+            query_audit_log(deps.as_ref(), None, None),
+            vec![
+                entry(
+                    0,
+                    Some("admin"),
+                    "set_fixed_prices",
+                    vec![
+                        AuditChange::TrackedDenom {
+                            price_profile: None,
+                            denom: "uosmo".to_string(),
+                            previous: None,
+                            new: Some(TrackedDenom::fixed("uosmo", Decimal::percent(150))),
+                        },
+                        AuditChange::Price {
+                            price_profile: None,
+                            denom: "uosmo".to_string(),
+                            previous: None,
+                            new: Some(Decimal::percent(150)),
+                        },
+                    ]
+                ),
+                entry(1, Some("admin"), "grant_role", vec![]),
+                entry(
+                    2,
+                    Some("price_manager"),
+                    "set_price_resolution_config",
+                    vec![AuditChange::PriceResolutionConfig {
+                        price_profile: None,
+                        previous: Some(conf.clone()),
+                        new: Some(PriceResolutionConfig {
+                            staleness_threshold: Uint64::from(7_200_000_000u64),
+                            ..conf
+                        }),
+                    }]
+                ),
+                entry(
+                    3,
+                    Some("admin"),
+                    "remove_tracked_denoms",
+                    vec![
+                        AuditChange::TrackedDenom {
+                            price_profile: None,
+                            denom: "uosmo".to_string(),
+                            previous: Some(TrackedDenom::fixed("uosmo", Decimal::percent(150))),
+                            new: None,
+                        },
+                        AuditChange::Price {
+                            price_profile: None,
+                            denom: "uosmo".to_string(),
+                            previous: Some(Decimal::percent(150)),
+                            new: None,
+                        },
+                    ]
+                ),
+                entry(4, None, "set_admin", vec![]),
+            ]
+        );
+
+        let entries = query_audit_log(deps.as_ref(), Some(1), Some(2));
+        assert_eq!(
+            entries.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let params = |time_limit: Option<TimeLimit>| SpendLimitParams {
+            limit: Uint128::from(1_000_000u128),
+            display_limit: None,
+            reset_period: Period::Day,
+            time_limit,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
+        };
+        let authenticator = move |id, params: SpendLimitParams| AccountAuthenticator {
+            id,
+            r#type: "CosmWasmAuthenticatorV1".to_string(),
+            config: to_json_vec(&CosmwasmAuthenticatorData {
+                contract: mock_env().contract.address.to_string(),
+                params: to_json_vec(&params).unwrap(),
+            })
+            .unwrap(),
+        };
+
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                get_authenticator_query_handler(Box::new(move |req| {
+                    match (req.account.as_str(), req.authenticator_id) {
+                        // still usable
+                        ("addr_a", 1) => ContractResult::Ok(GetAuthenticatorResponse {
+                            account_authenticator: Some(authenticator(
+                                1,
+                                params(Some(TimeLimit {
+                                    start: None,
+                                    end: mock_env().block.time,
+                                })),
+                            )),
+                        }),
+                        // time limit has ended
+                        ("addr_a", 2) => ContractResult::Ok(GetAuthenticatorResponse {
+                            account_authenticator: Some(authenticator(
+                                2,
+                                params(Some(TimeLimit {
+                                    start: None,
+                                    end: mock_env().block.time.minus_nanos(1),
+                                })),
+                            )),
+                        }),
+                        // removed authenticators
+                        ("addr_b", 3) | ("addr_e", 6) | ("addr_f", 7) => {
+                            ContractResult::Ok(GetAuthenticatorResponse {
+                                account_authenticator: Some(AccountAuthenticator::default()),
+                            })
+                        }
+                        _ => ContractResult::Err("query failed".to_string()),
+                    }
+                })),
+                get_authenticators_query_handler(Box::new(move |req| {
+                    match req.account.as_str() {
+                        // authenticator can't be queried but still exists
+                        "addr_c" => ContractResult::Ok(GetAuthenticatorsResponse {
+                            account_authenticators: vec![authenticator(4, params(None))],
+                        }),
+                        // removed authenticator that can't be queried
+                        "addr_d" => ContractResult::Ok(GetAuthenticatorsResponse {
+                            account_authenticators: vec![],
+                        }),
+                        _ => ContractResult::Err("query failed".to_string()),
+                    }
+                })),
+            ]),
+        );
+
+        let keys = [
+            ("addr_a", "1"),
+            ("addr_a", "2"),
+            ("addr_b", "3"),
+            ("addr_c", "4"),
+            ("addr_d", "5"),
+        ];
+        for (account, authenticator_id) in keys {
+            let key = (&Addr::unchecked(account), authenticator_id);
+            SPENDINGS
+                .save(deps.as_mut().storage, key, &Spending::default())
+                .unwrap();
+            PRE_EXEC_BALANCES
+                .save(deps.as_mut().storage, key, &vec![Coin::new(100, UUSDC)])
+                .unwrap();
+            UNTRACKED_SPENT_FEES
+                .save(
+                    deps.as_mut().storage,
+                    key,
+                    &UntrackedSpentFee::new(mock_env().block.time),
+                )
+                .unwrap();
+        }
+
+        // state left without a spending by the removals before it was cleared on removal
+        let orphaned_pre_exec_balance = (&Addr::unchecked("addr_e"), "6");
+        PRE_EXEC_BALANCES
+            .save(
+                deps.as_mut().storage,
+                orphaned_pre_exec_balance,
+                &vec![Coin::new(100, UUSDC)],
+            )
+            .unwrap();
+        let orphaned_untracked_spent_fee = (&Addr::unchecked("addr_f"), "7");
+        UNTRACKED_SPENT_FEES
+            .save(
+                deps.as_mut().storage,
+                orphaned_untracked_spent_fee,
+                &UntrackedSpentFee::new(mock_env().block.time),
+            )
+            .unwrap();
+
+        let mut prune = |start_after: Option<(String, String)>, limit: Option<u32>| {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::Prune { start_after, limit },
+            )
+            .unwrap();
+            from_json::<PruneResponse>(res.data.unwrap()).unwrap()
+        };
+
+        let res = prune(None, Some(2));
+        assert_eq!(
+            res,
+            PruneResponse {
+                pruned: vec![("addr_a".to_string(), "2".to_string())],
+                next_start_after: Some(("addr_a".to_string(), "2".to_string())),
+            }
+        );
+
+        let res = prune(res.next_start_after, None);
+        assert_eq!(
+            res,
+            PruneResponse {
+                pruned: vec![
+                    ("addr_b".to_string(), "3".to_string()),
+                    ("addr_d".to_string(), "5".to_string()),
+                    ("addr_e".to_string(), "6".to_string()),
+                    ("addr_f".to_string(), "7".to_string())
+                ],
+                next_start_after: None,
+            }
+        );
+
+        // authenticator that can't be queried is kept unless the account no longer has it
+        for (account, authenticator_id) in keys {
+            let key = (&Addr::unchecked(account), authenticator_id);
+            let is_kept = matches!(authenticator_id, "1" | "4");
+            assert_eq!(SPENDINGS.has(&deps.storage, key), is_kept);
+            assert_eq!(PRE_EXEC_BALANCES.has(&deps.storage, key), is_kept);
+            assert_eq!(UNTRACKED_SPENT_FEES.has(&deps.storage, key), is_kept);
+        }
+        assert!(!PRE_EXEC_BALANCES.has(&deps.storage, orphaned_pre_exec_balance));
+        assert!(!UNTRACKED_SPENT_FEES.has(&deps.storage, orphaned_untracked_spent_fee));
+    }
+
+    #[test]
+    fn test_no_admin() {
+        let mut deps =
+            mock_dependencies_with_balances(&[("creator", &[Coin::new(100000000, UUSDC)])]);
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: None,
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+
+        assert_eq!(admin, None);
+        assert_eq!(candidate, None);
+    }
+
+    #[test]
+    fn test_admin() {
+        let mut deps =
+            mock_dependencies_with_balances(&[("creator", &[Coin::new(100000000, UUSDC)])]);
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: Some("admin".to_string()),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+
+        assert_eq!(admin, Some("admin".to_string()));
+        assert_eq!(candidate, None);
+
+        // non admin can't transfer admin
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::TransferAdmin {
+            address: "new_admin".to_string(),
+            expires_at: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // admin can transfer admin
+        let info = mock_info("admin", &[]);
+        let msg = ExecuteMsg::TransferAdmin {
+            address: "new_admin".to_string(),
+            expires_at: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, Some("admin".to_string()));
+        assert_eq!(candidate, Some("new_admin".to_string()));
+
+        // non candidate can't claim admin
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ClaimAdminTransfer {};
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // candidate can claim admin
+        let info = mock_info("new_admin", &[]);
+        let msg = ExecuteMsg::ClaimAdminTransfer {};
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, Some("new_admin".to_string()));
+        assert_eq!(candidate, None);
+
+        // transfer again
+        let info = mock_info("new_admin", &[]);
+        let msg = ExecuteMsg::TransferAdmin {
+            address: "new_admin_2".to_string(),
+            expires_at: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, Some("new_admin".to_string()));
+        assert_eq!(candidate, Some("new_admin_2".to_string()));
+
+        // only candidate can reject admin transfer
+        let info = mock_info("new_admin", &[]);
+        let msg = ExecuteMsg::RejectAdminTransfer {};
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // candidate can reject admin transfer
+        let info = mock_info("new_admin_2", &[]);
+        let msg = ExecuteMsg::RejectAdminTransfer {};
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, Some("new_admin".to_string()));
+        assert_eq!(candidate, None);
+
+        // transfer again
+        let info = mock_info("new_admin", &[]);
+        let msg = ExecuteMsg::TransferAdmin {
+            address: "new_admin_2".to_string(),
+            expires_at: None,
+        };
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, Some("new_admin".to_string()));
+        assert_eq!(candidate, Some("new_admin_2".to_string()));
+
+        // only admin can cancel admin transfer
+        let info = mock_info("new_admin_2", &[]);
+        let msg = ExecuteMsg::CancelAdminTransfer {};
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // admin can cancel admin transfer
+        let info = mock_info("new_admin", &[]);
+        let msg = ExecuteMsg::CancelAdminTransfer {};
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, Some("new_admin".to_string()));
+        assert_eq!(candidate, None);
+
+        // only admin can revoke admin
+        let info = mock_info("new_admin_2", &[]);
+        let msg = ExecuteMsg::RevokeAdmin {};
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // admin can revoke admin
+        let info = mock_info("new_admin", &[]);
+        let msg = ExecuteMsg::RevokeAdmin {};
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let (admin, candidate) = query_admin_and_candidate(deps.as_ref());
+        assert_eq!(admin, None);
+        assert_eq!(candidate, None);
+    }
+
+    #[test]
+    fn test_admin_transfer_expiry() {
+        let mut deps = mock_dependencies_with_balances(&[("creator", &[Coin::new(100000, UUSDC)])]);
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config(UUSDC)
+            },
+            tracked_denoms: vec![],
+            admin: Some("admin".to_string()),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let expires_at = mock_env().block.time.plus_days(1);
+        let env_at = |time: Timestamp| {
+            let mut env = mock_env();
+            env.block.time = time;
+            env
+        };
+        let query_candidate = |deps: Deps, env: Env| -> AdminCandidateResponse {
+            from_json(query(deps, env, QueryMsg::AdminCandidate {}).unwrap()).unwrap()
+        };
+
+        // offer can't expire in the past
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::TransferAdmin {
+                address: "new_admin".to_string(),
+                expires_at: Some(mock_env().block.time),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AdminTransferExpired {
+                expires_at: mock_env().block.time
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::TransferAdmin {
+                address: "new_admin".to_string(),
+                expires_at: Some(expires_at),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            query_candidate(deps.as_ref(), env_at(expires_at)),
+            AdminCandidateResponse {
+                candidate: Some("new_admin".to_string()),
+                expires_at: Some(expires_at),
+            }
+        );
+
+        // expired offer can't be claimed and is no longer listed
+        let expired = expires_at.plus_nanos(1);
+        assert_eq!(
+            query_candidate(deps.as_ref(), env_at(expired)),
+            AdminCandidateResponse {
+                candidate: None,
+                expires_at: None,
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(expired),
+            mock_info("new_admin", &[]),
+            ExecuteMsg::ClaimAdminTransfer {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AdminTransferExpired { expires_at });
+
+        // state reverts to settled on the next admin update
+        execute(
+            deps.as_mut(),
+            env_at(expired),
+            mock_info("new_admin", &[]),
+            ExecuteMsg::RejectAdminTransfer {},
+        )
+        .unwrap();
+        assert_eq!(
+            ADMIN.load(&deps.storage).unwrap(),
+            Admin::Settled(Addr::unchecked("admin"))
+        );
+
+        // offer can be claimed until it expires
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::TransferAdmin {
+                address: "new_admin".to_string(),
+                expires_at: Some(expires_at),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env_at(expires_at),
+            mock_info("new_admin", &[]),
+            ExecuteMsg::ClaimAdminTransfer {},
+        )
+        .unwrap();
+        assert_eq!(
+            ADMIN.load(&deps.storage).unwrap(),
+            Admin::Settled(Addr::unchecked("new_admin"))
+        );
+    }
+
+    fn query_admin_and_candidate(deps: Deps) -> (Option<String>, Option<String>) {
+        let AdminResponse { admin } =
+            from_json(query(deps, mock_env(), QueryMsg::Admin {}).unwrap()).unwrap();
+
+        let AdminCandidateResponse { candidate, .. } =
+            from_json(query(deps, mock_env(), QueryMsg::AdminCandidate {}).unwrap()).unwrap();
+
+        (admin, candidate)
+    }
+
+    // TODO: test set tracked denoms
+    // test admin can set tracked denoms
+    // need to mock stargate query
+
+    #[test]
+    fn test_set_and_remove_tracked_denoms() {
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![
+                    (1, vec![coin(1_000_000, "uosmo"), coin(1_000_000, "uusdc")]),
+                    (2, vec![coin(1_000_000, "uatom"), coin(1_000_000, "uusdc")]),
+                    (3, vec![coin(1_000_000, "uion"), coin(1_000_000, "uusdc")]),
+                    (4, vec![coin(1_000_000, "ustars"), coin(1_000_000, "uusdc")]),
+                    (10, vec![coin(1_000_000, "uosmo"), coin(1_000_000, "uatom")]),
+                ]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", "uusdc") => "1.5",
+                        ("uatom", "uusdc") => "2.5",
+                        ("uion", "uusdc") => "3.5",
+                        ("ustars", "uusdc") => "4.5",
+                        ("uosmo", "uatom") => "0.5",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                staleness_threshold: Uint64::from(3_600_000_000u64),
+                twap_duration: Uint64::from(3_600_000_000u64),
+                ..price_resolution_config("uusdc")
+            },
+            tracked_denoms: vec![],
+            admin: Some("admin".to_string()),
+        };
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query tracked denoms
+        let TrackedDenomsResponse { tracked_denoms, .. } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms, vec![]);
+
+        // set tracked denoms
+        let tracked_denoms = vec![
+            tracked_denom(
+                "uatom",
+                vec![SwapAmountInRoute {
+                    pool_id: 2,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "uion",
+                vec![SwapAmountInRoute {
+                    pool_id: 3,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "ustars",
+                vec![SwapAmountInRoute {
+                    pool_id: 4,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+        ];
+        let info = mock_info("non_admin", &[]);
+        let msg = ExecuteMsg::SetTrackedDenoms {
+            price_profile: None,
+            tracked_denoms: tracked_denoms.clone(),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &[]);
+        let msg = ExecuteMsg::SetTrackedDenoms {
+            price_profile: None,
+            tracked_denoms: tracked_denoms.clone(),
+        };
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query tracked denoms
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms_response, tracked_denoms);
+
+        // remove tracked denoms
+        let info = mock_info("non_admin", &[]);
+        let msg = ExecuteMsg::RemoveTrackedDenoms {
+            price_profile: None,
+            target: DenomRemovalTarget::Partial(vec!["uatom".to_string(), "ustars".to_string()]),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query tracked denoms
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let expected = vec![
+            tracked_denom(
+                "uion",
+                vec![SwapAmountInRoute {
+                    pool_id: 3,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+        ];
+
+        assert_eq!(tracked_denoms_response, expected);
+
+        // set tracked denoms with replacement
+        let tracked_denoms = vec![
+            tracked_denom(
+                "uosmo",
+                vec![
+                    SwapAmountInRoute {
+                        pool_id: 10,
+                        token_out_denom: "uatom".to_string(),
+                    },
+                    SwapAmountInRoute {
+                        pool_id: 2,
+                        token_out_denom: "uusdc".to_string(),
+                    },
+                ],
+            ),
+            tracked_denom(
+                "uatom",
+                vec![SwapAmountInRoute {
+                    pool_id: 2,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "ustars",
+                vec![SwapAmountInRoute {
+                    pool_id: 4,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+        ];
+
+        let info = mock_info("admin", &[]);
+
+        let msg = ExecuteMsg::SetTrackedDenoms {
+            price_profile: None,
+            tracked_denoms: tracked_denoms.clone(),
+        };
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query tracked denoms
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let expected = vec![
+            tracked_denom(
+                "uatom",
+                vec![SwapAmountInRoute {
+                    pool_id: 2,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "uion",
+                vec![SwapAmountInRoute {
+                    pool_id: 3,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+            tracked_denom(
+                "uosmo",
+                vec![
+                    SwapAmountInRoute {
+                        pool_id: 10,
+                        token_out_denom: "uatom".to_string(),
+                    },
+                    SwapAmountInRoute {
+                        pool_id: 2,
+                        token_out_denom: "uusdc".to_string(),
+                    },
+                ],
+            ),
+            tracked_denom(
+                "ustars",
+                vec![SwapAmountInRoute {
+                    pool_id: 4,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            ),
+        ];
+        assert_eq!(tracked_denoms_response, expected);
+
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: Some(2),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms_response, expected[..2]);
+
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: Some("uion".to_string()),
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms_response, expected[2..3]);
+
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: Some("uion".to_string()),
+                    limit: Some(2),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms_response, expected[2..]);
+
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: Some("uion".to_string()),
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms_response, expected[2..]);
+
+        // remove all
+        let info = mock_info("admin", &[]);
+
+        let msg = ExecuteMsg::RemoveTrackedDenoms {
+            price_profile: None,
+            target: DenomRemovalTarget::All,
+        };
+
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query tracked denoms
+        let TrackedDenomsResponse {
+            tracked_denoms: tracked_denoms_response,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(tracked_denoms_response, vec![]);
+    }
+
+    #[test]
+    fn test_accept_prices() {
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", "uusdc") => "1.5",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let conf = PriceResolutionConfig {
+            max_price_change_per_refresh: Some(Decimal::percent(10)),
+            ..price_resolution_config("uusdc")
+        };
+
+        let msg = InstantiateMsg {
+            price_resolution_config: conf.clone(),
+            tracked_denoms: vec![tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            )],
+            admin: Some("admin".to_string()),
+        };
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // simulate stale cached price that deviates too much from the current one
+        let env = mock_env();
+        let cached_price_info = PRICE_INFOS
+            .update(deps.as_mut().storage, "uosmo", |price_info| {
+                let price_info = price_info.unwrap();
+                StdResult::Ok(PriceInfo {
+                    price: Decimal::one(),
+                    last_updated_time: env.block.time.minus_seconds(7200),
+                    ..price_info
+                })
+            })
+            .unwrap();
+
+        let err = get_and_cache_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_mut(),
+            &conf,
+            env.block.time,
+            "uosmo",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            PriceError::PriceDeviationExceeded {
+                denom: "uosmo".to_string(),
+                cached_price: Decimal::one(),
+                new_price: "1.5".parse().unwrap(),
+                max_price_change: Decimal::percent(10),
+            }
+        );
+
+        // only admin can accept prices
+        let msg = ExecuteMsg::AcceptPrices {
+            price_profile: None,
+            denoms: vec!["uosmo".to_string()],
+        };
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // untracked denom can't be accepted
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::AcceptPrices {
+                price_profile: None,
+                denoms: vec!["uatom".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDenom {
+                denom: "uatom".to_string()
+            }
+        );
+
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uosmo").unwrap(),
+            PriceInfo {
+                price: "1.5".parse().unwrap(),
+                last_updated_time: env.block.time,
+                ..cached_price_info
+            }
+        );
+    }
+
+    #[test]
+    fn test_accept_prices_with_admin_action_delay() {
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|_| {
+                    ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: "1.5".to_string(),
+                    })
+                })),
+            ]),
+        );
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                max_price_change_per_refresh: Some(Decimal::percent(10)),
+                ..price_resolution_config("uusdc")
+            },
+            tracked_denoms: vec![tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            )],
+            admin: Some("admin".to_string()),
+        };
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let delay = Uint64::from(3_600_000_000_000u64);
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::SetAdminActionDelay { delay },
+        )
+        .unwrap();
+
+        let env = mock_env();
+        let set_cached_price = |storage: &mut dyn Storage, price: Decimal| {
+            PRICE_INFOS
+                .update(storage, "uosmo", |price_info| {
+                    StdResult::Ok(PriceInfo {
+                        price,
+                        last_updated_time: env.block.time.minus_seconds(7200),
+                        ..price_info.unwrap()
+                    })
+                })
+                .unwrap();
+        };
+        let accept_prices = ExecuteMsg::AcceptPrices {
+            price_profile: None,
+            denoms: vec!["uosmo".to_string()],
+        };
+
+        // raising the price also raises the credit for receiving the denom,
+        // so accepting the price must be proposed whichever way it moves
+        for cached_price in [Decimal::one(), Decimal::percent(200)] {
+            set_cached_price(deps.as_mut().storage, cached_price);
+            let info = mock_info("admin", &[]);
+            let err = execute(deps.as_mut(), env.clone(), info, accept_prices.clone()).unwrap_err();
+            assert_eq!(err, ContractError::AdminActionTimelocked {});
+        }
+
+        set_cached_price(deps.as_mut().storage, Decimal::percent(200));
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::ProposeAdminAction {
+                action: Box::new(accept_prices),
+            },
+        )
+        .unwrap();
+
+        let mut env = env;
+        env.block.time = env.block.time.plus_nanos(delay.u64());
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::ExecuteAdminAction { id: 0 },
+        )
+        .unwrap();
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uosmo").unwrap().price,
+            "1.5".parse::<Decimal>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_auto_track_denom() {
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![
+                    (1, vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")]),
+                    (2, vec![coin(1_000_000, "uatom"), coin(10_000_000, "uusdc")]),
+                ]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", "uusdc") => "1.5",
+                        ("uatom", "uusdc") => "10",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let msg = InstantiateMsg {
+            price_resolution_config: price_resolution_config("uusdc"),
+            tracked_denoms: vec![],
+            admin: Some("admin".to_string()),
+        };
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only admin can auto track denom
+        let info = mock_info("non_admin", &[]);
+        let msg = ExecuteMsg::AutoTrackDenom {
+            price_profile: None,
+            denom: "uosmo".to_string(),
+            max_hops: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "auto_track_denom"),
+                attr("denom", "uosmo"),
+                attr("pool_ids", "1"),
+            ]
+        );
+
+        let price_info = PRICE_INFOS.load(&deps.storage, "uosmo").unwrap();
+        assert_eq!(price_info.price, "1.5".parse::<Decimal>().unwrap());
+        assert_eq!(
+            price_info.swap_routes,
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uusdc".to_string(),
+            }]
+        );
+
+        // no swap routes to quote denom
+        let info = mock_info("admin", &[]);
+        let msg = ExecuteMsg::AutoTrackDenom {
+            price_profile: None,
+            denom: "ujuno".to_string(),
+            max_hops: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriceResolutionError(PriceError::NoSwapRoutesFound {
+                denom: "ujuno".to_string(),
+                quote_denom: "uusdc".to_string(),
+            })
+        );
+        assert_eq!(PRICE_INFOS.may_load(&deps.storage, "ujuno").unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_fixed_prices() {
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", "uusdc") => "1.5",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let uosmo = tracked_denom(
+            "uosmo",
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uusdc".to_string(),
+            }],
+        );
+
+        let msg = InstantiateMsg {
+            price_resolution_config: price_resolution_config("uusdc"),
+            tracked_denoms: vec![uosmo.clone()],
+            admin: Some("admin".to_string()),
+        };
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetFixedPrices {
+            price_profile: None,
+            prices: vec![
+                ("uusdt".to_string(), Decimal::one()),
+                ("uusdc.axl".to_string(), Decimal::percent(99)),
+            ],
+        };
+
+        // only admin can set fixed prices
+        let info = mock_info("non_admin", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // quote denom can't have fixed price
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::SetFixedPrices {
+                price_profile: None,
+                prices: vec![("uusdc".to_string(), Decimal::one())],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DuplicatedDenom {
+                denom: "uusdc".to_string()
+            }
+        );
+
+        let info = mock_info("admin", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // fixed prices are listed along with the swap routes tracked denoms
+        let TrackedDenomsResponse { tracked_denoms, .. } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TrackedDenoms {
+                    price_profile: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let fixed = TrackedDenom::fixed;
+
+        assert_eq!(
+            tracked_denoms,
+            vec![
+                uosmo,
+                fixed("uusdc.axl", Decimal::percent(99)),
+                fixed("uusdt", Decimal::one()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_quote_denom() {
+        let routed_denom = |denom: &str, pool_id: u64, token_out_denom: &str| {
+            tracked_denom(
+                denom,
+                vec![SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: token_out_denom.to_string(),
+                }],
+            )
+        };
+        let fixed = TrackedDenom::fixed;
+
+        let account = Addr::unchecked("account");
+        let setup = || {
+            let mut deps = mock_dependencies_with_stargate_querier(
+                &[
+                    ("creator", &[Coin::new(100000, "uusdc")]),
+                    ("holder", &[Coin::new(100000, "unoble")]),
+                ],
+                combine_query_handlers(vec![
+                    balancer_pools_query_handler(vec![
+                        (1, vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")]),
+                        (2, vec![coin(1_000_000, "uosmo"), coin(1_400_000, "unoble")]),
+                        (3, vec![coin(1_000_000, "uusdc"), coin(990_000, "unoble")]),
+                    ]),
+                    arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                        let base_asset = req.base_asset.as_str();
+                        let quote_asset = req.quote_asset.as_str();
+
+                        let arithmetic_twap = match (req.pool_id, base_asset, quote_asset) {
+                            (1, "uosmo", "uusdc") => "1.5",
+                            (2, "uosmo", "unoble") => "1.4",
+                            (3, "uusdc", "unoble") => "0.99",
+                            _ => return ContractResult::Err("Price not found".to_string()),
+                        }
+                        .to_string();
+
+                        ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                    })),
+                ]),
+            );
+
+            let msg = InstantiateMsg {
+                price_resolution_config: price_resolution_config("uusdc"),
+                tracked_denoms: vec![
+                    routed_denom("uosmo", 1, "uusdc"),
+                    fixed("uusdt", Decimal::one()),
+                ],
+                admin: Some("admin".to_string()),
+            };
+
+            let info = mock_info("creator", &[]);
+            instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            SPENDINGS
+                .save(
+                    deps.as_mut().storage,
+                    (&account, "1"),
+                    &Spending {
+                        value_spent_in_period: Uint128::new(1_000),
+                        fee_spent_in_period: Uint128::zero(),
+                        last_spent_at: mock_env().block.time,
+                        limit_conversion_rate: None,
+                        display_limit_exponent: None,
+                        price_profile: None,
+                        quote_denom_migrations_applied: 0,
+                    },
+                )
+                .unwrap();
+
+            deps
+        };
+
+        let mut deps = setup();
+
+        let migrate = |quote_denom: &str, conversion_rate: &str, tracked_denoms| {
+            ExecuteMsg::MigrateQuoteDenom {
+                price_profile: None,
+                quote_denom: quote_denom.to_string(),
+                conversion_rate: conversion_rate.parse().unwrap(),
+                tracked_denoms,
+            }
+        };
+
+        // only admin can migrate quote denom
+        let info = mock_info("non_admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            migrate("unoble", "0.99", vec![]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            migrate("unoble", "0", vec![]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroConversionRate {});
+
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            migrate("uusdc", "1", vec![]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::QuoteDenomUnchanged {
+                quote_denom: "uusdc".to_string()
+            }
+        );
+
+        // new quote denom must have supply
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            migrate("unnoble", "0.99", vec![]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDenom {
+                denom: "unnoble".to_string()
+            }
+        );
+
+        // denoms priced by twap must be given swap routes to the new quote denom
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            setup().as_mut(),
+            mock_env(),
+            info,
+            migrate("unoble", "0.99", vec![]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MissingSwapRoutesToQuoteDenom {
+                quote_denom: "unoble".to_string(),
+                denoms: vec!["uosmo".to_string()],
+            }
+        );
+
+        // given swap routes must end with the new quote denom
+        let info = mock_info("admin", &[]);
+        let err = execute(
+            setup().as_mut(),
+            mock_env(),
+            info,
+            migrate("unoble", "0.99", vec![routed_denom("uosmo", 1, "uusdc")]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriceResolutionError(PriceError::SwapRoutesMustEndWithQuoteDenom {
+                quote_denom: "unoble".to_string(),
+                swap_routes: routed_denom("uosmo", 1, "uusdc").swap_routes,
+            })
+        );
+
+        let info = mock_info("admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            migrate(
+                "unoble",
+                "0.99",
+                vec![
+                    routed_denom("uosmo", 2, "unoble"),
+                    routed_denom("uusdc", 3, "unoble"),
+                ],
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                ("action", "migrate_quote_denom"),
+                ("quote_denom", "unoble"),
+                ("conversion_rate", "0.99"),
+            ]
+        );
 
-        // query tracked denoms
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
+        let PriceResolutionConfigResponse {
+            price_resolution_config,
         } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
-                QueryMsg::TrackedDenoms {
-                    start_after: None,
-                    limit: None,
+                QueryMsg::PriceResolutionConfig {
+                    price_profile: None,
                 },
             )
             .unwrap(),
         )
         .unwrap();
-
-        let expected = vec![
-            TrackedDenom {
-                denom: "uion".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 3,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-            TrackedDenom {
-                denom: "uosmo".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 1,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-        ];
-
-        assert_eq!(tracked_denoms_response, expected);
-
-        // set tracked denoms with replacement
-        let tracked_denoms = vec![
-            TrackedDenom {
-                denom: "uosmo".to_string(),
-                swap_routes: vec![
-                    SwapAmountInRoute {
-                        pool_id: 10,
-                        token_out_denom: "uatom".to_string(),
-                    },
-                    SwapAmountInRoute {
-                        pool_id: 2,
-                        token_out_denom: "uusdc".to_string(),
-                    },
-                ],
+        assert_eq!(price_resolution_config.quote_denom, "unoble");
+
+        // rescaled denoms and the conversion rate are recorded in the audit log
+        let (_, entry) = AUDIT_LOG.last(&deps.storage).unwrap().unwrap();
+        assert_eq!(entry.action, "migrate_quote_denom");
+        for change in [
+            AuditChange::TrackedDenom {
+                price_profile: None,
+                denom: "uusdt".to_string(),
+                previous: Some(fixed("uusdt", Decimal::one())),
+                new: Some(fixed("uusdt", "0.99".parse().unwrap())),
             },
-            TrackedDenom {
-                denom: "uatom".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 2,
-                    token_out_denom: "uusdc".to_string(),
-                }],
+            AuditChange::Price {
+                price_profile: None,
+                denom: "uusdt".to_string(),
+                previous: Some(Decimal::one()),
+                new: Some("0.99".parse().unwrap()),
             },
-            TrackedDenom {
-                denom: "ustars".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 4,
-                    token_out_denom: "uusdc".to_string(),
-                }],
+            AuditChange::QuoteDenomMigration {
+                price_profile: None,
+                conversion_rate: "0.99".parse().unwrap(),
             },
-        ];
-
-        let info = mock_info("admin", &[]);
-
-        let msg = ExecuteMsg::SetTrackedDenoms {
-            tracked_denoms: tracked_denoms.clone(),
-        };
-
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        ] {
+            assert!(entry.changes.contains(&change), "{change:?} not recorded");
+        }
 
-        // query tracked denoms
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
+        let TrackedDenomsResponse { tracked_denoms, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::TrackedDenoms {
+                    price_profile: None,
                     start_after: None,
                     limit: None,
                 },
@@ -1606,138 +5797,371 @@ mod tests {
             .unwrap(),
         )
         .unwrap();
+        assert_eq!(
+            tracked_denoms,
+            vec![
+                routed_denom("uosmo", 2, "unoble"),
+                routed_denom("uusdc", 3, "unoble"),
+                fixed("uusdt", "0.99".parse().unwrap()),
+            ]
+        );
 
-        let expected = vec![
-            TrackedDenom {
-                denom: "uatom".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 2,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-            TrackedDenom {
-                denom: "uion".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 3,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-            TrackedDenom {
-                denom: "uosmo".to_string(),
-                swap_routes: vec![
-                    SwapAmountInRoute {
-                        pool_id: 10,
-                        token_out_denom: "uatom".to_string(),
-                    },
-                    SwapAmountInRoute {
-                        pool_id: 2,
-                        token_out_denom: "uusdc".to_string(),
-                    },
-                ],
-            },
-            TrackedDenom {
-                denom: "ustars".to_string(),
-                swap_routes: vec![SwapAmountInRoute {
-                    pool_id: 4,
-                    token_out_denom: "uusdc".to_string(),
-                }],
-            },
-        ];
-        assert_eq!(tracked_denoms_response, expected);
+        // spending is rescaled and the limit of the existing authenticator is converted once loaded
+        let mut spending = SPENDINGS.load(&deps.storage, (&account, "1")).unwrap();
+        assert_eq!(spending.value_spent_in_period, Uint128::new(1_000));
 
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
+        PriceProfile::new(None)
+            .migrate_spending(&deps.storage, &mut spending)
+            .unwrap();
+        assert_eq!(
+            spending,
+            Spending {
+                value_spent_in_period: Uint128::new(990),
+                fee_spent_in_period: Uint128::zero(),
+                last_spent_at: mock_env().block.time,
+                limit_conversion_rate: Some("0.99".parse().unwrap()),
+                display_limit_exponent: None,
+                price_profile: None,
+                quote_denom_migrations_applied: 1,
+            }
+        );
+
+        // previous quote denom is tracked at the conversion rate if not overridden
+        let mut deps = setup();
+        let info = mock_info("admin", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            migrate("unoble", "0.99", vec![routed_denom("uosmo", 2, "unoble")]),
+        )
+        .unwrap();
+
+        let TrackedDenomsResponse { tracked_denoms, .. } = from_json(
             query(
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::TrackedDenoms {
+                    price_profile: None,
                     start_after: None,
-                    limit: Some(2),
+                    limit: None,
                 },
             )
             .unwrap(),
         )
         .unwrap();
+        assert_eq!(
+            tracked_denoms,
+            vec![
+                routed_denom("uosmo", 2, "unoble"),
+                fixed("uusdc", "0.99".parse().unwrap()),
+                fixed("uusdt", "0.99".parse().unwrap()),
+            ]
+        );
+    }
 
-        assert_eq!(tracked_denoms_response, expected[..2]);
+    #[test]
+    fn test_refresh_prices() {
+        let instantiated_at = mock_env().block.time;
 
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
-            query(
-                deps.as_ref(),
-                mock_env(),
-                QueryMsg::TrackedDenoms {
-                    start_after: Some("uion".to_string()),
-                    limit: Some(1),
-                },
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![
+                    (1, vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")]),
+                    (2, vec![coin(1_000_000, "uatom"), coin(10_000_000, "uusdc")]),
+                    (3, vec![coin(1_000_000, "ustars"), coin(100_000, "uusdc")]),
+                ]),
+                arithmetic_twap_to_now_query_handler(Box::new(move |req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+                    let refreshing =
+                        req.start_time.clone().unwrap().seconds as u64 > instantiated_at.seconds();
+
+                    let arithmetic_twap = match (base_asset, quote_asset, refreshing) {
+                        ("uosmo", "uusdc", false) => "1.5",
+                        ("uosmo", "uusdc", true) => "1.6",
+                        ("uatom", "uusdc", false) => "10",
+                        ("ustars", "uusdc", _) => "0.1",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let tracked_denom = |denom: &str, pool_id: u64| {
+            tracked_denom(
+                denom,
+                vec![SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: "uusdc".to_string(),
+                }],
             )
-            .unwrap(),
+        };
+
+        let msg = InstantiateMsg {
+            price_resolution_config: price_resolution_config("uusdc"),
+            tracked_denoms: vec![
+                tracked_denom("uosmo", 1),
+                tracked_denom("uatom", 2),
+                TrackedDenom {
+                    staleness_threshold: Some(Uint64::from(86_400_000_000_000u64)),
+                    ..tracked_denom("ustars", 3)
+                },
+            ],
+            admin: Some("admin".to_string()),
+        };
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = instantiated_at.plus_hours(2);
+
+        // anyone can refresh prices
+        let info = mock_info("anyone", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::RefreshPrices {
+                price_profile: None,
+                denoms: None,
+                start_after: None,
+                limit: None,
+            },
         )
         .unwrap();
 
-        assert_eq!(tracked_denoms_response, expected[2..3]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                ("action", "refresh_prices"),
+                ("refreshed", "1"),
+                ("stale", "0"),
+                ("failed", "1")
+            ]
+        );
 
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
-            query(
-                deps.as_ref(),
-                mock_env(),
-                QueryMsg::TrackedDenoms {
-                    start_after: Some("uion".to_string()),
+        let RefreshPricesResponse { results, .. } = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (
+                    "uatom".to_string(),
+                    PriceRefreshStatus::Failed {
+                        error: PriceError::twap_query_error(
+                            2,
+                            "uatom",
+                            "uusdc",
+                            env.block.time.minus_hours(1)
+                        )
+                        .to_string()
+                    }
+                ),
+                (
+                    "uosmo".to_string(),
+                    PriceRefreshStatus::Refreshed {
+                        price: "1.6".parse().unwrap()
+                    }
+                ),
+                (
+                    "ustars".to_string(),
+                    PriceRefreshStatus::Fresh {
+                        price: "0.1".parse().unwrap()
+                    }
+                ),
+            ]
+        );
+
+        // refresh the tracked denoms page by page
+        let mut refresh_page = |start_after: Option<&str>| {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::RefreshPrices {
+                    price_profile: None,
+                    denoms: None,
+                    start_after: start_after.map(String::from),
                     limit: Some(2),
                 },
             )
-            .unwrap(),
-        )
-        .unwrap();
+            .unwrap();
 
-        assert_eq!(tracked_denoms_response, expected[2..]);
+            let RefreshPricesResponse {
+                results,
+                next_start_after,
+            } = from_json(res.data.unwrap()).unwrap();
+            let denoms = results
+                .into_iter()
+                .map(|(denom, _)| denom)
+                .collect::<Vec<_>>();
 
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
-            query(
-                deps.as_ref(),
-                mock_env(),
-                QueryMsg::TrackedDenoms {
-                    start_after: Some("uion".to_string()),
-                    limit: None,
-                },
+            (denoms, next_start_after)
+        };
+
+        assert_eq!(
+            refresh_page(None),
+            (
+                vec!["uatom".to_string(), "uosmo".to_string()],
+                Some("uosmo".to_string())
             )
-            .unwrap(),
+        );
+        assert_eq!(
+            refresh_page(Some("uosmo")),
+            (vec!["ustars".to_string()], None)
+        );
+
+        // failed refresh keeps the cached price
+        let uatom = PRICE_INFOS.load(&deps.storage, "uatom").unwrap();
+        assert_eq!(uatom.price, Decimal::from_atomics(10u128, 0).unwrap());
+        assert_eq!(uatom.last_updated_time, instantiated_at);
+
+        let uosmo = PRICE_INFOS.load(&deps.storage, "uosmo").unwrap();
+        assert_eq!(uosmo.price, "1.6".parse::<Decimal>().unwrap());
+        assert_eq!(uosmo.last_updated_time, env.block.time);
+
+        // refresh only the requested denoms
+        let info = mock_info("anyone", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::RefreshPrices {
+                price_profile: None,
+                denoms: Some(vec!["uosmo".to_string(), "untracked".to_string()]),
+                start_after: None,
+                limit: None,
+            },
         )
         .unwrap();
 
-        assert_eq!(tracked_denoms_response, expected[2..]);
+        let RefreshPricesResponse { results, .. } = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (
+                    "uosmo".to_string(),
+                    PriceRefreshStatus::Fresh {
+                        price: "1.6".parse().unwrap()
+                    }
+                ),
+                ("untracked".to_string(), PriceRefreshStatus::NotTracked {}),
+            ]
+        );
+    }
 
-        // remove all
-        let info = mock_info("admin", &[]);
+    #[test]
+    fn test_refresh_prices_keeps_stale_price_within_max_price_age() {
+        let instantiated_at = mock_env().block.time;
 
-        let msg = ExecuteMsg::RemoveTrackedDenoms {
-            target: DenomRemovalTarget::All,
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[("creator", &[Coin::new(100000, "uusdc")])],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    2,
+                    vec![coin(1_000_000, "uatom"), coin(10_000_000, "uusdc")],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(move |req| {
+                    let refreshing =
+                        req.start_time.clone().unwrap().seconds as u64 > instantiated_at.seconds();
+
+                    if refreshing {
+                        return ContractResult::Err("Price not found".to_string());
+                    }
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: "10".to_string(),
+                    })
+                })),
+            ]),
+        );
+
+        let msg = InstantiateMsg {
+            price_resolution_config: PriceResolutionConfig {
+                max_price_age: Some(Uint64::from(3 * 3_600_000_000_000u64)),
+                ..price_resolution_config("uusdc")
+            },
+            tracked_denoms: vec![tracked_denom(
+                "uatom",
+                vec![SwapAmountInRoute {
+                    pool_id: 2,
+                    token_out_denom: "uusdc".to_string(),
+                }],
+            )],
+            admin: Some("admin".to_string()),
         };
 
-        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // query tracked denoms
-        let TrackedDenomsResponse {
-            tracked_denoms: tracked_denoms_response,
-        } = from_json(
-            query(
-                deps.as_ref(),
-                mock_env(),
-                QueryMsg::TrackedDenoms {
+        let refresh_prices = |deps: DepsMut, hours: u64| {
+            let mut env = mock_env();
+            env.block.time = instantiated_at.plus_hours(hours);
+
+            let res = execute(
+                deps,
+                env,
+                mock_info("anyone", &[]),
+                ExecuteMsg::RefreshPrices {
+                    price_profile: None,
+                    denoms: None,
                     start_after: None,
                     limit: None,
                 },
             )
-            .unwrap(),
-        )
-        .unwrap();
+            .unwrap();
 
-        assert_eq!(tracked_denoms_response, vec![]);
+            let count = |key: &str| {
+                res.attributes
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .unwrap()
+                    .value
+                    .clone()
+            };
+            let counts = (count("stale"), count("failed"));
+            let RefreshPricesResponse { results, .. } = from_json(res.data.unwrap()).unwrap();
+
+            (counts, results)
+        };
+
+        // stale price is kept within max price age and is not counted as failed
+        assert_eq!(
+            refresh_prices(deps.as_mut(), 2),
+            (
+                ("1".to_string(), "0".to_string()),
+                vec![(
+                    "uatom".to_string(),
+                    PriceRefreshStatus::Stale {
+                        price: Decimal::from_atomics(10u128, 0).unwrap()
+                    }
+                )]
+            )
+        );
+
+        // beyond max price age, the refresh fails
+        assert_eq!(
+            refresh_prices(deps.as_mut(), 3),
+            (
+                ("0".to_string(), "1".to_string()),
+                vec![(
+                    "uatom".to_string(),
+                    PriceRefreshStatus::Failed {
+                        error: PriceError::twap_query_error(
+                            2,
+                            "uatom",
+                            "uusdc",
+                            instantiated_at.plus_hours(2)
+                        )
+                        .to_string()
+                    }
+                )]
+            )
+        );
     }
 }
@@ -0,0 +1,183 @@
+use cosmwasm_std::{Decimal, Deps, StdError, StdResult, Uint128};
+use cw_storage_plus::Map;
+use osmosis_std::types::cosmos::bank::v1beta1::BankQuerier;
+
+/// Exponents of the display units of the denoms set by the admin,
+/// these take precedence over the exponents from the bank denom metadata.
+pub type DenomExponentStore<'a> = Map<'a, &'a str, u32>;
+
+/// Maximum supported exponent, bounded by the decimal places of [`Decimal`]
+/// so that any amount in base units can be expressed in display units.
+pub const MAX_DENOM_EXPONENT: u32 = Decimal::DECIMAL_PLACES;
+
+/// Get the exponent of the display unit of the denom, e.g. 6 for `uusdc` displayed as `usdc`.
+/// Falls back to the bank denom metadata if the exponent is not set in the store.
+/// Returns `None` if the exponent is unknown or greater than [`MAX_DENOM_EXPONENT`].
+pub fn get_denom_exponent(
+    deps: Deps,
+    denom_exponent_store: &DenomExponentStore,
+    denom: &str,
+) -> StdResult<Option<u32>> {
+    if let Some(exponent) = denom_exponent_store.may_load(deps.storage, denom)? {
+        return Ok(Some(exponent));
+    }
+
+    // the query fails if the denom has no metadata, which means the exponent is unknown
+    let Ok(response) = BankQuerier::new(&deps.querier).denom_metadata(denom.to_string()) else {
+        return Ok(None);
+    };
+
+    Ok(response
+        .metadata
+        .and_then(|metadata| {
+            metadata
+                .denom_units
+                .into_iter()
+                .find(|unit| unit.denom == metadata.display)
+                .map(|unit| unit.exponent)
+        })
+        .filter(|exponent| *exponent <= MAX_DENOM_EXPONENT))
+}
+
+/// Convert an amount in base units to display units, e.g. 1_500_000 uusdc to 1.5 usdc.
+pub fn to_display_amount(amount: Uint128, exponent: u32) -> StdResult<Decimal> {
+    Decimal::from_atomics(amount, exponent).map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+/// Convert an amount in display units to base units, e.g. 1.5 usdc to 1_500_000 uusdc.
+/// Rounds down to never allow more than the amount in display units.
+pub fn from_display_amount(amount: Decimal, exponent: u32) -> StdResult<Uint128> {
+    // scale down the atomics instead of scaling up the decimal, which overflows for large exponents
+    let scale_down = Decimal::DECIMAL_PLACES
+        .checked_sub(exponent)
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Exponent {exponent} exceeds the maximum of {MAX_DENOM_EXPONENT}"
+            ))
+        })?;
+
+    Ok(amount
+        .atomics()
+        .checked_div(Uint128::new(10).checked_pow(scale_down)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_dependencies, ContractResult};
+    use osmosis_std::types::cosmos::bank::v1beta1::{
+        DenomUnit, Metadata, QueryDenomMetadataResponse,
+    };
+    use rstest::rstest;
+
+    use crate::test_helper::mock_stargate_querier::{
+        denom_metadata_query_handler, mock_dependencies_with_stargate_querier,
+    };
+
+    use super::*;
+
+    const DENOM_EXPONENTS: DenomExponentStore = Map::new("denom_exponents");
+
+    #[rstest]
+    #[case::from_store("uusdc", Some(8), Some(8))]
+    #[case::from_bank_metadata("uusdc", None, Some(6))]
+    #[case::display_unit_not_listed("uatom", None, None)]
+    #[case::exponent_too_large("wei", None, None)]
+    #[case::no_metadata("uosmo", None, None)]
+    #[case::no_metadata_but_stored("uosmo", Some(6), Some(6))]
+    fn test_get_denom_exponent(
+        #[case] denom: &str,
+        #[case] stored_exponent: Option<u32>,
+        #[case] expected: Option<u32>,
+    ) {
+        let metadata = |base: &str, display: &str, units: Vec<(&str, u32)>| Metadata {
+            base: base.to_string(),
+            display: display.to_string(),
+            denom_units: units
+                .into_iter()
+                .map(|(denom, exponent)| DenomUnit {
+                    denom: denom.to_string(),
+                    exponent,
+                    aliases: vec![],
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            denom_metadata_query_handler(Box::new(move |req| {
+                let metadata = match req.denom.as_str() {
+                    "uusdc" => metadata("uusdc", "usdc", vec![("uusdc", 0), ("usdc", 6)]),
+                    "uatom" => metadata("uatom", "atom", vec![("uatom", 0)]),
+                    "wei" => metadata("wei", "eth", vec![("wei", 0), ("eth", 19)]),
+                    _ => return ContractResult::Err("metadata not found".to_string()),
+                };
+
+                ContractResult::Ok(QueryDenomMetadataResponse {
+                    metadata: Some(metadata),
+                })
+            })),
+        );
+
+        if let Some(exponent) = stored_exponent {
+            DENOM_EXPONENTS
+                .save(&mut deps.storage, denom, &exponent)
+                .unwrap();
+        }
+
+        assert_eq!(
+            get_denom_exponent(deps.as_ref(), &DENOM_EXPONENTS, denom).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_get_denom_exponent_without_stargate() {
+        let deps = mock_dependencies();
+        assert_eq!(
+            get_denom_exponent(deps.as_ref(), &DENOM_EXPONENTS, "uusdc").unwrap(),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case(1_500_000, 6, "1.5")]
+    #[case(1, 6, "0.000001")]
+    #[case(0, 6, "0")]
+    #[case(100, 0, "100")]
+    #[case(1, 18, "0.000000000000000001")]
+    #[case(1_000_000_000_000_000_000_000, 18, "1000")]
+    #[case(u128::MAX, 18, "340282366920938463463.374607431768211455")]
+    fn test_display_amount_conversion(
+        #[case] amount: u128,
+        #[case] exponent: u32,
+        #[case] display_amount: &str,
+    ) {
+        let display_amount: Decimal = display_amount.parse().unwrap();
+
+        assert_eq!(
+            to_display_amount(Uint128::new(amount), exponent).unwrap(),
+            display_amount
+        );
+        assert_eq!(
+            from_display_amount(display_amount, exponent).unwrap(),
+            Uint128::new(amount)
+        );
+    }
+
+    #[rstest]
+    #[case("1.0000009", 6, 1_000_000)]
+    #[case("0.0000001", 6, 0)]
+    #[case("1.9", 0, 1)]
+    #[case("1000.000000000000000019", 17, 100_000_000_000_000_000_001)]
+    fn test_from_display_amount_rounds_down(
+        #[case] display_amount: &str,
+        #[case] exponent: u32,
+        #[case] expected: u128,
+    ) {
+        assert_eq!(
+            from_display_amount(display_amount.parse().unwrap(), exponent).unwrap(),
+            Uint128::new(expected)
+        );
+    }
+}
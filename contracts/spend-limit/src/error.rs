@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use cosmwasm_std::{CoinsError, StdError, Timestamp};
+use cosmwasm_std::{CoinsError, StdError, Timestamp, Uint64};
 
 use crate::{
     authenticator::AuthenticatorError, period::PeriodError, price::PriceError,
@@ -28,6 +28,51 @@ pub enum ContractError {
     #[error("Duplicated denom: {denom}")]
     DuplicatedDenom { denom: String },
 
+    #[error("Quote denom is already {quote_denom}")]
+    QuoteDenomUnchanged { quote_denom: String },
+
+    #[error("Quote denom must stay {quote_denom}, migrate the quote denom to change it")]
+    QuoteDenomChanged { quote_denom: String },
+
+    #[error("Invalid exponent for {denom}: {exponent}, exponent must not exceed {max_exponent}", max_exponent = crate::denom_metadata::MAX_DENOM_EXPONENT)]
+    InvalidDenomExponent { denom: String, exponent: u32 },
+
+    #[error("Invalid price profile name: {name}")]
+    InvalidPriceProfileName { name: String },
+
+    #[error("Price profile already exists: {name}")]
+    PriceProfileAlreadyExists { name: String },
+
+    #[error("Swap routes to {quote_denom} must be given for the denoms priced by twap: {}", .denoms.join(", "))]
+    MissingSwapRoutesToQuoteDenom {
+        quote_denom: String,
+        denoms: Vec<String>,
+    },
+
+    #[error("Quote denom conversion rate must not be zero")]
+    ZeroConversionRate {},
+
+    #[error("Contract is paused: {reason}")]
+    ContractPaused { reason: String },
+
+    #[error("Admin transfer expired at {expires_at}")]
+    AdminTransferExpired { expires_at: Timestamp },
+
+    #[error("Admin action must be proposed and executed after the admin action delay")]
+    AdminActionTimelocked {},
+
+    #[error("Admin action is not timelocked, execute it directly")]
+    AdminActionNotTimelocked {},
+
+    #[error("Admin action delay {delay} exceeds the maximum of {max_delay}")]
+    AdminActionDelayTooLong { delay: Uint64, max_delay: Uint64 },
+
+    #[error("Admin action {id} not found")]
+    AdminActionNotFound { id: u64 },
+
+    #[error("Admin action {id} can't be executed before {executable_at}")]
+    AdminActionNotExecutable { id: u64, executable_at: Timestamp },
+
     #[error("Current time {current} not within time limit {start:?} - {end}")]
     NotWithinTimeLimit {
         current: Timestamp,
@@ -8,18 +8,39 @@ use crate::{
     ContractError,
 };
 
-/// Get the spending fee for an account. If the account is not the fee payer, it does not count towards the spending limit.
+/// How the transaction fee counts towards the spending of an account.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum FeePolicy {
+    /// Count the fee only if the account pays for it,
+    /// which is the fee granter if set, otherwise the fee payer
+    #[default]
+    AccountPaid,
+
+    /// Never count the fee towards the spending
+    Exclude,
+
+    /// Count the fee paid by the account, including the fee granted to it as the fee payer
+    IncludeGranted,
+}
+
+/// Get the spending fee for an account according to the fee policy.
+/// Fee that does not count towards the spending limit of the account is excluded.
 pub fn get_account_spending_fee(
+    fee_policy: FeePolicy,
     account: &Addr,
     fee_payer: &Addr,
     fee_granter: Option<&Addr>,
     fee: Vec<Coin>,
 ) -> Vec<Coin> {
-    // fee granter pay for the fee if specified
-    let fee_payer = fee_granter.unwrap_or(fee_payer);
-
-    // count fee paid towards this account only if the account is the fee payer
-    if account == fee_payer {
+    let is_counted = match fee_policy {
+        // fee granter pay for the fee if specified
+        FeePolicy::AccountPaid => account == fee_granter.unwrap_or(fee_payer),
+        FeePolicy::Exclude => false,
+        FeePolicy::IncludeGranted => account == fee_payer || Some(account) == fee_granter,
+    };
+
+    if is_counted {
         fee
     } else {
         vec![]
@@ -183,4 +204,34 @@ mod tests {
             .unwrap();
         assert!(!updated_fee.is_empty());
     }
+
+    #[rstest]
+    #[case::account_paid_by_payer(FeePolicy::AccountPaid, "account", None, true)]
+    #[case::account_paid_granted(FeePolicy::AccountPaid, "account", Some("granter"), false)]
+    #[case::account_paid_by_granter(FeePolicy::AccountPaid, "relayer", Some("account"), true)]
+    #[case::account_paid_by_other(FeePolicy::AccountPaid, "relayer", None, false)]
+    #[case::exclude_paid(FeePolicy::Exclude, "account", None, false)]
+    #[case::exclude_by_granter(FeePolicy::Exclude, "relayer", Some("account"), false)]
+    #[case::include_granted_by_payer(FeePolicy::IncludeGranted, "account", None, true)]
+    #[case::include_granted_granted(FeePolicy::IncludeGranted, "account", Some("granter"), true)]
+    #[case::include_granted_by_granter(FeePolicy::IncludeGranted, "relayer", Some("account"), true)]
+    #[case::include_granted_by_other(FeePolicy::IncludeGranted, "relayer", Some("granter"), false)]
+    fn test_get_account_spending_fee(
+        #[case] fee_policy: FeePolicy,
+        #[case] fee_payer: &str,
+        #[case] fee_granter: Option<&str>,
+        #[case] is_counted: bool,
+    ) {
+        let fee = coins(100, "uosmo");
+        let fee_granter = fee_granter.map(Addr::unchecked);
+        let spending_fee = get_account_spending_fee(
+            fee_policy,
+            &Addr::unchecked("account"),
+            &Addr::unchecked(fee_payer),
+            fee_granter.as_ref(),
+            fee.clone(),
+        );
+
+        assert_eq!(spending_fee, if is_counted { fee } else { vec![] });
+    }
 }
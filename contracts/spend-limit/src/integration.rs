@@ -2,7 +2,13 @@
 // and also, tarpaulin will not be able to read coverage out of wasm binary anyway
 #![cfg(all(test, not(tarpaulin)))]
 
-use cosmwasm_std::{Coin, Timestamp, Uint128};
+use std::path::PathBuf;
+
+use cosmwasm_std::{from_json, to_json_vec, Coin, Decimal, Timestamp, Uint128, Uint64};
+use osmosis_std::types::osmosis::cosmwasmpool::{
+    self,
+    v1beta1::{CosmWasmPool, MsgCreateCosmWasmPool, MsgCreateCosmWasmPoolResponse},
+};
 use osmosis_std::types::osmosis::poolmanager::v1beta1::{
     EstimateSwapExactAmountInRequest, EstimateSwapExactAmountInResponse,
 };
@@ -10,7 +16,10 @@ use osmosis_std::types::osmosis::smartaccount;
 use osmosis_std::types::osmosis::{
     gamm::v1beta1::MsgSwapExactAmountInResponse,
     poolmanager::v1beta1::{MsgSwapExactAmountIn, SwapAmountInRoute},
-    smartaccount::v1beta1::{MsgRemoveAuthenticator, MsgRemoveAuthenticatorResponse},
+    smartaccount::v1beta1::{
+        GetAuthenticatorRequest, GetAuthenticatorResponse, MsgRemoveAuthenticator,
+        MsgRemoveAuthenticatorResponse,
+    },
 };
 use osmosis_test_tube::osmosis_std::types::cosmos::bank::v1beta1::QueryBalanceRequest;
 use osmosis_test_tube::{
@@ -20,17 +29,22 @@ use osmosis_test_tube::{
 };
 use time::{Duration, OffsetDateTime};
 
+use crate::fee::FeePolicy;
 use crate::ContractError;
 use crate::{
     assert_substring,
-    msg::{InstantiateMsg, QueryMsg, SpendingResponse, SpendingsByAccountResponse, TrackedDenom},
+    msg::{
+        ExecuteMsg, InstantiateMsg, PriceRefreshStatus, PruneResponse, QueryMsg,
+        RefreshPricesResponse, SpendingResponse, SpendingsByAccountResponse, TrackedDenomsResponse,
+    },
     period::Period,
-    price::{PriceError, PriceResolutionConfig},
+    price::{PriceError, PriceResolutionConfig, SpotPriceFallback},
     spend_limit::{SpendLimitError, SpendLimitParams, Spending, TimeLimit},
     test_helper::authenticator_setup::{
         add_1ct_session_authenticator, add_all_of_sig_ver_spend_limit_authenticator,
         add_spend_limit_authenticator, spend_limit_instantiate, spend_limit_store_code,
     },
+    test_helper::price::{price_resolution_config, tracked_denom},
 };
 
 const UUSDC: &str = "ibc/498A0751C798A0D9A389AA3691123DADA57DAA4FE165D5C75894505B876BA6E4";
@@ -56,11 +70,7 @@ fn test_no_conversion() {
         &wasm,
         code_id,
         &InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: "uosmo".to_string(),
-                staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                twap_duration: 3_600_000_000_000u64.into(),       // 1h
-            },
+            price_resolution_config: price_resolution_config("uosmo"),
             tracked_denoms: vec![],
             admin: None,
         },
@@ -76,8 +86,12 @@ fn test_no_conversion() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(1_500_000),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -107,7 +121,12 @@ fn test_no_conversion() {
             "1".to_string(),
             Spending {
                 value_spent_in_period: Uint128::new(1_500_000),
-                last_spent_at: timestamp
+                fee_spent_in_period: Uint128::zero(),
+                last_spent_at: timestamp,
+                limit_conversion_rate: None,
+                display_limit_exponent: None,
+                price_profile: None,
+                quote_denom_migrations_applied: 0,
             }
         )]
     );
@@ -191,11 +210,7 @@ fn test_fee_draining() {
         &wasm,
         code_id,
         &InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: "uosmo".to_string(),
-                staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                twap_duration: 3_600_000_000_000u64.into(),       // 1h
-            },
+            price_resolution_config: price_resolution_config("uosmo"),
             tracked_denoms: vec![],
             admin: None,
         },
@@ -216,8 +231,12 @@ fn test_fee_draining() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(1_500_000),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -292,7 +311,12 @@ fn test_fee_draining() {
             "1".to_string(),
             Spending {
                 value_spent_in_period: 1500000u128.into(),
-                last_spent_at: Timestamp::from_nanos(latest_exec as u64)
+                fee_spent_in_period: Uint128::zero(),
+                last_spent_at: Timestamp::from_nanos(latest_exec as u64),
+                limit_conversion_rate: None,
+                display_limit_exponent: None,
+                price_profile: None,
+                quote_denom_migrations_applied: 0,
             }
         )]
     );
@@ -439,29 +463,23 @@ fn test_with_conversion() {
     let code_id = spend_limit_store_code(&wasm, &acc_1);
 
     // try to instantiate with incorrect routes
-    let now = app.get_block_timestamp();
-    let start_time = now.minus_nanos(3_600_000_000_000u64);
     let err = wasm
         .instantiate(
             code_id,
             &InstantiateMsg {
-                price_resolution_config: PriceResolutionConfig {
-                    quote_denom: UUSDC.to_string(),
-                    staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                    twap_duration: 3_600_000_000_000u64.into(),       // 1h
-                },
+                price_resolution_config: price_resolution_config(UUSDC),
                 tracked_denoms: vec![
-                    TrackedDenom {
-                        denom: "uosmo".to_string(),
-                        swap_routes: vec![SwapAmountInRoute {
+                    tracked_denom(
+                        "uosmo",
+                        vec![SwapAmountInRoute {
                             pool_id: osmo_usdc_pool_id,
                             token_out_denom: UUSDC.to_string(),
                         }],
-                    },
+                    ),
                     // incorrect
-                    TrackedDenom {
-                        denom: "uion".to_string(),
-                        swap_routes: vec![
+                    tracked_denom(
+                        "uion",
+                        vec![
                             SwapAmountInRoute {
                                 pool_id: ion_atom_pool_id,
                                 token_out_denom: UATOM.to_string(),
@@ -471,7 +489,7 @@ fn test_with_conversion() {
                                 token_out_denom: UUSDC.to_string(),
                             },
                         ],
-                    },
+                    ),
                 ],
                 admin: None,
             },
@@ -484,29 +502,29 @@ fn test_with_conversion() {
 
     assert_substring!(
         err.to_string(),
-        PriceError::twap_query_error(osmo_usdc_pool_id, UATOM, UUSDC, start_time).to_string()
+        PriceError::DenomNotInPool {
+            pool_id: osmo_usdc_pool_id,
+            denom: UATOM.to_string(),
+        }
+        .to_string()
     );
 
     let contract_addr = spend_limit_instantiate(
         &wasm,
         code_id,
         &InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: UUSDC.to_string(),
-                staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                twap_duration: 3_600_000_000_000u64.into(),       // 1h
-            },
+            price_resolution_config: price_resolution_config(UUSDC),
             tracked_denoms: vec![
-                TrackedDenom {
-                    denom: "uosmo".to_string(),
-                    swap_routes: vec![SwapAmountInRoute {
+                tracked_denom(
+                    "uosmo",
+                    vec![SwapAmountInRoute {
                         pool_id: osmo_usdc_pool_id,
                         token_out_denom: UUSDC.to_string(),
                     }],
-                },
-                TrackedDenom {
-                    denom: "uion".to_string(),
-                    swap_routes: vec![
+                ),
+                tracked_denom(
+                    "uion",
+                    vec![
                         SwapAmountInRoute {
                             pool_id: ion_osmo_pool_id,
                             token_out_denom: "uosmo".to_string(),
@@ -516,10 +534,10 @@ fn test_with_conversion() {
                             token_out_denom: UUSDC.to_string(),
                         },
                     ],
-                },
-                TrackedDenom {
-                    denom: UATOM.to_string(),
-                    swap_routes: vec![
+                ),
+                tracked_denom(
+                    UATOM,
+                    vec![
                         SwapAmountInRoute {
                             pool_id: atom_osmo_pool_id,
                             token_out_denom: "uosmo".to_string(),
@@ -529,7 +547,7 @@ fn test_with_conversion() {
                             token_out_denom: UUSDC.to_string(),
                         },
                     ],
-                },
+                ),
             ],
             admin: None,
         },
@@ -543,8 +561,12 @@ fn test_with_conversion() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(1_000_000),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -623,6 +645,215 @@ fn test_with_conversion() {
     );
 }
 
+#[test]
+fn test_track_denom_with_missing_or_cosmwasm_pool() {
+    let app = OsmosisTestApp::new();
+    let acc = app
+        .init_account(&[
+            Coin::new(1_000_000_000_000, "uosmo"),
+            Coin::new(1_000_000_000_000, UUSDC),
+            Coin::new(1_000_000_000_000, UATOM),
+        ])
+        .unwrap();
+
+    let cw_pool_id = create_cosmwasm_pool(
+        &app,
+        vec![Coin::new(1_000_000, UATOM), Coin::new(1_000_000, UUSDC)],
+        &acc,
+    );
+
+    let wasm = Wasm::new(&app);
+    let code_id = spend_limit_store_code(&wasm, &acc);
+
+    let instantiate_with_pool = |pool_id: u64| {
+        wasm.instantiate(
+            code_id,
+            &InstantiateMsg {
+                price_resolution_config: price_resolution_config(UUSDC),
+                tracked_denoms: vec![tracked_denom(
+                    UATOM,
+                    vec![SwapAmountInRoute {
+                        pool_id,
+                        token_out_denom: UUSDC.to_string(),
+                    }],
+                )],
+                admin: None,
+            },
+            None,
+            Some("spend_limit_authenticator"),
+            &[],
+            &acc,
+        )
+        .unwrap_err()
+    };
+
+    // pool does not exist
+    let missing_pool_id = cw_pool_id + 1;
+    assert_substring!(
+        instantiate_with_pool(missing_pool_id).to_string(),
+        PriceError::PoolNotFound {
+            pool_id: missing_pool_id
+        }
+        .to_string()
+    );
+
+    // twap is not supported for CosmWasm pools
+    assert_substring!(
+        instantiate_with_pool(cw_pool_id).to_string(),
+        PriceError::UnsupportedPoolType {
+            pool_id: cw_pool_id,
+            pool_type: CosmWasmPool::TYPE_URL.to_string(),
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn test_auto_track_denom_and_spot_price_fallback() {
+    let app = OsmosisTestApp::new();
+    let acc = app
+        .init_account(&[
+            Coin::new(1_000_000_000_000, "uosmo"),
+            Coin::new(1_000_000_000_000, UUSDC),
+            Coin::new(1_000_000_000_000, UATOM),
+        ])
+        .unwrap();
+
+    let gamm = Gamm::new(&app);
+
+    // 1:2, listed first but less liquid
+    gamm.create_basic_pool(
+        &[Coin::new(1_000_000, UATOM), Coin::new(2_000_000, UUSDC)],
+        &acc,
+    )
+    .unwrap();
+
+    // 1:2
+    let atom_usdc_pool_id = gamm
+        .create_basic_pool(
+            &[
+                Coin::new(1_000_000_000, UATOM),
+                Coin::new(2_000_000_000, UUSDC),
+            ],
+            &acc,
+        )
+        .unwrap()
+        .data
+        .pool_id;
+
+    // increase time by 1h
+    app.increase_time(3_600u64);
+
+    let wasm = Wasm::new(&app);
+    let code_id = spend_limit_store_code(&wasm, &acc);
+    let contract_addr = wasm
+        .instantiate(
+            code_id,
+            &InstantiateMsg {
+                price_resolution_config: PriceResolutionConfig {
+                    spot_price_fallback: Some(SpotPriceFallback {
+                        max_deviation: Decimal::percent(10),
+                        markup: Decimal::percent(5),
+                    }),
+                    ..price_resolution_config(UUSDC)
+                },
+                tracked_denoms: vec![],
+                admin: Some(acc.address()),
+            },
+            None,
+            Some("spend_limit_authenticator"),
+            &[],
+            &acc,
+        )
+        .unwrap()
+        .data
+        .address;
+
+    // swap routes are discovered from the pools listed by denom
+    wasm.execute(
+        &contract_addr,
+        &ExecuteMsg::AutoTrackDenom {
+            price_profile: None,
+            denom: UATOM.to_string(),
+            max_hops: None,
+        },
+        &[],
+        &acc,
+    )
+    .unwrap();
+
+    let TrackedDenomsResponse { tracked_denoms, .. } = wasm
+        .query(
+            &contract_addr,
+            &QueryMsg::TrackedDenoms {
+                price_profile: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        tracked_denoms,
+        vec![tracked_denom(
+            UATOM,
+            vec![SwapAmountInRoute {
+                pool_id: atom_usdc_pool_id,
+                token_out_denom: UUSDC.to_string(),
+            }],
+        )]
+    );
+
+    // twap can't be queried from before the pool creation, so the spot price is used instead
+    wasm.execute(
+        &contract_addr,
+        &ExecuteMsg::SetPriceResolutionParams {
+            price_profile: None,
+            staleness_threshold: None,
+            twap_duration: Some(Uint64::from(24 * 3_600_000_000_000u64)),
+            twap_kind: None,
+            max_price_change_per_refresh: None,
+            block_time_behind_last_update: None,
+            min_pool_liquidity: None,
+            spot_price_fallback: None,
+            max_price_age: None,
+        },
+        &[],
+        &acc,
+    )
+    .unwrap();
+
+    // increase time by 2h so that the price gets stale
+    app.increase_time(7_200u64);
+
+    let res = wasm
+        .execute(
+            &contract_addr,
+            &ExecuteMsg::RefreshPrices {
+                price_profile: None,
+                denoms: Some(vec![UATOM.to_string()]),
+                start_after: None,
+                limit: None,
+            },
+            &[],
+            &acc,
+        )
+        .unwrap();
+
+    let RefreshPricesResponse { results, .. } = from_json(res.data.data).unwrap();
+
+    // spot price is cached without the markup, which only applies to the spent coins
+    assert_eq!(
+        results,
+        vec![(
+            UATOM.to_string(),
+            PriceRefreshStatus::Refreshed {
+                price: Decimal::from_ratio(2u128, 1u128)
+            }
+        )]
+    );
+}
+
 #[test]
 fn test_setup_and_teardown() {
     let app = OsmosisTestApp::new();
@@ -640,11 +871,7 @@ fn test_setup_and_teardown() {
         &wasm,
         code_id,
         &InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: "uosmo".to_string(),
-                staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                twap_duration: 3_600_000_000_000u64.into(),       // 1h
-            },
+            price_resolution_config: price_resolution_config("uosmo"),
             tracked_denoms: vec![],
             admin: None,
         },
@@ -658,8 +885,12 @@ fn test_setup_and_teardown() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(1_000_000),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -669,8 +900,12 @@ fn test_setup_and_teardown() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(999_999),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -680,12 +915,16 @@ fn test_setup_and_teardown() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(100_000),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: None,
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
-    let SpendingsByAccountResponse { spendings } = wasm
+    let SpendingsByAccountResponse { spendings, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::SpendingsByAccount {
@@ -702,7 +941,7 @@ fn test_setup_and_teardown() {
         ]
     );
 
-    let SpendingsByAccountResponse { spendings } = wasm
+    let SpendingsByAccountResponse { spendings, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::SpendingsByAccount {
@@ -724,7 +963,54 @@ fn test_setup_and_teardown() {
     )
     .unwrap();
 
-    let SpendingsByAccountResponse { spendings } = wasm
+    let SpendingsByAccountResponse { spendings, .. } = wasm
+        .query(
+            &contract_addr,
+            &QueryMsg::SpendingsByAccount {
+                account: accs[0].address(),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(spendings, vec![("2.1".to_string(), Spending::default())]);
+
+    // querying the removed authenticator either fails or responds with an empty authenticator,
+    // both of which mean that the authenticator no longer exists when pruning
+    let removed_authenticator = app.query::<_, GetAuthenticatorResponse>(
+        "/osmosis.smartaccount.v1beta1.Query/GetAuthenticator",
+        &GetAuthenticatorRequest {
+            account: accs[0].address(),
+            authenticator_id: 1,
+        },
+    );
+    if let Ok(GetAuthenticatorResponse {
+        account_authenticator: Some(authenticator),
+    }) = removed_authenticator
+    {
+        assert_eq!(authenticator.r#type, "");
+    }
+
+    // pruning after the removal keeps the spend limits of the existing authenticators
+    let res = wasm
+        .execute(
+            &contract_addr,
+            &ExecuteMsg::Prune {
+                start_after: None,
+                limit: None,
+            },
+            &[],
+            &accs[1],
+        )
+        .unwrap();
+
+    let PruneResponse {
+        pruned,
+        next_start_after,
+    } = from_json(res.data.data).unwrap();
+    assert_eq!(pruned, Vec::<(String, String)>::new());
+    assert_eq!(next_start_after, None);
+
+    let SpendingsByAccountResponse { spendings, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::SpendingsByAccount {
@@ -810,22 +1096,18 @@ fn test_1_click_trading() {
         &wasm,
         code_id,
         &InstantiateMsg {
-            price_resolution_config: PriceResolutionConfig {
-                quote_denom: UUSDC.to_string(),
-                staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-                twap_duration: 3_600_000_000_000u64.into(),       // 1h
-            },
+            price_resolution_config: price_resolution_config(UUSDC),
             tracked_denoms: vec![
-                TrackedDenom {
-                    denom: "uosmo".to_string(),
-                    swap_routes: vec![SwapAmountInRoute {
+                tracked_denom(
+                    "uosmo",
+                    vec![SwapAmountInRoute {
                         pool_id: osmo_usdc_pool_id,
                         token_out_denom: UUSDC.to_string(),
                     }],
-                },
-                TrackedDenom {
-                    denom: "uion".to_string(),
-                    swap_routes: vec![
+                ),
+                tracked_denom(
+                    "uion",
+                    vec![
                         SwapAmountInRoute {
                             pool_id: ion_osmo_pool_id,
                             token_out_denom: "uosmo".to_string(),
@@ -835,10 +1117,10 @@ fn test_1_click_trading() {
                             token_out_denom: UUSDC.to_string(),
                         },
                     ],
-                },
-                TrackedDenom {
-                    denom: UATOM.to_string(),
-                    swap_routes: vec![
+                ),
+                tracked_denom(
+                    UATOM,
+                    vec![
                         SwapAmountInRoute {
                             pool_id: atom_osmo_pool_id,
                             token_out_denom: "uosmo".to_string(),
@@ -848,7 +1130,7 @@ fn test_1_click_trading() {
                             token_out_denom: UUSDC.to_string(),
                         },
                     ],
-                },
+                ),
             ],
             admin: None,
         },
@@ -874,11 +1156,15 @@ fn test_1_click_trading() {
         &contract_addr,
         &SpendLimitParams {
             limit: limit.into(),
+            display_limit: None,
             reset_period: Period::Day,
             time_limit: Some(TimeLimit {
                 start: None,
                 end: session_1_end,
             }),
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -952,7 +1238,7 @@ fn test_1_click_trading() {
     .unwrap();
 
     // query spendings
-    let SpendingResponse { spending } = wasm
+    let SpendingResponse { spending, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::Spending {
@@ -1027,11 +1313,15 @@ fn test_1_click_trading() {
         &contract_addr,
         &SpendLimitParams {
             limit: Uint128::new(10_000_000_000),
+            display_limit: None,
             reset_period: Period::Month,
             time_limit: Some(TimeLimit {
                 start: None,
                 end: Timestamp::from_seconds(app.get_block_time_seconds() as u64).plus_hours(3),
             }),
+            price_profile: None,
+            fee_policy: FeePolicy::default(),
+            fee_limit: None,
         },
     );
 
@@ -1068,7 +1358,7 @@ fn test_1_click_trading() {
     .unwrap();
 
     // query spendings for session 2
-    let SpendingResponse { spending } = wasm
+    let SpendingResponse { spending, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::Spending {
@@ -1084,7 +1374,7 @@ fn test_1_click_trading() {
     );
 
     // query spending for session 1
-    let SpendingResponse { spending } = wasm
+    let SpendingResponse { spending, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::Spending {
@@ -1099,7 +1389,7 @@ fn test_1_click_trading() {
     // increases time for 2 days
     app.increase_time(24 * 60 * 60 * 2);
 
-    let SpendingResponse { spending } = wasm
+    let SpendingResponse { spending, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::Spending {
@@ -1154,7 +1444,7 @@ fn test_1_click_trading() {
     .unwrap();
 
     // query spending for session 1
-    let SpendingResponse { spending } = wasm
+    let SpendingResponse { spending, .. } = wasm
         .query(
             &contract_addr,
             &QueryMsg::Spending {
@@ -1261,6 +1551,52 @@ fn set_maximum_unauthenticated_gas(app: &OsmosisTestApp, maximum_unauthenticated
     .unwrap();
 }
 
+/// Create a CosmWasm pool backed by the mock pool contract that reports `pool_liquidity`
+fn create_cosmwasm_pool(
+    app: &OsmosisTestApp,
+    pool_liquidity: Vec<Coin>,
+    signer: &SigningAccount,
+) -> u64 {
+    let wasm_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("target")
+        .join("wasm32-unknown-unknown")
+        .join("release")
+        .join("mock_cosmwasm_pool.wasm");
+
+    let code_id = Wasm::new(app)
+        .store_code(&std::fs::read(wasm_path).unwrap(), None, signer)
+        .unwrap()
+        .data
+        .code_id;
+
+    // only whitelisted code can be instantiated as a CosmWasm pool
+    app.set_param_set(
+        "cosmwasmpool",
+        cosmwasmpool::v1beta1::Params {
+            code_id_whitelist: vec![code_id],
+            pool_migration_limit: 20,
+        }
+        .to_any(),
+    )
+    .unwrap();
+
+    app.execute::<_, MsgCreateCosmWasmPoolResponse>(
+        MsgCreateCosmWasmPool {
+            code_id,
+            instantiate_msg: to_json_vec(&mock_cosmwasm_pool::InstantiateMsg { pool_liquidity })
+                .unwrap(),
+            sender: signer.address(),
+        },
+        MsgCreateCosmWasmPool::TYPE_URL,
+        signer,
+    )
+    .unwrap()
+    .data
+    .pool_id
+}
+
 struct SpendLimitQuerier<'a> {
     app: &'a OsmosisTestApp,
     contract_addr: String,
@@ -1272,7 +1608,7 @@ impl SpendLimitQuerier<'_> {
     }
     fn query_spendings_by_account(&self, account: String) -> RunnerResult<Vec<(String, Spending)>> {
         let wasm = Wasm::new(self.app);
-        let SpendingsByAccountResponse { spendings } = wasm.query(
+        let SpendingsByAccountResponse { spendings, .. } = wasm.query(
             &self.contract_addr,
             &QueryMsg::SpendingsByAccount { account },
         )?;
@@ -3,10 +3,15 @@ mod serde;
 pub mod authenticator;
 
 pub mod admin;
+pub mod audit;
+pub mod denom_metadata;
 pub mod fee;
+pub mod pause;
 pub mod period;
 pub mod price;
+pub mod price_profile;
 pub mod spend_limit;
+pub mod timelock;
 
 pub mod contract;
 pub mod error;
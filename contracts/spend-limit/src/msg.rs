@@ -1,17 +1,23 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint64;
+use cosmwasm_std::{Decimal, Timestamp, Uint128, Uint64};
 pub use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
 
-use crate::{price::PriceResolutionConfig, spend_limit::Spending};
+pub use crate::price::TrackedDenom;
+use crate::{
+    admin::Role,
+    audit::AuditLogEntry,
+    pause::{PauseMode, PauseState},
+    price::{
+        BlockTimeBehindLastUpdate, DenomAlias, PriceResolutionConfig, SpotPriceFallback, TwapKind,
+    },
+    spend_limit::Spending,
+    timelock::PendingAdminAction,
+};
 
-// re-export the structs from cw_authenticator
-pub use cw_authenticator::AuthenticatorSudoMsg as SudoMsg;
-
-#[cw_serde]
-pub struct TrackedDenom {
-    pub denom: String,
-    pub swap_routes: Vec<SwapAmountInRoute>,
-}
+use cw_authenticator::{
+    AuthenticationRequest, ConfirmExecutionRequest, OnAuthenticatorAddedRequest,
+    OnAuthenticatorRemovedRequest, TrackRequest,
+};
 
 #[cw_serde]
 pub enum DenomRemovalTarget {
@@ -19,6 +25,24 @@ pub enum DenomRemovalTarget {
     Partial(Vec<String>),
 }
 
+/// Update of a setting that can be unset, the setting is kept as is if the update is not given
+#[cw_serde]
+pub enum OptionalUpdate<T> {
+    Set(T),
+    Unset,
+}
+
+impl<T> OptionalUpdate<T> {
+    /// Apply the update, if any, to the current value of the setting
+    pub fn apply(update: Option<Self>, current: Option<T>) -> Option<T> {
+        match update {
+            Some(OptionalUpdate::Set(value)) => Some(value),
+            Some(OptionalUpdate::Unset) => None,
+            None => current,
+        }
+    }
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub price_resolution_config: PriceResolutionConfig,
@@ -26,28 +50,248 @@ pub struct InstantiateMsg {
     pub admin: Option<String>,
 }
 
+/// Sudo messages from the smart account module, see [`cw_authenticator::AuthenticatorSudoMsg`],
+/// extended with the admin operations that chain governance can execute without the admin,
+/// e.g. to keep managing the contract after the admin is revoked.
+#[cw_serde]
+pub enum SudoMsg {
+    OnAuthenticatorAdded(OnAuthenticatorAddedRequest),
+    OnAuthenticatorRemoved(OnAuthenticatorRemovedRequest),
+    Authenticate(Box<AuthenticationRequest>),
+    Track(TrackRequest),
+    ConfirmExecution(ConfirmExecutionRequest),
+
+    /// Set tracked denoms, same as [`ExecuteMsg::SetTrackedDenoms`]
+    SetTrackedDenoms {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        tracked_denoms: Vec<TrackedDenom>,
+    },
+    /// Set the price resolution config, the quote denom must stay the same
+    /// since changing it requires [`ExecuteMsg::MigrateQuoteDenom`]
+    SetPriceResolutionConfig {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        price_resolution_config: PriceResolutionConfig,
+    },
+    /// Set the admin, or remove it if not set. This overrides any pending admin transfer
+    /// and revokes all the roles granted by the previous admin.
+    SetAdmin {
+        address: Option<String>,
+    },
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
-    /// Set the price resolution parameters
+    /// Set the price resolution parameters, parameters that are not given are kept as is
     SetPriceResolutionParams {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
         /// Duration in nanoseconds that the price is considered stale.
         /// If the current time is greater than the last_updated_time + staleness_threshold,
         /// the price needs to be updated.
-        staleness_threshold: Uint64,
+        staleness_threshold: Option<Uint64>,
 
         /// Twap duration in nanoseconds
-        twap_duration: Uint64,
+        twap_duration: Option<Uint64>,
+
+        /// Kind of twap used to calculate the price
+        twap_kind: Option<TwapKind>,
+
+        /// Maximum change of the price relative to the cached price allowed per refresh.
+        /// If unset, price change is not limited.
+        max_price_change_per_refresh: Option<OptionalUpdate<Decimal>>,
+
+        /// How to handle the cached price when the block time is behind its last updated time
+        block_time_behind_last_update: Option<BlockTimeBehindLastUpdate>,
+
+        /// Minimum liquidity of each pool along the swap routes required to track a denom,
+        /// valued in quote denom. If unset, pool liquidity is not checked.
+        min_pool_liquidity: Option<OptionalUpdate<Uint128>>,
+
+        /// Fall back to the spot price within the given bounds when the twap is unavailable.
+        /// If unset, refreshing the price fails when the twap is unavailable.
+        spot_price_fallback: Option<OptionalUpdate<SpotPriceFallback>>,
+
+        /// Maximum age in nanoseconds of the cached price that is still used when refreshing it fails.
+        /// If unset, refreshing the price fails.
+        max_price_age: Option<OptionalUpdate<Uint64>>,
+    },
+    /// Switch the quote denom that all prices and spendings are valued in.
+    /// All tracked denoms are re-tracked against the new quote denom and
+    /// all recorded spendings are rescaled by `conversion_rate` when they are loaded next.
+    /// The previous quote denom is tracked with `conversion_rate` as its fixed price,
+    /// unless it's included in `tracked_denoms`.
+    MigrateQuoteDenom {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        /// New quote denom
+        quote_denom: String,
+
+        /// Units of the new quote denom worth 1 unit of the current quote denom,
+        /// used to rescale spendings, fixed prices and limits of the existing authenticators
+        conversion_rate: Decimal,
+
+        /// Tracked denoms to set along with the migration, overriding the currently tracked ones.
+        /// Every currently tracked denom priced through swap routes must be overridden,
+        /// since its swap routes end with the current quote denom. The other tracked denoms
+        /// are retained, with their fixed prices rescaled by `conversion_rate`.
+        #[serde(default)]
+        tracked_denoms: Vec<TrackedDenom>,
+    },
+    /// Create a named price profile with its own price resolution config and tracked denoms,
+    /// e.g. to value spendings in OSMO. Authenticators select it with `price_profile` in their params.
+    CreatePriceProfile {
+        name: String,
+        price_resolution_config: PriceResolutionConfig,
+        tracked_denoms: Vec<TrackedDenom>,
+    },
+    /// Remove the named price profile along with its tracked denoms.
+    /// Authenticators selecting the profile can't resolve prices until it is created again.
+    RemovePriceProfile {
+        name: String,
     },
     RemoveTrackedDenoms {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
         target: DenomRemovalTarget,
     },
     /// Set tracked denoms, this will overwrite the current tracked denoms if exists
     /// or add new tracked denoms if not exists
     SetTrackedDenoms {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
         tracked_denoms: Vec<TrackedDenom>,
     },
+    /// Track the denom with the swap routes discovered on chain, scored by twap availability
+    /// and liquidity, this will overwrite the current tracking of the denom if exists.
+    AutoTrackDenom {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        denom: String,
+
+        /// Maximum number of hops of the discovered swap routes,
+        /// defaults to and can't exceed [`crate::price::MAX_DISCOVERED_SWAP_ROUTES_HOPS`]
+        #[serde(default)]
+        max_hops: Option<u32>,
+    },
+    /// Set fixed prices of the denoms, e.g. for pegged stablecoins,
+    /// this will overwrite the current tracking of the denoms if exists.
+    /// Fixed prices never expire and don't require swap routes.
+    SetFixedPrices {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        prices: Vec<(String, Decimal)>,
+    },
+    /// Set the exponents of the display units of the denoms, e.g. 6 for `uusdc` displayed as `usdc`,
+    /// this will overwrite the exponents from the bank denom metadata.
+    /// The exponent of the quote denom is used to convert display limits and amounts.
+    SetDenomExponents {
+        exponents: Vec<(String, u32)>,
+    },
+    /// Set the aliases of the denoms that represent the same asset as their canonical denom,
+    /// e.g. `USDC.axl` for `USDC`, this will overwrite the current aliases of the denoms if exists.
+    /// Aliases are priced by their canonical denom in all price profiles, even if they are tracked.
+    /// In a price profile that doesn't track the canonical denom, a tracked alias is priced on its own.
+    SetDenomAliases {
+        aliases: Vec<(String, DenomAlias)>,
+    },
+    /// Remove the aliases of the denoms
+    RemoveDenomAliases {
+        denoms: Vec<String>,
+    },
+    /// Accept the current price of the tracked denoms regardless of `max_price_change_per_refresh`,
+    /// this is required to resume pricing the denoms after their price deviation exceeded the limit
+    AcceptPrices {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        denoms: Vec<String>,
+    },
+    /// Refresh stale prices of the tracked denoms, anyone can call this to keep prices fresh
+    /// so that users don't have to pay for refreshing them.
+    /// Refresh at most `limit` tracked denoms after `start_after` if `denoms` is not set.
+    /// Responds with [`RefreshPricesResponse`] as data.
+    RefreshPrices {
+        /// Price profile to apply this to, the default one if not set
+        price_profile: Option<String>,
+
+        denoms: Option<Vec<String>>,
+
+        /// Tracked denom to start after, only used if `denoms` is not set
+        start_after: Option<String>,
+
+        /// Maximum number of tracked denoms to refresh, only used if `denoms` is not set
+        limit: Option<u32>,
+    },
+    /// Remove the state of the spend limits that can no longer be used, anyone can call this.
+    /// A spend limit is pruned if its time limit has ended or its authenticator has been removed,
+    /// including the state left without a spending by the removals before it was cleared on removal.
+    /// Checks at most `limit` spend limits after `start_after`, ordered by account and authenticator id.
+    /// Responds with [`PruneResponse`] as data.
+    Prune {
+        /// Account and authenticator id of the spend limit to start after
+        start_after: Option<(String, String)>,
+
+        limit: Option<u32>,
+    },
+    /// Set the delay in nanoseconds before the proposed admin actions can be executed, up to 30 days.
+    /// While the delay is set, actions that can loosen the spend limits must be proposed
+    /// with `ProposeAdminAction` instead of being executed directly,
+    /// including granting roles and transferring the admin.
+    /// Decreasing the delay must be proposed as well, increasing it takes effect immediately.
+    SetAdminActionDelay {
+        delay: Uint64,
+    },
+    /// Propose a timelocked admin action, which can be executed once the admin action delay has passed.
+    /// The proposer must be authorized to execute the action both when proposing and executing it.
+    ProposeAdminAction {
+        action: Box<ExecuteMsg>,
+    },
+    /// Execute the proposed admin action after its delay has passed,
+    /// only the proposer or the admin can execute it
+    ExecuteAdminAction {
+        id: u64,
+    },
+    /// Cancel the proposed admin action, only the proposer or the admin can cancel it
+    CancelAdminAction {
+        id: u64,
+    },
+    /// Pause authentication through the contract, e.g. when pricing is compromised.
+    /// Rejects every authentication unless `mode` is strict, defaults to frozen.
+    Pause {
+        #[serde(default)]
+        mode: PauseMode,
+        reason: String,
+    },
+    /// Resume authentication through the contract
+    Unpause {},
+    /// Grant the role to the address, only the admin can grant roles
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+    /// Revoke the role from the address, only the admin can revoke roles
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+    /// Offer the admin role to the address, which has to claim it to become the admin.
+    /// The offer can't be claimed after `expires_at` if set.
+    /// Roles granted by the current admin are revoked once the offer is claimed.
     TransferAdmin {
         address: String,
+
+        #[serde(default)]
+        expires_at: Option<Timestamp>,
     },
     ClaimAdminTransfer {},
     RejectAdminTransfer {},
@@ -59,10 +303,25 @@ pub enum ExecuteMsg {
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(PriceResolutionConfigResponse)]
-    PriceResolutionConfig {},
+    PriceResolutionConfig { price_profile: Option<String> },
 
     #[returns(TrackedDenomsResponse)]
     TrackedDenoms {
+        price_profile: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Aliases of the denoms and their canonical denoms
+    #[returns(DenomAliasesResponse)]
+    DenomAliases {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Names and price resolution configs of the named price profiles
+    #[returns(PriceProfilesResponse)]
+    PriceProfiles {
         start_after: Option<String>,
         limit: Option<u32>,
     },
@@ -73,14 +332,50 @@ pub enum QueryMsg {
         authenticator_id: String,
     },
 
+    /// Spendings of the account's authenticators, valued at the current prices.
+    /// Spendings of the authenticators selecting a removed price profile are returned as stored.
     #[returns(SpendingsByAccountResponse)]
     SpendingsByAccount { account: String },
 
+    /// Exponent of the display unit of the denom, from the admin-set exponents
+    /// or the bank denom metadata
+    #[returns(DenomExponentResponse)]
+    DenomExponent { denom: String },
+
     #[returns(AdminResponse)]
     Admin {},
 
     #[returns(AdminCandidateResponse)]
     AdminCandidate {},
+
+    #[returns(AdminActionDelayResponse)]
+    AdminActionDelay {},
+
+    /// Admin actions that are proposed and not yet executed or cancelled
+    #[returns(PendingAdminActionsResponse)]
+    PendingAdminActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Pause state of the contract, not set if the contract is not paused
+    #[returns(PauseStateResponse)]
+    PauseState {},
+
+    /// Log of the admin operations, oldest first
+    #[returns(AuditLogResponse)]
+    AuditLog {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Addresses granted the role, the admin holds all the roles without being listed
+    #[returns(RoleHoldersResponse)]
+    RoleHolders {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -88,19 +383,82 @@ pub struct PriceResolutionConfigResponse {
     pub price_resolution_config: PriceResolutionConfig,
 }
 
+#[cw_serde]
+pub struct PriceProfilesResponse {
+    pub price_profiles: Vec<(String, PriceResolutionConfig)>,
+}
+
 #[cw_serde]
 pub struct TrackedDenomsResponse {
     pub tracked_denoms: Vec<TrackedDenom>,
+
+    /// Aliases priced by the tracked denoms in the response
+    pub aliases: Vec<(String, DenomAlias)>,
+}
+
+#[cw_serde]
+pub struct DenomAliasesResponse {
+    pub aliases: Vec<(String, DenomAlias)>,
 }
 
 #[cw_serde]
 pub struct SpendingResponse {
     pub spending: Spending,
+
+    /// Value spent in the current period in display units of the quote denom,
+    /// not set if the exponent of the quote denom is unknown
+    pub value_spent_in_period_display: Option<Decimal>,
 }
 
 #[cw_serde]
 pub struct SpendingsByAccountResponse {
     pub spendings: Vec<(String, Spending)>,
+
+    /// Value spent in the current period in display units of the quote denom by authenticator id,
+    /// only for the spendings whose quote denom exponent is known
+    pub values_spent_in_period_display: Vec<(String, Decimal)>,
+}
+
+#[cw_serde]
+pub struct DenomExponentResponse {
+    pub exponent: Option<u32>,
+}
+
+#[cw_serde]
+pub struct RefreshPricesResponse {
+    pub results: Vec<(String, PriceRefreshStatus)>,
+
+    /// Tracked denom to continue refreshing after,
+    /// not set if all of them have been refreshed or `denoms` is set
+    pub next_start_after: Option<String>,
+}
+
+#[cw_serde]
+pub struct PruneResponse {
+    /// Account and authenticator id of the pruned spend limits
+    pub pruned: Vec<(String, String)>,
+
+    /// Spend limit to continue pruning after, not set if all of them have been checked
+    pub next_start_after: Option<(String, String)>,
+}
+
+#[cw_serde]
+pub enum PriceRefreshStatus {
+    /// Price was stale and has been refreshed
+    Refreshed { price: Decimal },
+
+    /// Price is still fresh, nothing to refresh
+    Fresh { price: Decimal },
+
+    /// Denom is not tracked
+    NotTracked {},
+
+    /// Price refresh failed, the cached price is kept
+    Failed { error: String },
+
+    /// Price refresh failed and the stale cached price is still used,
+    /// since it's not older than `max_price_age`
+    Stale { price: Decimal },
 }
 
 #[cw_serde]
@@ -111,4 +469,33 @@ pub struct AdminResponse {
 #[cw_serde]
 pub struct AdminCandidateResponse {
     pub candidate: Option<String>,
+
+    /// Time after which the candidate can no longer claim the transfer
+    pub expires_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct AdminActionDelayResponse {
+    /// Delay in nanoseconds, zero if admin actions are not timelocked
+    pub delay: Uint64,
+}
+
+#[cw_serde]
+pub struct PendingAdminActionsResponse {
+    pub actions: Vec<PendingAdminAction>,
+}
+
+#[cw_serde]
+pub struct PauseStateResponse {
+    pub pause_state: Option<PauseState>,
+}
+
+#[cw_serde]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditLogEntry>,
+}
+
+#[cw_serde]
+pub struct RoleHoldersResponse {
+    pub holders: Vec<String>,
 }
@@ -0,0 +1,113 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, StdResult, Storage, Timestamp};
+use cw_storage_plus::Item;
+
+use crate::ContractError;
+
+/// Store for the pause state, the contract is not paused if not set.
+pub type PauseStateStore<'a> = Item<'a, PauseState>;
+
+/// How authentication is restricted while the contract is paused.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum PauseMode {
+    /// Reject every authentication through the contract
+    #[default]
+    Frozen,
+
+    /// Keep authenticating, but reject spending untracked denoms
+    /// and never fall back to expired cached prices when refreshing them fails
+    Strict,
+}
+
+impl PauseMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseMode::Frozen => "frozen",
+            PauseMode::Strict => "strict",
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PauseState {
+    pub mode: PauseMode,
+    pub reason: String,
+    pub paused_by: Addr,
+    pub paused_at: Timestamp,
+}
+
+/// Check whether authentication is allowed under the current pause state.
+/// Returns whether spend limits must be checked strictly, see [`PauseMode::Strict`].
+pub fn ensure_authentication_allowed(
+    pause_state_store: &PauseStateStore,
+    storage: &dyn Storage,
+) -> Result<bool, ContractError> {
+    match pause_state_store.may_load(storage)? {
+        None => Ok(false),
+        Some(PauseState {
+            mode: PauseMode::Frozen,
+            reason,
+            ..
+        }) => Err(ContractError::ContractPaused { reason }),
+        Some(PauseState {
+            mode: PauseMode::Strict,
+            ..
+        }) => Ok(true),
+    }
+}
+
+/// Whether spend limits must be checked strictly under the current pause state.
+/// Transactions authenticated before being frozen are confirmed strictly as well.
+pub fn is_strict(pause_state_store: &PauseStateStore, storage: &dyn Storage) -> StdResult<bool> {
+    Ok(pause_state_store.may_load(storage)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_dependencies;
+    use rstest::rstest;
+
+    use super::*;
+
+    const PAUSE_STATE: PauseStateStore = Item::new("pause_state");
+
+    #[rstest]
+    #[case::not_paused(None, Ok(false), false)]
+    #[case::frozen(
+        Some(PauseMode::Frozen),
+        Err(ContractError::ContractPaused { reason: "oracle compromised".to_string() }),
+        true
+    )]
+    #[case::strict(Some(PauseMode::Strict), Ok(true), true)]
+    fn test_pause_state(
+        #[case] mode: Option<PauseMode>,
+        #[case] expected_authentication: Result<bool, ContractError>,
+        #[case] expected_strict: bool,
+    ) {
+        let mut deps = mock_dependencies();
+
+        if let Some(mode) = mode {
+            PAUSE_STATE
+                .save(
+                    &mut deps.storage,
+                    &PauseState {
+                        mode,
+                        reason: "oracle compromised".to_string(),
+                        paused_by: Addr::unchecked("guardian"),
+                        paused_at: Timestamp::from_seconds(1),
+                    },
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            ensure_authentication_allowed(&PAUSE_STATE, &deps.storage),
+            expected_authentication
+        );
+        assert_eq!(
+            is_strict(&PAUSE_STATE, &deps.storage).unwrap(),
+            expected_strict
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Decimal, Empty, Order, StdResult, Storage};
+use cw_storage_plus::Map;
+
+use super::{PriceError, PriceInfo};
+
+/// Aliases of the denoms representing the same asset, e.g. `USDC.axl` for `USDC`,
+/// keyed by the alias denom.
+pub type DenomAliasStore<'a> = Map<'a, &'a str, DenomAlias>;
+
+/// Index of the aliases keyed by their canonical denom and the alias denom,
+/// so that the aliases of a canonical denom are looked up without going through all the aliases.
+pub type CanonicalDenomAliasStore<'a> = Map<'a, (&'a str, &'a str), Empty>;
+
+/// Alias of a denom that is priced by its canonical denom instead of its own swap routes.
+#[cw_serde]
+pub struct DenomAlias {
+    /// Denom that the alias shares the price with
+    pub canonical_denom: String,
+
+    /// Units of the canonical denom worth 1 unit of the alias, defaults to 1
+    pub ratio: Option<Decimal>,
+}
+
+impl DenomAlias {
+    /// Price info of the alias derived from the price info of the canonical denom
+    pub fn price_info(&self, canonical_price_info: PriceInfo) -> Result<PriceInfo, PriceError> {
+        let Some(ratio) = self.ratio else {
+            return Ok(canonical_price_info);
+        };
+
+        Ok(PriceInfo {
+            price: canonical_price_info.price.checked_mul(ratio)?,
+            ..canonical_price_info
+        })
+    }
+}
+
+/// Alias of the denom if it's aliased and not the quote denom,
+/// quote denom is always priced at 1 even if it's aliased.
+pub fn resolve_alias(
+    denom_alias_store: &DenomAliasStore,
+    storage: &dyn Storage,
+    quote_denom: &str,
+    denom: &str,
+) -> StdResult<Option<DenomAlias>> {
+    if denom == quote_denom {
+        return Ok(None);
+    }
+
+    denom_alias_store.may_load(storage, denom)
+}
+
+/// Save the alias of `denom` along with its entry in the canonical denom index,
+/// replacing the existing alias of `denom` if any.
+pub fn save_denom_alias(
+    denom_alias_store: &DenomAliasStore,
+    canonical_denom_alias_store: &CanonicalDenomAliasStore,
+    storage: &mut dyn Storage,
+    denom: &str,
+    alias: &DenomAlias,
+) -> StdResult<()> {
+    remove_denom_alias(
+        denom_alias_store,
+        canonical_denom_alias_store,
+        storage,
+        denom,
+    )?;
+
+    denom_alias_store.save(storage, denom, alias)?;
+    canonical_denom_alias_store.save(storage, (alias.canonical_denom.as_str(), denom), &Empty {})
+}
+
+/// Remove the alias of `denom` along with its entry in the canonical denom index
+pub fn remove_denom_alias(
+    denom_alias_store: &DenomAliasStore,
+    canonical_denom_alias_store: &CanonicalDenomAliasStore,
+    storage: &mut dyn Storage,
+    denom: &str,
+) -> StdResult<()> {
+    if let Some(alias) = denom_alias_store.may_load(storage, denom)? {
+        denom_alias_store.remove(storage, denom);
+        canonical_denom_alias_store.remove(storage, (alias.canonical_denom.as_str(), denom));
+    }
+
+    Ok(())
+}
+
+/// Aliases of the canonical denom, ordered by the alias denom
+pub fn aliases_of(
+    denom_alias_store: &DenomAliasStore,
+    canonical_denom_alias_store: &CanonicalDenomAliasStore,
+    storage: &dyn Storage,
+    canonical_denom: &str,
+) -> StdResult<Vec<(String, DenomAlias)>> {
+    canonical_denom_alias_store
+        .prefix(canonical_denom)
+        .keys(storage, None, None, Order::Ascending)
+        .map(|denom| {
+            let denom = denom?;
+            let alias = denom_alias_store.load(storage, &denom)?;
+            Ok((denom, alias))
+        })
+        .collect()
+}
+
+/// Validate the alias of `denom` against the existing aliases, aliases can't be chained
+/// so every alias resolves to its canonical denom in a single step.
+pub fn validate_denom_alias(
+    denom_alias_store: &DenomAliasStore,
+    canonical_denom_alias_store: &CanonicalDenomAliasStore,
+    storage: &dyn Storage,
+    denom: &str,
+    alias: &DenomAlias,
+) -> Result<(), PriceError> {
+    let canonical_denom = alias.canonical_denom.as_str();
+
+    ensure!(
+        denom != canonical_denom,
+        PriceError::InvalidDenomAlias {
+            denom: denom.to_string(),
+            reason: "denom can't be an alias of itself".to_string()
+        }
+    );
+
+    ensure!(
+        !alias.ratio.is_some_and(|ratio| ratio.is_zero()),
+        PriceError::InvalidDenomAlias {
+            denom: denom.to_string(),
+            reason: "ratio must be greater than zero".to_string()
+        }
+    );
+
+    ensure!(
+        !denom_alias_store.has(storage, canonical_denom),
+        PriceError::InvalidDenomAlias {
+            denom: denom.to_string(),
+            reason: format!("canonical denom {canonical_denom} is an alias itself")
+        }
+    );
+
+    let is_canonical_denom = canonical_denom_alias_store
+        .prefix(denom)
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .is_some();
+
+    ensure!(
+        !is_canonical_denom,
+        PriceError::InvalidDenomAlias {
+            denom: denom.to_string(),
+            reason: "denom is the canonical denom of other aliases".to_string()
+        }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_dependencies, Timestamp};
+    use rstest::rstest;
+
+    use super::*;
+
+    const DENOM_ALIASES: DenomAliasStore = Map::new("denom_aliases");
+    const CANONICAL_DENOM_ALIASES: CanonicalDenomAliasStore =
+        Map::new("denom_aliases_by_canonical_denom");
+
+    fn alias(canonical_denom: &str, ratio: Option<Decimal>) -> DenomAlias {
+        DenomAlias {
+            canonical_denom: canonical_denom.to_string(),
+            ratio,
+        }
+    }
+
+    #[rstest]
+    #[case::valid("uusdc.axl", alias("uusdc", None), Ok(()))]
+    #[case::valid_with_ratio("uusdc.axl", alias("uusdc", Some(Decimal::percent(99))), Ok(()))]
+    #[case::override_existing_alias("uusdt.axl", alias("uusdc", None), Ok(()))]
+    #[case::self_alias(
+        "uusdc",
+        alias("uusdc", None),
+        Err("denom can't be an alias of itself")
+    )]
+    #[case::zero_ratio(
+        "uusdc.axl",
+        alias("uusdc", Some(Decimal::zero())),
+        Err("ratio must be greater than zero")
+    )]
+    #[case::chained(
+        "uusdc.eth",
+        alias("uusdt.axl", None),
+        Err("canonical denom uusdt.axl is an alias itself")
+    )]
+    #[case::canonical_denom_as_alias(
+        "uusdt",
+        alias("uusdc", None),
+        Err("denom is the canonical denom of other aliases")
+    )]
+    fn test_validate_denom_alias(
+        #[case] denom: &str,
+        #[case] alias: DenomAlias,
+        #[case] expected: Result<(), &str>,
+    ) {
+        let mut deps = mock_dependencies();
+        save_denom_alias(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            &mut deps.storage,
+            "uusdt.axl",
+            &DenomAlias {
+                canonical_denom: "uusdt".to_string(),
+                ratio: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate_denom_alias(
+                &DENOM_ALIASES,
+                &CANONICAL_DENOM_ALIASES,
+                &deps.storage,
+                denom,
+                &alias
+            ),
+            expected.map_err(|reason| PriceError::InvalidDenomAlias {
+                denom: denom.to_string(),
+                reason: reason.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_aliases_of() {
+        let mut deps = mock_dependencies();
+        let aliases_of = |storage: &dyn Storage, canonical_denom: &str| {
+            aliases_of(
+                &DENOM_ALIASES,
+                &CANONICAL_DENOM_ALIASES,
+                storage,
+                canonical_denom,
+            )
+            .unwrap()
+        };
+
+        for (denom, canonical_denom) in [
+            ("uusdc.eth", "uusdc"),
+            ("uusdc.axl", "uusdc"),
+            ("uosmo.axl", "uosmo"),
+        ] {
+            save_denom_alias(
+                &DENOM_ALIASES,
+                &CANONICAL_DENOM_ALIASES,
+                &mut deps.storage,
+                denom,
+                &alias(canonical_denom, None),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            aliases_of(&deps.storage, "uusdc"),
+            vec![
+                ("uusdc.axl".to_string(), alias("uusdc", None)),
+                ("uusdc.eth".to_string(), alias("uusdc", None)),
+            ]
+        );
+
+        // overriding an alias moves it to the new canonical denom
+        save_denom_alias(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            &mut deps.storage,
+            "uusdc.eth",
+            &alias("uosmo", None),
+        )
+        .unwrap();
+        remove_denom_alias(
+            &DENOM_ALIASES,
+            &CANONICAL_DENOM_ALIASES,
+            &mut deps.storage,
+            "uosmo.axl",
+        )
+        .unwrap();
+
+        assert_eq!(
+            aliases_of(&deps.storage, "uusdc"),
+            vec![("uusdc.axl".to_string(), alias("uusdc", None))]
+        );
+        assert_eq!(
+            aliases_of(&deps.storage, "uosmo"),
+            vec![("uusdc.eth".to_string(), alias("uosmo", None))]
+        );
+        assert_eq!(aliases_of(&deps.storage, "uatom"), vec![]);
+    }
+
+    #[rstest]
+    #[case::no_ratio(None, "1.5")]
+    #[case::with_ratio(Some(Decimal::percent(200)), "3")]
+    fn test_denom_alias_price_info(#[case] ratio: Option<Decimal>, #[case] expected: &str) {
+        let canonical_price_info =
+            PriceInfo::fixed(Decimal::percent(150), Timestamp::from_seconds(1));
+
+        assert_eq!(
+            alias("uusdc", ratio)
+                .price_info(canonical_price_info.clone())
+                .unwrap(),
+            PriceInfo {
+                price: expected.parse().unwrap(),
+                ..canonical_price_info
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_alias() {
+        let mut deps = mock_dependencies();
+        DENOM_ALIASES
+            .save(&mut deps.storage, "uusdc.axl", &alias("uusdc", None))
+            .unwrap();
+
+        assert_eq!(
+            resolve_alias(&DENOM_ALIASES, &deps.storage, "uusdc", "uusdc.axl").unwrap(),
+            Some(alias("uusdc", None))
+        );
+        assert_eq!(
+            resolve_alias(&DENOM_ALIASES, &deps.storage, "uusdc", "uosmo").unwrap(),
+            None
+        );
+
+        // quote denom is never resolved as an alias
+        assert_eq!(
+            resolve_alias(&DENOM_ALIASES, &deps.storage, "uusdc.axl", "uusdc.axl").unwrap(),
+            None
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Uint64;
+use cosmwasm_std::{Decimal, Uint128, Uint64};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
 
 #[cw_serde]
 pub struct PriceResolutionConfig {
@@ -13,4 +14,150 @@ pub struct PriceResolutionConfig {
 
     /// Twap duration in nanoseconds
     pub twap_duration: Uint64,
+
+    /// Kind of twap used to calculate the price, defaults to arithmetic.
+    /// Can be overridden per tracked denom.
+    #[serde(default)]
+    pub twap_kind: TwapKind,
+
+    /// Maximum change of the price relative to the cached price allowed per refresh,
+    /// e.g. `0.5` allows the refreshed price to be within 50% of the cached price.
+    /// Refreshed price outside of this range will be rejected until accepted by the admin.
+    /// If not set, price change is not limited.
+    pub max_price_change_per_refresh: Option<Decimal>,
+
+    /// How to handle the cached price when the block time is behind its last updated time,
+    /// e.g. on simulation or query at an older height. Defaults to treating the price as fresh.
+    #[serde(default)]
+    pub block_time_behind_last_update: BlockTimeBehindLastUpdate,
+
+    /// Minimum liquidity of each pool along the swap routes required to track a denom,
+    /// measured as the value of the pool's reserve of the hop's output denom in quote denom.
+    /// If not set, pool liquidity is not checked.
+    pub min_pool_liquidity: Option<Uint128>,
+
+    /// Fall back to the spot price when the twap can't be queried through any of the swap routes,
+    /// e.g. the pool is younger than the twap duration. If not set, refreshing the price fails.
+    pub spot_price_fallback: Option<SpotPriceFallback>,
+
+    /// Maximum age in nanoseconds of the cached price that is still used when refreshing it fails,
+    /// so that a temporarily unavailable price source doesn't block spending.
+    /// Only has effect if greater than the staleness threshold. If not set, refreshing the price fails.
+    #[serde(default)]
+    pub max_price_age: Option<Uint64>,
+}
+
+/// Bounds of the spot price used when the twap is unavailable.
+#[cw_serde]
+#[derive(Copy)]
+pub struct SpotPriceFallback {
+    /// Maximum deviation of the spot price from the last twap price,
+    /// e.g. `0.1` only accepts spot price within 10% of the last twap price.
+    pub max_deviation: Decimal,
+
+    /// Markup applied to the accepted spot price when valuing spent coins, e.g. `0.05` values
+    /// the spent denom at 105% of the spot price. Received coins are valued at the spot price
+    /// without the markup, so that neither side undercounts the spending.
+    pub markup: Decimal,
+}
+
+/// Handling of the cached price when the block time is behind its last updated time.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum BlockTimeBehindLastUpdate {
+    /// Use the cached price as if it's fresh and emit an event about it
+    #[default]
+    TreatAsFresh,
+
+    /// Fail the price resolution
+    Reject,
+}
+
+/// Kind of twap to query from the twap module.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum TwapKind {
+    /// Arithmetic mean of the spot prices over the twap duration
+    #[default]
+    Arithmetic,
+
+    /// Geometric mean of the spot prices over the twap duration,
+    /// less sensitive to short price spikes than the arithmetic mean
+    Geometric,
+}
+
+/// External oracle contract that the price of a tracked denom is queried from.
+#[cw_serde]
+pub struct Oracle {
+    /// Address of the oracle contract
+    pub contract: String,
+
+    /// Smart query sent to the oracle contract as JSON, where `{denom}` and `{quote_denom}`
+    /// are replaced by the tracked denom and the quote denom,
+    /// e.g. `{"price":{"base":"{denom}","quote":"{quote_denom}"}}`.
+    pub query_template: String,
+
+    /// Keys of the nested objects leading to the price in the query response,
+    /// e.g. `["price", "rate"]` for `{"price":{"rate":"1.5"}}`.
+    /// The price must be a decimal string or an integer.
+    pub price_path: Vec<String>,
+}
+
+#[cw_serde]
+pub struct TrackedDenom {
+    pub denom: String,
+    pub swap_routes: Vec<SwapAmountInRoute>,
+
+    /// Swap routes to fall back to, in order, when the price can't be resolved through `swap_routes`
+    #[serde(default)]
+    pub fallback_swap_routes: Vec<Vec<SwapAmountInRoute>>,
+
+    /// Twap kind used for this denom, overrides the one set in price resolution config
+    pub twap_kind: Option<TwapKind>,
+
+    /// Staleness threshold in nanoseconds for this denom,
+    /// overrides the one set in price resolution config
+    pub staleness_threshold: Option<Uint64>,
+
+    /// Twap duration in nanoseconds for this denom,
+    /// overrides the one set in price resolution config
+    pub twap_duration: Option<Uint64>,
+
+    /// Fixed price of the denom set by the admin, e.g. for pegged stablecoins.
+    /// Fixed price never expires and swap routes are not used to resolve it.
+    pub fixed_price: Option<Decimal>,
+
+    /// Oracle contract to resolve the price from instead of the twap of the swap routes,
+    /// swap routes must be empty if set.
+    #[serde(default)]
+    pub oracle: Option<Oracle>,
+}
+
+impl TrackedDenom {
+    /// Denom priced by twap through `swap_routes` without any per-denom override
+    pub fn twap(denom: impl Into<String>, swap_routes: Vec<SwapAmountInRoute>) -> Self {
+        TrackedDenom {
+            denom: denom.into(),
+            swap_routes,
+            fallback_swap_routes: vec![],
+            twap_kind: None,
+            staleness_threshold: None,
+            twap_duration: None,
+            fixed_price: None,
+            oracle: None,
+        }
+    }
+
+    /// Denom with price fixed by the admin
+    pub fn fixed(denom: impl Into<String>, price: Decimal) -> Self {
+        TrackedDenom {
+            fixed_price: Some(price),
+            ..TrackedDenom::twap(denom, vec![])
+        }
+    }
+
+    /// All swap routes of the denom, starting with the primary one followed by the fallbacks
+    pub fn all_swap_routes(&self) -> impl Iterator<Item = &Vec<SwapAmountInRoute>> {
+        std::iter::once(&self.swap_routes).chain(self.fallback_swap_routes.iter())
+    }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, Timestamp, Uint64};
+use cosmwasm_std::{Decimal, OverflowError, Timestamp, Uint128, Uint64};
 use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
 
 use thiserror::Error;
@@ -32,6 +32,67 @@ pub enum PriceError {
         quote_denom: String,
         start_time: Timestamp,
     },
+
+    #[error("Pool {pool_id} not found")]
+    PoolNotFound { pool_id: u64 },
+
+    #[error("Pool {pool_id} of type {pool_type} is not supported for price resolution")]
+    UnsupportedPoolType { pool_id: u64, pool_type: String },
+
+    #[error("Denom {denom} is not in pool {pool_id}")]
+    DenomNotInPool { pool_id: u64, denom: String },
+
+    #[error("Insufficient liquidity in pool {pool_id}: {liquidity} is below the minimum of {min_pool_liquidity}")]
+    InsufficientPoolLiquidity {
+        pool_id: u64,
+        liquidity: Uint128,
+        min_pool_liquidity: Uint128,
+    },
+
+    #[error("Max hops of the discovered swap routes must be between 1 and {max}, got {max_hops}")]
+    InvalidMaxHops { max_hops: u32, max: u32 },
+
+    #[error("No swap routes found from {denom} to quote denom {quote_denom}")]
+    NoSwapRoutesFound { denom: String, quote_denom: String },
+
+    #[error("Spot price of {denom}: {spot_price} deviates from the last twap price {twap_price} more than {max_deviation}")]
+    SpotPriceDeviationExceeded {
+        denom: String,
+        spot_price: Decimal,
+        twap_price: Decimal,
+        max_deviation: Decimal,
+    },
+
+    #[error("Denom {denom} priced by an oracle must not have swap routes")]
+    OracleWithSwapRoutes { denom: String },
+
+    #[error("Oracle query error: can't get price for {denom} from oracle {contract}: {error}")]
+    OracleQueryError {
+        denom: String,
+        contract: String,
+        error: String,
+    },
+
+    #[error("Invalid price for {denom} from oracle {contract}: expected a positive decimal at {price_path:?}")]
+    InvalidOraclePrice {
+        denom: String,
+        contract: String,
+        price_path: Vec<String>,
+    },
+
+    #[error("Invalid alias of {denom}: {reason}")]
+    InvalidDenomAlias { denom: String, reason: String },
+
+    #[error("Invalid fixed price for {denom}: {price}, fixed price must be greater than zero")]
+    InvalidFixedPrice { denom: String, price: Decimal },
+
+    #[error("Price deviation exceeded for {denom}: price changed from {cached_price} to {new_price}, but max price change per refresh is {max_price_change}")]
+    PriceDeviationExceeded {
+        denom: String,
+        cached_price: Decimal,
+        new_price: Decimal,
+        max_price_change: Decimal,
+    },
 }
 
 impl PriceError {
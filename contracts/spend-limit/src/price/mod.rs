@@ -1,9 +1,23 @@
+mod alias;
 mod config;
 mod error;
 mod price_info;
 mod price_info_store;
+mod route_discovery;
+mod source;
+mod swap_routes;
 
-pub use config::PriceResolutionConfig;
+pub use alias::{
+    aliases_of, remove_denom_alias, resolve_alias, save_denom_alias, validate_denom_alias,
+    CanonicalDenomAliasStore, DenomAlias, DenomAliasStore,
+};
+pub use config::{
+    BlockTimeBehindLastUpdate, Oracle, PriceResolutionConfig, SpotPriceFallback, TrackedDenom,
+    TwapKind,
+};
 pub use error::PriceError;
 pub use price_info::PriceInfo;
 pub use price_info_store::{get_and_cache_price, get_price, track_denom, PriceInfoStore};
+pub use route_discovery::{discover_swap_routes, MAX_DISCOVERED_SWAP_ROUTES_HOPS};
+pub use source::{price_source, OraclePriceSource, PriceSource, TwapPriceSource};
+pub use swap_routes::validate_swap_routes;
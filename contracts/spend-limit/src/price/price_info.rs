@@ -1,8 +1,8 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Decimal, Timestamp, Uint64};
+use cosmwasm_std::{Decimal, Event, StdResult, Timestamp, Uint64};
 use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
 
-use super::PriceError;
+use super::{Oracle, PriceError, PriceResolutionConfig, SpotPriceFallback, TrackedDenom, TwapKind};
 
 #[cw_serde]
 pub struct PriceInfo {
@@ -14,14 +14,148 @@ pub struct PriceInfo {
 
     /// Paths used to calculate the price
     pub swap_routes: Vec<SwapAmountInRoute>,
+
+    /// Paths to fall back to, in order, when the price can't be calculated through `swap_routes`
+    #[serde(default)]
+    pub fallback_swap_routes: Vec<Vec<SwapAmountInRoute>>,
+
+    /// Index of the route that the price was calculated from,
+    /// `0` is `swap_routes` and `n` is `fallback_swap_routes[n - 1]`
+    #[serde(default)]
+    pub route_index: u32,
+
+    /// Twap kind override for this denom, if not set, the twap kind from the config is used
+    pub twap_kind: Option<TwapKind>,
+
+    /// Staleness threshold override for this denom, if not set, the one from the config is used
+    pub staleness_threshold: Option<Uint64>,
+
+    /// Twap duration override for this denom, if not set, the one from the config is used
+    pub twap_duration: Option<Uint64>,
+
+    /// Whether the price is fixed by the admin, fixed price never expires
+    #[serde(default)]
+    pub fixed: bool,
+
+    /// Last twap price that the spot price fallback was bounded around,
+    /// only set if the price came from the spot price fallback
+    #[serde(default)]
+    pub spot_price_fallback_twap: Option<Decimal>,
+
+    /// Oracle contract that the price is resolved from, if not set, the swap routes are used
+    #[serde(default)]
+    pub oracle: Option<Oracle>,
 }
 
 impl PriceInfo {
+    /// Tracking configuration of the denom that this price info belongs to
+    pub fn tracked_denom(&self, denom: &str) -> TrackedDenom {
+        TrackedDenom {
+            denom: denom.to_string(),
+            swap_routes: self.swap_routes.clone(),
+            fallback_swap_routes: self.fallback_swap_routes.clone(),
+            twap_kind: self.twap_kind,
+            staleness_threshold: self.staleness_threshold,
+            twap_duration: self.twap_duration,
+            fixed_price: self.fixed.then_some(self.price),
+            oracle: self.oracle.clone(),
+        }
+    }
+
+    /// Price info of a denom with price fixed by the admin
+    pub fn fixed(price: Decimal, block_time: Timestamp) -> Self {
+        PriceInfo {
+            price,
+            fixed: true,
+            ..PriceInfo::quote_denom(block_time)
+        }
+    }
+
+    /// Price info of the quote denom, which is always priced at 1
+    pub fn quote_denom(block_time: Timestamp) -> Self {
+        PriceInfo {
+            price: Decimal::one(),
+            last_updated_time: block_time,
+            swap_routes: vec![],
+            fallback_swap_routes: vec![],
+            route_index: 0,
+            twap_kind: None,
+            staleness_threshold: None,
+            twap_duration: None,
+            fixed: false,
+            spot_price_fallback_twap: None,
+            oracle: None,
+        }
+    }
+
+    /// Price to value the spent coins with. The spot price from the spot price fallback
+    /// is marked up so that the spending isn't undervalued, while received coins are valued
+    /// at [`PriceInfo::price`] so that their credit isn't overvalued.
+    pub fn spent_price(&self, conf: &PriceResolutionConfig) -> StdResult<Decimal> {
+        match (self.spot_price_fallback_twap, conf.spot_price_fallback) {
+            (Some(_), Some(SpotPriceFallback { markup, .. })) => Ok(self
+                .price
+                .checked_mul(Decimal::one().checked_add(markup)?)?),
+            _ => Ok(self.price),
+        }
+    }
+
+    /// Events about how the price of `denom` has been resolved at `block_time`,
+    /// to be emitted by the caller along with its response
+    pub fn events(
+        &self,
+        denom: &str,
+        block_time: Timestamp,
+        staleness_threshold: Uint64,
+    ) -> Vec<Event> {
+        let mut events = vec![];
+
+        // resolved price is only expired if refreshing it failed and the cached price is used instead
+        if !self.fixed && self.has_expired(block_time, staleness_threshold) == Ok(true) {
+            events.push(
+                Event::new("price_stale")
+                    .add_attribute("denom", denom)
+                    .add_attribute("price", self.price.to_string())
+                    .add_attribute(
+                        "last_updated_time",
+                        self.last_updated_time.nanos().to_string(),
+                    ),
+            );
+        }
+
+        if block_time < self.last_updated_time {
+            events.push(
+                Event::new("price_block_time_behind_last_update")
+                    .add_attribute("denom", denom)
+                    .add_attribute("block_time", block_time.nanos().to_string())
+                    .add_attribute(
+                        "last_updated_time",
+                        self.last_updated_time.nanos().to_string(),
+                    ),
+            );
+        }
+
+        if let Some(twap_price) = self.spot_price_fallback_twap {
+            events.push(
+                Event::new("price_spot_price_fallback")
+                    .add_attribute("denom", denom)
+                    .add_attribute("price", self.price.to_string())
+                    .add_attribute("twap_price", twap_price.to_string()),
+            );
+        }
+
+        events
+    }
+
+    /// Check if the price has expired, the staleness threshold override of the denom
+    /// takes precedence over the given default `staleness_threshold`
     pub fn has_expired(
         &self,
         block_time: Timestamp,
         staleness_threshold: Uint64,
     ) -> Result<bool, PriceError> {
+        let staleness_threshold = self.staleness_threshold.unwrap_or(staleness_threshold);
+
         let duration_since_last_update = Uint64::from(block_time.nanos())
             .checked_sub(Uint64::from(self.last_updated_time.nanos()))
             .map_err(|_| {
@@ -38,6 +172,7 @@ impl PriceInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helper::price::price_info;
     use rstest::rstest;
 
     #[rstest]
@@ -61,11 +196,11 @@ mod tests {
         #[case] staleness_threshold: u64,
         #[case] expected: Result<bool, PriceError>,
     ) {
-        let price_info = PriceInfo {
-            price: Decimal::one(),
-            last_updated_time: Timestamp::from_nanos(last_updated_time),
-            swap_routes: vec![],
-        };
+        let price_info = price_info(
+            Decimal::one(),
+            Timestamp::from_nanos(last_updated_time),
+            vec![],
+        );
 
         let block_time = Timestamp::from_nanos(block_time);
         let staleness_threshold = Uint64::from(staleness_threshold);
@@ -75,4 +210,32 @@ mod tests {
             expected
         );
     }
+
+    #[rstest]
+    #[case::override_shorter(3600_000000000, Some(600_000000000), 600_000000000, Ok(true))]
+    #[case::override_shorter(3600_000000000, Some(600_000000000), 599_999999999, Ok(false))]
+    #[case::override_longer(600_000000000, Some(3600_000000000), 600_000000000, Ok(false))]
+    #[case::override_longer(600_000000000, Some(3600_000000000), 3600_000000000, Ok(true))]
+    #[case::no_override(600_000000000, None, 600_000000000, Ok(true))]
+    #[case::no_override(600_000000000, None, 599_999999999, Ok(false))]
+    fn test_has_expired_with_staleness_threshold_override(
+        #[case] default_staleness_threshold: u64,
+        #[case] staleness_threshold_override: Option<u64>,
+        #[case] elapsed: u64,
+        #[case] expected: Result<bool, PriceError>,
+    ) {
+        let last_updated_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let price_info = PriceInfo {
+            staleness_threshold: staleness_threshold_override.map(Uint64::from),
+            ..PriceInfo::fixed(Decimal::one(), last_updated_time)
+        };
+
+        assert_eq!(
+            price_info.has_expired(
+                last_updated_time.plus_nanos(elapsed),
+                Uint64::from(default_staleness_threshold)
+            ),
+            expected
+        );
+    }
 }
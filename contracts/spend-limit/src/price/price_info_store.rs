@@ -1,12 +1,18 @@
-use cosmwasm_std::{ensure, Decimal, Deps, DepsMut, Timestamp};
+use cosmwasm_std::{ensure, Decimal, Deps, DepsMut, Timestamp, Uint64};
 use cw_storage_plus::Map;
 use osmosis_std::shim::Timestamp as ProtoTimestamp;
 use osmosis_std::types::osmosis::{
-    poolmanager::v1beta1::SwapAmountInRoute, twap::v1beta1::TwapQuerier,
+    poolmanager::v1beta1::{PoolmanagerQuerier, SwapAmountInRoute},
+    twap::v1beta1::TwapQuerier,
 };
 
+use super::alias::{resolve_alias, DenomAliasStore};
 use super::price_info::PriceInfo;
-use super::{PriceError, PriceResolutionConfig};
+use super::source::price_source;
+use super::{
+    BlockTimeBehindLastUpdate, PriceError, PriceResolutionConfig, SpotPriceFallback, TrackedDenom,
+    TwapKind,
+};
 
 pub type PriceInfoStore<'a> = Map<'a, &'a str, PriceInfo>;
 
@@ -14,35 +20,106 @@ pub fn track_denom(
     price_info_store: &PriceInfoStore,
     deps: DepsMut,
     conf: &PriceResolutionConfig,
-    denom: &str,
     block_time: Timestamp,
-    swap_routes: Vec<SwapAmountInRoute>,
+    tracked_denom: TrackedDenom,
 ) -> Result<(), PriceError> {
-    let price_info = fetch_twap_price(deps.as_ref(), conf, denom, block_time, swap_routes)?;
+    let price_info = match tracked_denom.fixed_price {
+        Some(price) => {
+            ensure!(
+                !price.is_zero(),
+                PriceError::InvalidFixedPrice {
+                    denom: tracked_denom.denom,
+                    price
+                }
+            );
+
+            PriceInfo::fixed(price, block_time)
+        }
+        None => {
+            let price_source = price_source(&tracked_denom);
+            price_source.validate(deps.as_ref(), conf, block_time)?;
+            price_source.fetch_price(deps.as_ref(), conf, block_time)?
+        }
+    };
+
     price_info_store
-        .save(deps.storage, denom, &price_info)
+        .save(deps.storage, tracked_denom.denom.as_str(), &price_info)
         .map_err(PriceError::StdError)
 }
 
+/// Get the price of the denom and cache it if refreshed, aliases are priced by their canonical denom.
+/// Aliases are shared by all price profiles, so an alias is priced on its own
+/// if its canonical denom is not tracked by the price profile.
 pub fn get_and_cache_price(
     price_info_store: &PriceInfoStore,
-    deps: DepsMut,
+    denom_alias_store: &DenomAliasStore,
+    mut deps: DepsMut,
     conf: &PriceResolutionConfig,
     block_time: Timestamp,
     denom: &str,
 ) -> Result<Option<PriceInfo>, PriceError> {
-    if let Some(price_info) = get_price(price_info_store, deps.as_ref(), conf, block_time, denom)? {
-        price_info_store
-            .save(deps.storage, denom, &price_info)
-            .map_err(PriceError::StdError)?;
+    // price of an alias is cached for its canonical denom
+    if let Some(alias) = resolve_alias(denom_alias_store, deps.storage, &conf.quote_denom, denom)? {
+        if let Some(price_info) = get_and_cache_unaliased_price(
+            price_info_store,
+            deps.branch(),
+            conf,
+            block_time,
+            &alias.canonical_denom,
+        )? {
+            return alias.price_info(price_info).map(Some);
+        }
+    }
 
-        return Ok(Some(price_info));
+    get_and_cache_unaliased_price(price_info_store, deps, conf, block_time, denom)
+}
+
+/// Get the price of the denom, aliases are priced by their canonical denom
+/// or on their own if the canonical denom is not tracked by the price profile.
+pub fn get_price(
+    price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
+    deps: Deps,
+    conf: &PriceResolutionConfig,
+    block_time: Timestamp,
+    denom: &str,
+) -> Result<Option<PriceInfo>, PriceError> {
+    if let Some(alias) = resolve_alias(denom_alias_store, deps.storage, &conf.quote_denom, denom)? {
+        if let Some(price_info) = get_unaliased_price(
+            price_info_store,
+            deps,
+            conf,
+            block_time,
+            &alias.canonical_denom,
+        )? {
+            return alias.price_info(price_info).map(Some);
+        }
+    }
+
+    get_unaliased_price(price_info_store, deps, conf, block_time, denom)
+}
+
+fn get_and_cache_unaliased_price(
+    price_info_store: &PriceInfoStore,
+    deps: DepsMut,
+    conf: &PriceResolutionConfig,
+    block_time: Timestamp,
+    denom: &str,
+) -> Result<Option<PriceInfo>, PriceError> {
+    let Some(price_info) =
+        get_unaliased_price(price_info_store, deps.as_ref(), conf, block_time, denom)?
+    else {
+        return Ok(None);
     };
 
-    Ok(None)
+    price_info_store
+        .save(deps.storage, denom, &price_info)
+        .map_err(PriceError::StdError)?;
+
+    Ok(Some(price_info))
 }
 
-pub fn get_price(
+fn get_unaliased_price(
     price_info_store: &PriceInfoStore,
     deps: Deps,
     conf: &PriceResolutionConfig,
@@ -51,11 +128,7 @@ pub fn get_price(
 ) -> Result<Option<PriceInfo>, PriceError> {
     // if denom is quote denom, return 1
     if denom == conf.quote_denom.as_str() {
-        return Ok(Some(PriceInfo {
-            price: Decimal::one(),
-            last_updated_time: block_time,
-            swap_routes: vec![],
-        }));
+        return Ok(Some(PriceInfo::quote_denom(block_time)));
     }
 
     // if no cached price, it means that it's not tracked, return None
@@ -63,70 +136,284 @@ pub fn get_price(
         return Ok(None);
     };
 
-    // if cached price is not over staleness threshold, return it
-    if !price_info.has_expired(block_time, conf.staleness_threshold)? {
+    // if price is fixed or cached price is not over staleness threshold, return it
+    if price_info.fixed || !has_expired(conf, &price_info, block_time)? {
         return Ok(Some(price_info));
     }
 
     // else fetch the new price and cache it
-    let price_info = fetch_twap_price(deps, conf, denom, block_time, price_info.swap_routes)?;
+    let tracked_denom = price_info.tracked_denom(denom);
+    let fetched_price_info = match (
+        price_source(&tracked_denom).fetch_price(deps, conf, block_time),
+        &conf.spot_price_fallback,
+    ) {
+        (Err(PriceError::TwapQueryError { .. }), Some(spot_price_fallback)) => {
+            fetch_spot_price_fallback(deps, spot_price_fallback, block_time, denom, &price_info)
+        }
+        (res, _) => res,
+    };
 
-    Ok(Some(price_info))
+    // refreshed price deviating too much from the cached price is rejected like a failed refresh
+    let refreshed_price_info = fetched_price_info.and_then(|new_price_info| {
+        ensure_price_change_within_limit(conf, denom, &price_info, &new_price_info)?;
+        Ok(new_price_info)
+    });
+
+    match refreshed_price_info {
+        Ok(new_price_info) => Ok(Some(new_price_info)),
+        // keep using the cached price until it gets older than max price age
+        Err(_) if within_max_price_age(conf, &price_info, block_time) => Ok(Some(price_info)),
+        Err(err) => Err(err),
+    }
+}
+
+fn has_expired(
+    conf: &PriceResolutionConfig,
+    price_info: &PriceInfo,
+    block_time: Timestamp,
+) -> Result<bool, PriceError> {
+    match price_info.has_expired(block_time, conf.staleness_threshold) {
+        // block time behind the last update means the cached price is as fresh as it gets
+        Err(PriceError::CurrentBlockTimeBehindLastUpdate { .. })
+            if conf.block_time_behind_last_update == BlockTimeBehindLastUpdate::TreatAsFresh =>
+        {
+            Ok(false)
+        }
+        res => res,
+    }
+}
+
+/// Whether the cached price is younger than `max_price_age`, if set.
+fn within_max_price_age(
+    conf: &PriceResolutionConfig,
+    price_info: &PriceInfo,
+    block_time: Timestamp,
+) -> bool {
+    let age = block_time
+        .nanos()
+        .saturating_sub(price_info.last_updated_time.nanos());
+
+    conf.max_price_age
+        .is_some_and(|max_price_age| age < max_price_age.u64())
+}
+
+/// Ensure that the refreshed price does not deviate from the cached price
+/// more than `max_price_change_per_refresh`, if set.
+fn ensure_price_change_within_limit(
+    conf: &PriceResolutionConfig,
+    denom: &str,
+    cached_price_info: &PriceInfo,
+    new_price_info: &PriceInfo,
+) -> Result<(), PriceError> {
+    let Some(max_price_change) = conf.max_price_change_per_refresh else {
+        return Ok(());
+    };
+
+    let cached_price = cached_price_info.price;
+    let new_price = new_price_info.price;
+    let price_change = new_price.abs_diff(cached_price);
+
+    ensure!(
+        price_change <= cached_price.checked_mul(max_price_change)?,
+        PriceError::PriceDeviationExceeded {
+            denom: denom.to_string(),
+            cached_price,
+            new_price,
+            max_price_change,
+        }
+    );
+
+    Ok(())
 }
 
-fn fetch_twap_price(
+/// Fetch the twap price of the tracked denom, swap routes are tried in order
+/// and the first one that resolves to a price is used.
+pub(super) fn fetch_twap_price(
     deps: Deps,
     conf: &PriceResolutionConfig,
-    base_denom: &str,
     block_time: Timestamp,
-    swap_routes: Vec<SwapAmountInRoute>,
+    tracked_denom: &TrackedDenom,
 ) -> Result<PriceInfo, PriceError> {
-    // Ensure that the swap routes end with the quote denom
+    // Ensure that all the swap routes end with the quote denom
+    for swap_routes in tracked_denom.all_swap_routes() {
+        ensure!(
+            valid_swap_routes(swap_routes, conf.quote_denom.as_str()),
+            PriceError::SwapRoutesMustEndWithQuoteDenom {
+                quote_denom: conf.quote_denom.to_string(),
+                swap_routes: swap_routes.clone()
+            }
+        );
+    }
+
+    let fetch_route_price = |swap_routes: &[SwapAmountInRoute]| {
+        fetch_swap_routes_price(
+            deps,
+            conf,
+            tracked_denom.denom.as_str(),
+            block_time,
+            swap_routes,
+            tracked_denom.twap_kind,
+            tracked_denom.twap_duration,
+        )
+    };
+
+    // fall back to the next route only if the previous one fails,
+    // the error from the primary route is returned if all of them fail
+    let (price, route_index) = match fetch_route_price(&tracked_denom.swap_routes) {
+        Ok(price) => (price, 0),
+        Err(err) => tracked_denom
+            .fallback_swap_routes
+            .iter()
+            .zip(1..)
+            .find_map(|(swap_routes, route_index)| {
+                fetch_route_price(swap_routes)
+                    .ok()
+                    .map(|price| (price, route_index))
+            })
+            .ok_or(err)?,
+    };
+
+    Ok(PriceInfo {
+        price,
+        last_updated_time: block_time,
+        swap_routes: tracked_denom.swap_routes.clone(),
+        fallback_swap_routes: tracked_denom.fallback_swap_routes.clone(),
+        route_index,
+        twap_kind: tracked_denom.twap_kind,
+        staleness_threshold: tracked_denom.staleness_threshold,
+        twap_duration: tracked_denom.twap_duration,
+        fixed: false,
+        spot_price_fallback_twap: None,
+        oracle: None,
+    })
+}
+
+/// Fetch the spot price through the primary swap routes of the cached price info,
+/// bounded around the last twap price. The markup is applied when valuing spent coins,
+/// see [`PriceInfo::spent_price`].
+fn fetch_spot_price_fallback(
+    deps: Deps,
+    spot_price_fallback: &SpotPriceFallback,
+    block_time: Timestamp,
+    denom: &str,
+    cached_price_info: &PriceInfo,
+) -> Result<PriceInfo, PriceError> {
+    let SpotPriceFallback { max_deviation, .. } = *spot_price_fallback;
+
+    let poolmanager_querier = PoolmanagerQuerier::new(&deps.querier);
+    let mut base_denom = denom.to_string();
+    let mut spot_price = Decimal::one();
+
+    for route in cached_price_info.swap_routes.iter() {
+        let hop_spot_price: Decimal = poolmanager_querier
+            .spot_price(
+                route.pool_id,
+                base_denom.clone(),
+                route.token_out_denom.clone(),
+            )?
+            .spot_price
+            .parse()?;
+
+        spot_price = spot_price.checked_mul(hop_spot_price)?;
+        base_denom = route.token_out_denom.clone();
+    }
+
+    // keep bounding around the last twap price while the twap stays unavailable
+    let twap_price = cached_price_info
+        .spot_price_fallback_twap
+        .unwrap_or(cached_price_info.price);
+
     ensure!(
-        valid_swap_routes(&swap_routes, conf.quote_denom.as_str()),
-        PriceError::SwapRoutesMustEndWithQuoteDenom {
-            quote_denom: conf.quote_denom.to_string(),
-            swap_routes
+        spot_price.abs_diff(twap_price) <= twap_price.checked_mul(max_deviation)?,
+        PriceError::SpotPriceDeviationExceeded {
+            denom: denom.to_string(),
+            spot_price,
+            twap_price,
+            max_deviation,
         }
     );
 
-    // swap_routes will never be empty, as checked in the above function
+    Ok(PriceInfo {
+        price: spot_price,
+        last_updated_time: block_time,
+        route_index: 0,
+        spot_price_fallback_twap: Some(twap_price),
+        ..cached_price_info.clone()
+    })
+}
+
+/// Fetch the twap price of the base denom by multiplying twap of each pool along the swap routes.
+fn fetch_swap_routes_price(
+    deps: Deps,
+    conf: &PriceResolutionConfig,
+    base_denom: &str,
+    block_time: Timestamp,
+    swap_routes: &[SwapAmountInRoute],
+    twap_kind: Option<TwapKind>,
+    twap_duration: Option<Uint64>,
+) -> Result<Decimal, PriceError> {
+    let twaps = fetch_swap_routes_twaps(
+        deps,
+        conf,
+        base_denom,
+        block_time,
+        swap_routes,
+        twap_kind,
+        twap_duration,
+    )?;
+
+    // swap_routes will never be empty, as checked before fetching the price
     // so price will never remain 1 implicitly
-    let mut price = Decimal::one();
+    twaps
+        .into_iter()
+        .try_fold(Decimal::one(), |price, twap| price.checked_mul(twap))
+        .map_err(PriceError::from)
+}
 
-    let start_time = block_time.minus_nanos(conf.twap_duration.u64());
+/// Fetch the twap of each pool along the swap routes, in the same order as the swap routes.
+pub(super) fn fetch_swap_routes_twaps(
+    deps: Deps,
+    conf: &PriceResolutionConfig,
+    base_denom: &str,
+    block_time: Timestamp,
+    swap_routes: &[SwapAmountInRoute],
+    twap_kind: Option<TwapKind>,
+    twap_duration: Option<Uint64>,
+) -> Result<Vec<Decimal>, PriceError> {
+    let twap_duration = twap_duration.unwrap_or(conf.twap_duration);
+    let start_time = block_time.minus_nanos(twap_duration.u64());
     let proto_start_time = to_proto_timestamp(start_time);
     let mut base_denom = base_denom.to_string();
+    let twap_querier = TwapQuerier::new(&deps.querier);
+    let mut twaps = Vec::with_capacity(swap_routes.len());
 
     for route in swap_routes.iter() {
         let pool_id = route.pool_id;
+        let quote_denom = route.token_out_denom.clone();
+        let proto_start_time = Some(proto_start_time.clone());
 
-        let arithmetic_twap = TwapQuerier::new(&deps.querier)
-            .arithmetic_twap_to_now(
+        let twap = match twap_kind.unwrap_or(conf.twap_kind) {
+            TwapKind::Arithmetic => twap_querier
+                .arithmetic_twap_to_now(pool_id, base_denom.clone(), quote_denom, proto_start_time)
+                .map(|res| res.arithmetic_twap),
+            TwapKind::Geometric => twap_querier
+                .geometric_twap_to_now(pool_id, base_denom.clone(), quote_denom, proto_start_time)
+                .map(|res| res.geometric_twap),
+        }
+        .map_err(|_| {
+            PriceError::twap_query_error(
                 pool_id,
-                base_denom.clone(),
-                route.token_out_denom.clone(),
-                Some(proto_start_time.clone()),
+                base_denom.as_str(),
+                route.token_out_denom.as_str(),
+                start_time,
             )
-            .map_err(|_| {
-                PriceError::twap_query_error(
-                    pool_id,
-                    base_denom.as_str(),
-                    route.token_out_denom.as_str(),
-                    start_time,
-                )
-            })?
-            .arithmetic_twap;
+        })?;
 
-        price = price.checked_mul(arithmetic_twap.parse()?)?;
+        twaps.push(twap.parse()?);
         base_denom = route.token_out_denom.clone();
     }
 
-    Ok(PriceInfo {
-        price,
-        last_updated_time: block_time,
-        swap_routes,
-    })
+    Ok(twaps)
 }
 
 fn to_proto_timestamp(timestamp: Timestamp) -> ProtoTimestamp {
@@ -136,7 +423,7 @@ fn to_proto_timestamp(timestamp: Timestamp) -> ProtoTimestamp {
     }
 }
 
-fn valid_swap_routes(swap_routes: &[SwapAmountInRoute], quote_denom: &str) -> bool {
+pub(super) fn valid_swap_routes(swap_routes: &[SwapAmountInRoute], quote_denom: &str) -> bool {
     if let Some(last_swap_route) = swap_routes.last() {
         last_swap_route.token_out_denom == quote_denom
     } else {
@@ -146,55 +433,67 @@ fn valid_swap_routes(swap_routes: &[SwapAmountInRoute], quote_denom: &str) -> bo
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::{ContractResult, OverflowError, OverflowOperation};
-    use osmosis_std::types::osmosis::twap::v1beta1::ArithmeticTwapToNowResponse;
+    use std::{cell::Cell, rc::Rc};
+
+    use cosmwasm_std::{coin, Binary, ContractResult, Event, OverflowError, OverflowOperation};
+    use osmosis_std::types::osmosis::{
+        poolmanager::v1beta1::SpotPriceResponse,
+        twap::v1beta1::{ArithmeticTwapToNowResponse, GeometricTwapToNowResponse},
+    };
     use rstest::rstest;
 
     use crate::{
-        state::PRICE_INFOS,
+        state::{DENOM_ALIASES, PRICE_INFOS},
         test_helper::mock_stargate_querier::{
-            arithmetic_twap_to_now_query_handler, mock_dependencies_with_stargate_querier,
+            arithmetic_twap_to_now_query_handler, balancer_pools_query_handler,
+            combine_query_handlers, geometric_twap_to_now_query_handler,
+            mock_dependencies_with_stargate_querier, oracle_query_handler,
+            spot_price_query_handler,
         },
     };
 
     use super::*;
+    use crate::price::Oracle;
+    use crate::test_helper::price::{price_info, price_resolution_config, tracked_denom};
 
     const UUSDC: &str = "ibc/498A0751C798A0D9A389AA3691123DADA57DAA4FE165D5C75894505B876BA6E4";
     const UATOM: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
 
     #[test]
     fn test_track_denom() {
-        let conf = PriceResolutionConfig {
-            quote_denom: UUSDC.to_string(),
-            staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-            twap_duration: 3_600_000_000_000u64.into(),       // 1h
-        };
+        let conf = price_resolution_config(UUSDC);
         let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
         let expected_start_time =
             to_proto_timestamp(block_time.minus_nanos(conf.twap_duration.u64()));
 
         let mut deps = mock_dependencies_with_stargate_querier(
             &[],
-            arithmetic_twap_to_now_query_handler(Box::new(move |req| {
-                let base_asset = req.base_asset.as_str();
-                let quote_asset = req.quote_asset.as_str();
-                let start_time = req.start_time.clone().unwrap();
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, UUSDC)],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(move |req| {
+                    let base_asset = req.base_asset.as_str();
+                    let quote_asset = req.quote_asset.as_str();
+                    let start_time = req.start_time.clone().unwrap();
 
-                if start_time != expected_start_time {
-                    return ContractResult::Err(format!(
-                        "expected start time: {:?}, got: {:?}",
-                        expected_start_time, start_time
-                    ));
-                }
+                    if start_time != expected_start_time {
+                        return ContractResult::Err(format!(
+                            "expected start time: {:?}, got: {:?}",
+                            expected_start_time, start_time
+                        ));
+                    }
 
-                let arithmetic_twap = match (base_asset, quote_asset) {
-                    ("uosmo", UUSDC) => "1.500000000000000000",
-                    _ => return ContractResult::Err("Price not found".to_string()),
-                }
-                .to_string();
+                    let arithmetic_twap = match (base_asset, quote_asset) {
+                        ("uosmo", UUSDC) => "1.500000000000000000",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
 
-                ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
-            })),
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
         );
 
         let swap_routes = vec![SwapAmountInRoute {
@@ -206,30 +505,25 @@ mod tests {
             &PRICE_INFOS,
             deps.as_mut(),
             &conf,
-            "uosmo",
             block_time,
-            swap_routes.clone(),
+            tracked_denom("uosmo", swap_routes.clone()),
         )
         .unwrap();
 
-        let price_info = PRICE_INFOS.load(deps.as_ref().storage, "uosmo").unwrap();
+        let actual = PRICE_INFOS.load(deps.as_ref().storage, "uosmo").unwrap();
         assert_eq!(
-            price_info,
-            PriceInfo {
-                price: "1.500000000000000000".parse::<Decimal>().unwrap(),
-                last_updated_time: block_time,
+            actual,
+            price_info(
+                "1.500000000000000000".parse::<Decimal>().unwrap(),
+                block_time,
                 swap_routes
-            }
+            )
         );
     }
 
     #[test]
     fn test_get_and_cache_price() {
-        let conf = PriceResolutionConfig {
-            quote_denom: UUSDC.to_string(),
-            staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-            twap_duration: 3_600_000_000_000u64.into(),       // 1h
-        };
+        let conf = price_resolution_config(UUSDC);
         let last_updated_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
 
         let mut deps = mock_dependencies_with_stargate_querier(
@@ -253,11 +547,11 @@ mod tests {
             token_out_denom: UUSDC.to_string(),
         }];
 
-        let cached_price_info = PriceInfo {
-            price: "1.400000000000000000".parse::<Decimal>().unwrap(),
+        let cached_price_info = price_info(
+            "1.400000000000000000".parse::<Decimal>().unwrap(),
             last_updated_time,
-            swap_routes: swap_routes.clone(),
-        };
+            swap_routes.clone(),
+        );
 
         // save cached price
         PRICE_INFOS
@@ -265,19 +559,21 @@ mod tests {
             .unwrap();
 
         // cache hit
-        let price_info = get_and_cache_price(
+        let actual = get_and_cache_price(
             &PRICE_INFOS,
+            &DENOM_ALIASES,
             deps.as_mut(),
             &conf,
             last_updated_time.plus_nanos(1_800_000_000_000u64), // + 30m
             "uosmo",
         )
         .unwrap();
-        assert_eq!(price_info, Some(cached_price_info));
+        assert_eq!(actual, Some(cached_price_info));
 
         // cache miss, update
-        let price_info = get_and_cache_price(
+        let actual = get_and_cache_price(
             &PRICE_INFOS,
+            &DENOM_ALIASES,
             deps.as_mut(),
             &conf,
             last_updated_time.plus_nanos(3_600_000_000_001u64), // + 1h + 1ns
@@ -285,17 +581,18 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            price_info,
-            Some(PriceInfo {
-                price: "1.500000000000000000".parse::<Decimal>().unwrap(),
-                last_updated_time: last_updated_time.plus_nanos(3_600_000_000_001u64), // + 1h + 1ns
-                swap_routes: swap_routes.clone()
-            })
+            actual,
+            Some(price_info(
+                "1.500000000000000000".parse::<Decimal>().unwrap(),
+                last_updated_time.plus_nanos(3_600_000_000_001u64), // + 1h + 1ns
+                swap_routes.clone()
+            ))
         );
 
         // cache hit updated one
-        let price_info = get_and_cache_price(
+        let actual = get_and_cache_price(
             &PRICE_INFOS,
+            &DENOM_ALIASES,
             deps.as_mut(),
             &conf,
             last_updated_time.plus_nanos(3_600_000_000_002u64), // + 1h + 2ns
@@ -303,17 +600,18 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            price_info,
-            Some(PriceInfo {
-                price: "1.500000000000000000".parse::<Decimal>().unwrap(),
-                last_updated_time: last_updated_time.plus_nanos(3_600_000_000_001u64), // + 1h + 1ns
+            actual,
+            Some(price_info(
+                "1.500000000000000000".parse::<Decimal>().unwrap(),
+                last_updated_time.plus_nanos(3_600_000_000_001u64), // + 1h + 1ns
                 swap_routes
-            })
+            ))
         );
 
         // get quote denom
-        let price_info = get_and_cache_price(
+        let actual = get_and_cache_price(
             &PRICE_INFOS,
+            &DENOM_ALIASES,
             deps.as_mut(),
             &conf,
             last_updated_time.plus_nanos(3_600_000_000_002u64), // + 1h + 2ns
@@ -321,24 +619,705 @@ mod tests {
         )
         .unwrap();
         assert_eq!(
-            price_info,
-            Some(PriceInfo {
-                price: Decimal::one(),
-                last_updated_time: last_updated_time.plus_nanos(3_600_000_000_002u64), // + 1h + 2ns
-                swap_routes: vec![]
-            })
+            actual,
+            Some(price_info(
+                Decimal::one(),
+                last_updated_time.plus_nanos(3_600_000_000_002u64), // + 1h + 2ns
+                vec![]
+            ))
         );
 
         // get non-tracked denom
-        let price_info = get_and_cache_price(
+        let actual = get_and_cache_price(
             &PRICE_INFOS,
+            &DENOM_ALIASES,
             deps.as_mut(),
             &conf,
             last_updated_time.plus_nanos(3_600_000_000_002u64), // + 1h + 2ns
             "uatom",
         )
         .unwrap();
-        assert_eq!(price_info, None);
+        assert_eq!(actual, None);
+    }
+
+    #[rstest]
+    #[case::treat_as_fresh(BlockTimeBehindLastUpdate::TreatAsFresh, 1, Ok(()))]
+    #[case::treat_as_fresh(BlockTimeBehindLastUpdate::TreatAsFresh, 3_600_000_000_000, Ok(()))]
+    #[case::reject(
+        BlockTimeBehindLastUpdate::Reject,
+        1,
+        Err(PriceError::current_block_time_behind_last_update(
+            1_708_416_816_000_000_000 - 1,
+            1_708_416_816_000_000_000
+        ))
+    )]
+    fn test_get_and_cache_price_with_block_time_behind_last_update(
+        #[case] block_time_behind_last_update: BlockTimeBehindLastUpdate,
+        #[case] behind_by: u64,
+        #[case] expected: Result<(), PriceError>,
+    ) {
+        let conf = PriceResolutionConfig {
+            block_time_behind_last_update,
+            ..price_resolution_config(UUSDC)
+        };
+        let last_updated_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let block_time = last_updated_time.minus_nanos(behind_by);
+
+        // cached price must not be refreshed
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            arithmetic_twap_to_now_query_handler(Box::new(|_| {
+                ContractResult::Err("twap must not be queried".to_string())
+            })),
+        );
+
+        let cached_price_info = price_info(
+            "1.5".parse::<Decimal>().unwrap(),
+            last_updated_time,
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: UUSDC.to_string(),
+            }],
+        );
+
+        PRICE_INFOS
+            .save(&mut deps.storage, "uosmo", &cached_price_info)
+            .unwrap();
+
+        let result = get_and_cache_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            "uosmo",
+        );
+
+        match expected {
+            Ok(()) => {
+                let actual = result.unwrap().unwrap();
+                assert_eq!(actual, cached_price_info);
+                assert_eq!(
+                    actual.events("uosmo", block_time, conf.staleness_threshold),
+                    vec![Event::new("price_block_time_behind_last_update")
+                        .add_attribute("denom", "uosmo")
+                        .add_attribute("block_time", block_time.nanos().to_string())
+                        .add_attribute("last_updated_time", last_updated_time.nanos().to_string())]
+                );
+            }
+            Err(e) => assert_eq!(result.unwrap_err(), e),
+        }
+
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uosmo").unwrap(),
+            cached_price_info
+        );
+    }
+
+    #[test]
+    fn test_track_denom_with_staleness_threshold_and_twap_duration_overrides() {
+        let conf = price_resolution_config(UUSDC);
+        let staleness_threshold = Uint64::from(600_000_000_000u64); // 10m
+        let twap_duration = Uint64::from(86_400_000_000_000u64); // 24h
+
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                balancer_pools_query_handler(vec![(
+                    1,
+                    vec![coin(1_000_000, "uosmo"), coin(1_500_000, UUSDC)],
+                )]),
+                arithmetic_twap_to_now_query_handler(Box::new(move |req| {
+                    let start_time = req.start_time.clone().unwrap();
+                    let end_time = Timestamp::from_seconds(start_time.seconds as u64)
+                        .plus_nanos(start_time.nanos as u64)
+                        .plus_nanos(twap_duration.u64());
+
+                    // encode the end time of the twap in the price to tell the refreshes apart
+                    ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: end_time.seconds().to_string(),
+                    })
+                })),
+            ]),
+        );
+
+        let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let tracked_denom = TrackedDenom {
+            staleness_threshold: Some(staleness_threshold),
+            twap_duration: Some(twap_duration),
+            ..tracked_denom(
+                "uosmo",
+                vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: UUSDC.to_string(),
+                }],
+            )
+        };
+
+        track_denom(
+            &PRICE_INFOS,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            tracked_denom.clone(),
+        )
+        .unwrap();
+
+        let price_info = PRICE_INFOS.load(&deps.storage, "uosmo").unwrap();
+        assert_eq!(
+            price_info.price,
+            Decimal::from_atomics(block_time.seconds(), 0).unwrap()
+        );
+        assert_eq!(price_info.tracked_denom("uosmo"), tracked_denom);
+
+        // not expired before the overridden staleness threshold
+        let before_expiry = block_time.plus_nanos(staleness_threshold.u64() - 1);
+        let price = get_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_ref(),
+            &conf,
+            before_expiry,
+            "uosmo",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(price, price_info);
+
+        // expired after the overridden staleness threshold, even though it's within the config's
+        let after_expiry = block_time.plus_nanos(staleness_threshold.u64());
+        let price = get_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_ref(),
+            &conf,
+            after_expiry,
+            "uosmo",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            price,
+            PriceInfo {
+                price: Decimal::from_atomics(after_expiry.seconds(), 0).unwrap(),
+                last_updated_time: after_expiry,
+                ..price_info
+            }
+        );
+    }
+
+    #[test]
+    fn test_track_and_refresh_denom_with_oracle() {
+        let conf = price_resolution_config(UUSDC);
+        let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let oracle = Oracle {
+            contract: "oracle".to_string(),
+            query_template: r#"{"price":{"denom":"{denom}"}}"#.to_string(),
+            price_path: vec!["price".to_string()],
+        };
+
+        let price = Rc::new(Cell::new("1.5"));
+        let oracle_price = price.clone();
+
+        // no stargate query is expected for denoms priced by the oracle
+        let mut deps = mock_dependencies_with_stargate_querier(&[], combine_query_handlers(vec![]));
+        deps.querier.update_wasm(oracle_query_handler(
+            "oracle",
+            Box::new(move |query| {
+                assert_eq!(query, Binary::from(br#"{"price":{"denom":"uosmo"}}"#));
+                ContractResult::Ok(
+                    format!(r#"{{"price":"{}"}}"#, oracle_price.get())
+                        .into_bytes()
+                        .into(),
+                )
+            }),
+        ));
+
+        track_denom(
+            &PRICE_INFOS,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            TrackedDenom {
+                oracle: Some(oracle.clone()),
+                ..tracked_denom("uosmo", vec![])
+            },
+        )
+        .unwrap();
+
+        let expected_price_info = |price: &str, last_updated_time: Timestamp| PriceInfo {
+            oracle: Some(oracle.clone()),
+            ..price_info(price.parse().unwrap(), last_updated_time, vec![])
+        };
+
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uosmo").unwrap(),
+            expected_price_info("1.5", block_time)
+        );
+
+        // expired price is refreshed from the oracle
+        price.set("1.6");
+        let block_time = block_time.plus_nanos(conf.staleness_threshold.u64());
+
+        assert_eq!(
+            get_and_cache_price(
+                &PRICE_INFOS,
+                &DENOM_ALIASES,
+                deps.as_mut(),
+                &conf,
+                block_time,
+                "uosmo"
+            )
+            .unwrap(),
+            Some(expected_price_info("1.6", block_time))
+        );
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uosmo").unwrap(),
+            expected_price_info("1.6", block_time)
+        );
+    }
+
+    #[rstest]
+    #[case::fixed_price(Decimal::one(), Ok(()))]
+    #[case::fixed_price(Decimal::percent(99), Ok(()))]
+    #[case::zero_price(
+        Decimal::zero(),
+        Err(PriceError::InvalidFixedPrice {
+            denom: "uusdt".to_string(),
+            price: Decimal::zero(),
+        })
+    )]
+    fn test_track_denom_with_fixed_price(
+        #[case] fixed_price: Decimal,
+        #[case] expected: Result<(), PriceError>,
+    ) {
+        let conf = price_resolution_config(UUSDC);
+        let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+
+        // twap must not be queried for fixed price
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            arithmetic_twap_to_now_query_handler(Box::new(|_| {
+                ContractResult::Err("twap must not be queried".to_string())
+            })),
+        );
+
+        let tracked_denom = TrackedDenom {
+            fixed_price: Some(fixed_price),
+            ..tracked_denom("uusdt", vec![])
+        };
+
+        let result = track_denom(
+            &PRICE_INFOS,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            tracked_denom.clone(),
+        );
+        assert_eq!(result, expected);
+
+        if result.is_err() {
+            assert_eq!(PRICE_INFOS.may_load(&deps.storage, "uusdt").unwrap(), None);
+            return;
+        }
+
+        // fixed price never expires
+        let expected_price_info = PriceInfo::fixed(fixed_price, block_time);
+        for block_time in [block_time, block_time.plus_days(365)] {
+            let price_info = get_and_cache_price(
+                &PRICE_INFOS,
+                &DENOM_ALIASES,
+                deps.as_mut(),
+                &conf,
+                block_time,
+                "uusdt",
+            )
+            .unwrap();
+            assert_eq!(price_info, Some(expected_price_info.clone()));
+        }
+
+        assert_eq!(
+            PRICE_INFOS
+                .load(&deps.storage, "uusdt")
+                .unwrap()
+                .tracked_denom("uusdt"),
+            tracked_denom
+        );
+    }
+
+    #[rstest]
+    #[case::no_limit(None, None, "3.000000000000000000", Ok("3.000000000000000000"))]
+    #[case::within_limit(
+        Some(Decimal::percent(10)),
+        None,
+        "1.650000000000000000",
+        Ok("1.650000000000000000")
+    )]
+    #[case::within_limit(
+        Some(Decimal::percent(10)),
+        None,
+        "1.350000000000000000",
+        Ok("1.350000000000000000")
+    )]
+    #[case::exceeded_cached_within_max_price_age(
+        Some(Decimal::percent(10)),
+        Some(7_200_000_000_000),
+        "1.650000000000000001",
+        Ok("1.5")
+    )]
+    #[case::exceeded_beyond_max_price_age(
+        Some(Decimal::percent(10)),
+        Some(3_600_000_000_000),
+        "1.650000000000000001",
+        Err(PriceError::PriceDeviationExceeded {
+            denom: "uosmo".to_string(),
+            cached_price: "1.5".parse().unwrap(),
+            new_price: "1.650000000000000001".parse().unwrap(),
+            max_price_change: Decimal::percent(10),
+        })
+    )]
+    #[case::increase_exceeded(
+        Some(Decimal::percent(10)),
+        None,
+        "1.650000000000000001",
+        Err(PriceError::PriceDeviationExceeded {
+            denom: "uosmo".to_string(),
+            cached_price: "1.5".parse().unwrap(),
+            new_price: "1.650000000000000001".parse().unwrap(),
+            max_price_change: Decimal::percent(10),
+        })
+    )]
+    #[case::decrease_exceeded(
+        Some(Decimal::percent(10)),
+        None,
+        "1.349999999999999999",
+        Err(PriceError::PriceDeviationExceeded {
+            denom: "uosmo".to_string(),
+            cached_price: "1.5".parse().unwrap(),
+            new_price: "1.349999999999999999".parse().unwrap(),
+            max_price_change: Decimal::percent(10),
+        })
+    )]
+    fn test_get_and_cache_price_with_max_price_change(
+        #[case] max_price_change_per_refresh: Option<Decimal>,
+        #[case] max_price_age: Option<u64>,
+        #[case] new_price: &'static str,
+        #[case] expected: Result<&str, PriceError>,
+    ) {
+        let conf = PriceResolutionConfig {
+            max_price_change_per_refresh,
+            max_price_age: max_price_age.map(Uint64::from),
+            ..price_resolution_config(UUSDC)
+        };
+        let last_updated_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let block_time = last_updated_time.plus_nanos(3_600_000_000_000u64); // + 1h
+
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            arithmetic_twap_to_now_query_handler(Box::new(move |_| {
+                ContractResult::Ok(ArithmeticTwapToNowResponse {
+                    arithmetic_twap: new_price.to_string(),
+                })
+            })),
+        );
+
+        let cached_price_info = price_info(
+            "1.5".parse::<Decimal>().unwrap(),
+            last_updated_time,
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: UUSDC.to_string(),
+            }],
+        );
+
+        PRICE_INFOS
+            .save(&mut deps.storage, "uosmo", &cached_price_info)
+            .unwrap();
+
+        let result = get_and_cache_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            "uosmo",
+        );
+        let stored_price_info = PRICE_INFOS.load(&deps.storage, "uosmo").unwrap();
+
+        match expected {
+            // rejected price falls back to the cached price
+            Ok(expected) if expected.parse::<Decimal>().unwrap() == cached_price_info.price => {
+                assert_eq!(result.unwrap(), Some(cached_price_info.clone()));
+                assert_eq!(stored_price_info, cached_price_info);
+            }
+            Ok(expected) => {
+                let expected = PriceInfo {
+                    price: expected.parse::<Decimal>().unwrap(),
+                    last_updated_time: block_time,
+                    ..cached_price_info
+                };
+                assert_eq!(result.unwrap(), Some(expected.clone()));
+                assert_eq!(stored_price_info, expected);
+            }
+            Err(e) => {
+                assert_eq!(result.unwrap_err(), e);
+                assert_eq!(stored_price_info, cached_price_info);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::refreshed(Some(7_200_000_000_000), 3_600_000_000_000, true, Ok(Some("2")))]
+    #[case::cached_within_max_price_age(
+        Some(7_200_000_000_000),
+        3_600_000_000_000,
+        false,
+        Ok(None)
+    )]
+    #[case::cached_just_before_max_price_age(
+        Some(7_200_000_000_000),
+        7_199_999_999_999,
+        false,
+        Ok(None)
+    )]
+    #[case::beyond_max_price_age(
+        Some(7_200_000_000_000),
+        7_200_000_000_000,
+        false,
+        Err(())
+    )]
+    #[case::no_max_price_age(None, 3_600_000_000_000, false, Err(()))]
+    fn test_get_and_cache_price_with_max_price_age(
+        #[case] max_price_age: Option<u64>,
+        #[case] age: u64,
+        #[case] twap_available: bool,
+        #[case] expected: Result<Option<&str>, ()>,
+    ) {
+        let conf = PriceResolutionConfig {
+            max_price_age: max_price_age.map(Uint64::from),
+            ..price_resolution_config(UUSDC)
+        };
+        let last_updated_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let block_time = last_updated_time.plus_nanos(age);
+
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            arithmetic_twap_to_now_query_handler(Box::new(move |_| {
+                if twap_available {
+                    ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: "2".to_string(),
+                    })
+                } else {
+                    ContractResult::Err("twap unavailable".to_string())
+                }
+            })),
+        );
+
+        let cached_price_info = price_info(
+            "1.5".parse::<Decimal>().unwrap(),
+            last_updated_time,
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: UUSDC.to_string(),
+            }],
+        );
+
+        PRICE_INFOS
+            .save(&mut deps.storage, "uosmo", &cached_price_info)
+            .unwrap();
+
+        let result = get_and_cache_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            "uosmo",
+        );
+        let stored_price_info = PRICE_INFOS.load(&deps.storage, "uosmo").unwrap();
+
+        match expected {
+            Ok(Some(refreshed_price)) => {
+                let expected = PriceInfo {
+                    price: refreshed_price.parse::<Decimal>().unwrap(),
+                    last_updated_time: block_time,
+                    ..cached_price_info
+                };
+                assert_eq!(result.unwrap(), Some(expected.clone()));
+                assert_eq!(stored_price_info, expected);
+                assert!(expected
+                    .events("uosmo", block_time, conf.staleness_threshold)
+                    .is_empty());
+            }
+            Ok(None) => {
+                assert_eq!(result.unwrap(), Some(cached_price_info.clone()));
+                assert_eq!(stored_price_info, cached_price_info);
+                assert_eq!(
+                    cached_price_info.events("uosmo", block_time, conf.staleness_threshold),
+                    vec![Event::new("price_stale")
+                        .add_attribute("denom", "uosmo")
+                        .add_attribute("price", "1.5")
+                        .add_attribute("last_updated_time", last_updated_time.nanos().to_string())]
+                );
+            }
+            Err(()) => {
+                assert_eq!(
+                    result.unwrap_err(),
+                    PriceError::twap_query_error(
+                        1,
+                        "uosmo",
+                        UUSDC,
+                        block_time.minus_nanos(conf.twap_duration.u64())
+                    )
+                );
+                assert_eq!(stored_price_info, cached_price_info);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::fallback_disabled(
+        None,
+        None,
+        "1.6",
+        Err(PriceError::twap_query_error(
+            1,
+            "uosmo",
+            UUSDC,
+            Timestamp::from_nanos(1_708_416_816_000_000_000)
+        ))
+    )]
+    #[case::within_max_deviation(
+        Some(Decimal::percent(10)),
+        None,
+        "1.6",
+        Ok(("1.6", "1.5"))
+    )]
+    #[case::within_max_deviation(
+        Some(Decimal::percent(10)),
+        None,
+        "1.35",
+        Ok(("1.35", "1.5"))
+    )]
+    #[case::max_deviation_exceeded(
+        Some(Decimal::percent(10)),
+        None,
+        "1.650000000000000001",
+        Err(PriceError::SpotPriceDeviationExceeded {
+            denom: "uosmo".to_string(),
+            spot_price: "1.650000000000000001".parse().unwrap(),
+            twap_price: "1.5".parse().unwrap(),
+            max_deviation: Decimal::percent(10),
+        })
+    )]
+    #[case::bounded_around_last_twap(
+        Some(Decimal::percent(10)),
+        Some("1.4"),
+        "1.6",
+        Err(PriceError::SpotPriceDeviationExceeded {
+            denom: "uosmo".to_string(),
+            spot_price: "1.6".parse().unwrap(),
+            twap_price: "1.4".parse().unwrap(),
+            max_deviation: Decimal::percent(10),
+        })
+    )]
+    #[case::bounded_around_last_twap(
+        Some(Decimal::percent(10)),
+        Some("1.4"),
+        "1.54",
+        Ok(("1.54", "1.4"))
+    )]
+    fn test_get_and_cache_price_with_spot_price_fallback(
+        #[case] max_deviation: Option<Decimal>,
+        #[case] cached_spot_price_fallback_twap: Option<&str>,
+        #[case] spot_price: &'static str,
+        #[case] expected: Result<(&str, &str), PriceError>,
+    ) {
+        let conf = PriceResolutionConfig {
+            spot_price_fallback: max_deviation.map(|max_deviation| SpotPriceFallback {
+                max_deviation,
+                markup: Decimal::percent(5),
+            }),
+            ..price_resolution_config(UUSDC)
+        };
+        let last_updated_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+        let block_time = last_updated_time.plus_nanos(3_600_000_000_000u64); // + 1h
+
+        // twap is unavailable, e.g. the pool is younger than the twap duration
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                arithmetic_twap_to_now_query_handler(Box::new(|_| {
+                    ContractResult::Err("twap unavailable".to_string())
+                })),
+                spot_price_query_handler(Box::new(move |req| {
+                    match (
+                        req.base_asset_denom.as_str(),
+                        req.quote_asset_denom.as_str(),
+                    ) {
+                        ("uosmo", UUSDC) => ContractResult::Ok(SpotPriceResponse {
+                            spot_price: spot_price.to_string(),
+                        }),
+                        _ => ContractResult::Err("Price not found".to_string()),
+                    }
+                })),
+            ]),
+        );
+
+        let cached_price_info = PriceInfo {
+            spot_price_fallback_twap: cached_spot_price_fallback_twap
+                .map(|twap| twap.parse().unwrap()),
+            ..price_info(
+                "1.5".parse::<Decimal>().unwrap(),
+                last_updated_time,
+                vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: UUSDC.to_string(),
+                }],
+            )
+        };
+
+        PRICE_INFOS
+            .save(&mut deps.storage, "uosmo", &cached_price_info)
+            .unwrap();
+
+        let result = get_and_cache_price(
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            deps.as_mut(),
+            &conf,
+            block_time,
+            "uosmo",
+        );
+        let stored_price_info = PRICE_INFOS.load(&deps.storage, "uosmo").unwrap();
+
+        match expected {
+            Ok((expected_price, expected_twap)) => {
+                let expected = PriceInfo {
+                    price: expected_price.parse::<Decimal>().unwrap(),
+                    last_updated_time: block_time,
+                    spot_price_fallback_twap: Some(expected_twap.parse().unwrap()),
+                    ..cached_price_info
+                };
+
+                // the markup is applied when valuing spent coins, not to the cached price
+                assert_eq!(expected.price, spot_price.parse::<Decimal>().unwrap());
+                assert_eq!(result.unwrap(), Some(expected.clone()));
+                assert_eq!(stored_price_info, expected);
+
+                assert_eq!(
+                    expected.events("uosmo", block_time, conf.staleness_threshold),
+                    vec![Event::new("price_spot_price_fallback")
+                        .add_attribute("denom", "uosmo")
+                        .add_attribute("price", expected_price)
+                        .add_attribute("twap_price", expected_twap)]
+                );
+            }
+            Err(e) => {
+                assert_eq!(result.unwrap_err(), e);
+                assert_eq!(stored_price_info, cached_price_info);
+            }
+        }
     }
 
     #[rstest]
@@ -391,11 +1370,7 @@ mod tests {
         #[case] swap_routes: Vec<SwapAmountInRoute>,
         #[case] expected: Result<&str, PriceError>,
     ) {
-        let conf = PriceResolutionConfig {
-            quote_denom: quote_denom.to_string(),
-            staleness_threshold: 3_600_000_000_000u64.into(), // 1h
-            twap_duration: 3_600_000_000_000u64.into(),       // 1h
-        };
+        let conf = price_resolution_config(quote_denom);
         let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
 
         let deps = mock_dependencies_with_stargate_querier(
@@ -429,24 +1404,187 @@ mod tests {
         let result = fetch_twap_price(
             deps.as_ref(),
             &conf,
-            base_denom,
             block_time,
-            swap_routes.clone(),
+            &tracked_denom(base_denom, swap_routes.clone()),
         );
 
         match expected {
             Ok(expected) => assert_eq!(
                 result.unwrap(),
-                PriceInfo {
-                    price: expected.parse::<Decimal>().unwrap(),
-                    last_updated_time: block_time,
+                price_info(
+                    expected.parse::<Decimal>().unwrap(),
+                    block_time,
                     swap_routes
+                )
+            ),
+            Err(e) => assert_eq!(result.unwrap_err(), e),
+        }
+    }
+
+    #[rstest]
+    #[case::primary_route(vec![vec![(2, UUSDC)]], Ok(("1.500000000000000000", 0)))]
+    #[case::first_fallback_route(
+        vec![vec![(3, UUSDC)], vec![(4, UUSDC)], vec![(2, UUSDC)]],
+        Ok(("1.400000000000000000", 1))
+    )]
+    #[case::second_fallback_route(
+        vec![vec![(3, UUSDC)], vec![(5, UUSDC)], vec![(1, UATOM), (6, UUSDC)]],
+        Ok(("6.400000000000000000", 2))
+    )]
+    #[case::all_routes_failed(
+        vec![vec![(3, UUSDC)], vec![(5, UUSDC)]],
+        Err(PriceError::twap_query_error(3, "uosmo", UUSDC, block_time.minus_nanos(3_600_000_000_000u64)))
+    )]
+    #[case::fallback_not_ending_with_quote_denom(
+        vec![vec![(2, UUSDC)], vec![(1, UATOM)]],
+        Err(PriceError::SwapRoutesMustEndWithQuoteDenom {
+            quote_denom: UUSDC.to_string(),
+            swap_routes: vec![SwapAmountInRoute { pool_id: 1, token_out_denom: UATOM.to_string() }]
+        })
+    )]
+    fn test_fetch_twap_price_with_fallback_swap_routes(
+        #[values(Timestamp::from_nanos(1_708_416_816_000_000_000))] block_time: Timestamp,
+        #[case] routes: Vec<Vec<(u64, &str)>>,
+        #[case] expected: Result<(&str, u32), PriceError>,
+    ) {
+        let conf = price_resolution_config(UUSDC);
+
+        let deps = mock_dependencies_with_stargate_querier(
+            &[],
+            arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                let arithmetic_twap = match (req.pool_id, req.base_asset.as_str()) {
+                    (1, "uosmo") => "4.000000000000000000",
+                    (2, "uosmo") => "1.500000000000000000",
+                    (4, "uosmo") => "1.400000000000000000",
+                    (6, UATOM) => "1.600000000000000000",
+                    _ => return ContractResult::Err("Price not found".to_string()),
+                }
+                .to_string();
+
+                ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+            })),
+        );
+
+        let mut routes = routes.into_iter().map(|route| {
+            route
+                .into_iter()
+                .map(|(pool_id, token_out_denom)| SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: token_out_denom.to_string(),
+                })
+                .collect::<Vec<_>>()
+        });
+        let swap_routes = routes.next().unwrap();
+        let fallback_swap_routes = routes.collect::<Vec<_>>();
+
+        let result = fetch_twap_price(
+            deps.as_ref(),
+            &conf,
+            block_time,
+            &TrackedDenom {
+                fallback_swap_routes: fallback_swap_routes.clone(),
+                ..tracked_denom("uosmo", swap_routes.clone())
+            },
+        );
+
+        match expected {
+            Ok((price, route_index)) => assert_eq!(
+                result.unwrap(),
+                PriceInfo {
+                    fallback_swap_routes,
+                    route_index,
+                    ..price_info(price.parse::<Decimal>().unwrap(), block_time, swap_routes)
                 }
             ),
             Err(e) => assert_eq!(result.unwrap_err(), e),
         }
     }
 
+    #[rstest]
+    #[case::config_arithmetic(TwapKind::Arithmetic, None, "9.600000000000000000")]
+    #[case::config_geometric(TwapKind::Geometric, None, "8.000000000000000000")]
+    #[case::override_geometric(
+        TwapKind::Arithmetic,
+        Some(TwapKind::Geometric),
+        "8.000000000000000000"
+    )]
+    #[case::override_arithmetic(
+        TwapKind::Geometric,
+        Some(TwapKind::Arithmetic),
+        "9.600000000000000000"
+    )]
+    fn test_fetch_twap_price_with_twap_kind(
+        #[case] conf_twap_kind: TwapKind,
+        #[case] twap_kind: Option<TwapKind>,
+        #[case] expected: &str,
+    ) {
+        let conf = PriceResolutionConfig {
+            twap_kind: conf_twap_kind,
+            ..price_resolution_config(UUSDC)
+        };
+        let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+
+        let deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let arithmetic_twap = match (req.pool_id, req.base_asset.as_str()) {
+                        (1, UATOM) => "6.400000000000000000",
+                        (2, "uosmo") => "1.500000000000000000",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+                geometric_twap_to_now_query_handler(Box::new(|req| {
+                    let geometric_twap = match (req.pool_id, req.base_asset.as_str()) {
+                        (1, UATOM) => "6.400000000000000000",
+                        (2, "uosmo") => "1.250000000000000000",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(GeometricTwapToNowResponse { geometric_twap })
+                })),
+            ]),
+        );
+
+        let swap_routes = vec![
+            SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uosmo".to_string(),
+            },
+            SwapAmountInRoute {
+                pool_id: 2,
+                token_out_denom: UUSDC.to_string(),
+            },
+        ];
+
+        let actual = fetch_twap_price(
+            deps.as_ref(),
+            &conf,
+            block_time,
+            &TrackedDenom {
+                twap_kind,
+                ..tracked_denom(UATOM, swap_routes.clone())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            actual,
+            PriceInfo {
+                twap_kind,
+                ..price_info(
+                    expected.parse::<Decimal>().unwrap(),
+                    block_time,
+                    swap_routes
+                )
+            }
+        );
+    }
+
     #[rstest]
     #[case(Timestamp::from_nanos(1_708_416_816_000_000_000), ProtoTimestamp { seconds: 1708416816, nanos: 0 })]
     #[case(Timestamp::from_nanos(1_609_459_200_000_000_000), ProtoTimestamp { seconds: 1609459200, nanos: 0 })]
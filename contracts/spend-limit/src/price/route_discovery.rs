@@ -0,0 +1,481 @@
+use std::collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap};
+
+use cosmwasm_std::{ensure, Deps, Timestamp, Uint128};
+use osmosis_std::shim::Any;
+use osmosis_std::types::osmosis::{
+    concentratedliquidity::v1beta1::Pool as ConcentratedLiquidityPool,
+    gamm::{
+        poolmodels::stableswap::v1beta1::Pool as StableswapPool, v1beta1::Pool as BalancerPool,
+    },
+    poolmanager::v1beta1::{PoolmanagerQuerier, SwapAmountInRoute},
+};
+
+use super::price_info_store::fetch_swap_routes_twaps;
+use super::swap_routes::{pools_liquidity, validate_pools};
+use super::{PriceError, PriceResolutionConfig};
+
+/// Maximum number of hops of the swap routes discovered on chain.
+pub const MAX_DISCOVERED_SWAP_ROUTES_HOPS: u32 = 3;
+
+/// Maximum number of pools ranked for each denom. Only the first pools listed for the denom
+/// have their liquidity queried, the rest of its pools are skipped.
+pub const MAX_RANKED_POOLS_PER_DENOM: usize = 20;
+
+/// Maximum number of pools searched for each denom. The ranked pools are ordered by their reserve
+/// of the denom and only the most liquid ones are searched.
+pub const MAX_SEARCHED_POOLS_PER_DENOM: usize = 5;
+
+/// Maximum number of denoms whose pools are searched at each hop.
+pub const MAX_SEARCHED_DENOMS_PER_HOP: usize = 5;
+
+/// Maximum number of candidate swap routes checked for twap and liquidity,
+/// the search stops once this many candidates are found.
+pub const MAX_CANDIDATE_SWAP_ROUTES: usize = 5;
+
+/// Search the pools on chain for swap routes from `denom` to the quote denom
+/// with at most `max_hops` hops, up to [`MAX_DISCOVERED_SWAP_ROUTES_HOPS`].
+/// The search is bounded by [`MAX_RANKED_POOLS_PER_DENOM`], [`MAX_SEARCHED_POOLS_PER_DENOM`],
+/// [`MAX_SEARCHED_DENOMS_PER_HOP`] and [`MAX_CANDIDATE_SWAP_ROUTES`] to keep the gas usage bounded for widely paired denoms.
+///
+/// Candidates without twap for every hop are discarded. The remaining ones are scored by
+/// their liquidity, which is the lowest liquidity among their pools valued in quote denom.
+/// The candidate with the highest liquidity wins, ties are broken by the fewest hops.
+pub fn discover_swap_routes(
+    deps: Deps,
+    conf: &PriceResolutionConfig,
+    block_time: Timestamp,
+    denom: &str,
+    max_hops: u32,
+) -> Result<Vec<SwapAmountInRoute>, PriceError> {
+    ensure!(
+        (1..=MAX_DISCOVERED_SWAP_ROUTES_HOPS).contains(&max_hops),
+        PriceError::InvalidMaxHops {
+            max_hops,
+            max: MAX_DISCOVERED_SWAP_ROUTES_HOPS,
+        }
+    );
+
+    let candidates = find_candidate_swap_routes(deps, denom, conf.quote_denom.as_str(), max_hops)?;
+
+    let mut best: Option<(Uint128, Vec<SwapAmountInRoute>)> = None;
+
+    // candidates are ordered by number of hops, so only strictly better candidates replace the best
+    for swap_routes in candidates {
+        let Some(liquidity) = swap_routes_liquidity(deps, conf, block_time, denom, &swap_routes)
+        else {
+            continue;
+        };
+
+        if conf
+            .min_pool_liquidity
+            .is_some_and(|min_pool_liquidity| liquidity < min_pool_liquidity)
+        {
+            continue;
+        }
+
+        match &best {
+            Some((best_liquidity, _)) if liquidity <= *best_liquidity => {}
+            _ => best = Some((liquidity, swap_routes)),
+        }
+    }
+
+    best.map(|(_, swap_routes)| swap_routes)
+        .ok_or_else(|| PriceError::NoSwapRoutesFound {
+            denom: denom.to_string(),
+            quote_denom: conf.quote_denom.to_string(),
+        })
+}
+
+/// Find swap routes from `denom` to `quote_denom` that don't revisit a denom,
+/// ordered by number of hops, within the search bounds.
+/// The liquidity of at most [`MAX_RANKED_POOLS_PER_DENOM`] pools of a searched denom
+/// is queried to rank its pools.
+fn find_candidate_swap_routes(
+    deps: Deps,
+    denom: &str,
+    quote_denom: &str,
+    max_hops: u32,
+) -> Result<Vec<Vec<SwapAmountInRoute>>, PriceError> {
+    let poolmanager_querier = PoolmanagerQuerier::new(&deps.querier);
+
+    // pools are listed once per denom and their reserves are queried once per pool
+    let mut pools_by_denom: HashMap<String, Vec<u64>> = HashMap::new();
+    let mut pool_reserves: HashMap<u64, BTreeMap<String, Uint128>> = HashMap::new();
+
+    let mut candidates = vec![];
+    let mut paths: Vec<(String, Vec<SwapAmountInRoute>)> = vec![(denom.to_string(), vec![])];
+
+    for _ in 0..max_hops {
+        let mut next_paths = vec![];
+        let mut searched_denoms = BTreeSet::new();
+
+        for (token_in_denom, swap_routes) in paths {
+            if !searched_denoms.contains(&token_in_denom) {
+                if searched_denoms.len() >= MAX_SEARCHED_DENOMS_PER_HOP {
+                    continue;
+                }
+                searched_denoms.insert(token_in_denom.clone());
+            }
+
+            if !pools_by_denom.contains_key(&token_in_denom) {
+                let mut pools = vec![];
+
+                // skip pools that twap is not supported for, e.g. CosmWasm pools,
+                // and cap the pools queried for liquidity before ranking them
+                let pool_ids = poolmanager_querier
+                    .list_pools_by_denom(token_in_denom.clone())?
+                    .pools
+                    .iter()
+                    .filter_map(twap_supported_pool_id)
+                    .take(MAX_RANKED_POOLS_PER_DENOM)
+                    .collect::<Vec<_>>();
+
+                for pool_id in pool_ids {
+                    if let Entry::Vacant(entry) = pool_reserves.entry(pool_id) {
+                        entry.insert(
+                            poolmanager_querier
+                                .total_pool_liquidity(pool_id)?
+                                .liquidity
+                                .into_iter()
+                                .map(|coin| Ok((coin.denom, coin.amount.parse::<Uint128>()?)))
+                                .collect::<Result<_, PriceError>>()?,
+                        );
+                    }
+
+                    let reserve = pool_reserves[&pool_id]
+                        .get(&token_in_denom)
+                        .copied()
+                        .unwrap_or_default();
+
+                    pools.push((reserve, pool_id));
+                }
+
+                // search the pools with the largest reserve of the denom first,
+                // the sort is stable so ties keep the listed order
+                pools.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+                pools_by_denom.insert(
+                    token_in_denom.clone(),
+                    pools
+                        .into_iter()
+                        .take(MAX_SEARCHED_POOLS_PER_DENOM)
+                        .map(|(_, pool_id)| pool_id)
+                        .collect(),
+                );
+            }
+
+            for pool_id in pools_by_denom[&token_in_denom].iter() {
+                for token_out_denom in pool_reserves[pool_id].keys() {
+                    let revisited = token_out_denom == denom
+                        || token_out_denom == &token_in_denom
+                        || swap_routes
+                            .iter()
+                            .any(|route| &route.token_out_denom == token_out_denom);
+
+                    if revisited {
+                        continue;
+                    }
+
+                    let mut next_swap_routes = swap_routes.clone();
+                    next_swap_routes.push(SwapAmountInRoute {
+                        pool_id: *pool_id,
+                        token_out_denom: token_out_denom.clone(),
+                    });
+
+                    if token_out_denom == quote_denom {
+                        candidates.push(next_swap_routes);
+
+                        if candidates.len() >= MAX_CANDIDATE_SWAP_ROUTES {
+                            return Ok(candidates);
+                        }
+                    } else {
+                        next_paths.push((token_out_denom.clone(), next_swap_routes));
+                    }
+                }
+            }
+        }
+
+        paths = next_paths;
+    }
+
+    Ok(candidates)
+}
+
+/// Lowest liquidity among the pools of the swap routes valued in quote denom,
+/// or `None` if the swap routes can't be priced.
+fn swap_routes_liquidity(
+    deps: Deps,
+    conf: &PriceResolutionConfig,
+    block_time: Timestamp,
+    denom: &str,
+    swap_routes: &[SwapAmountInRoute],
+) -> Option<Uint128> {
+    let reserves = validate_pools(deps, denom, swap_routes).ok()?;
+    let twaps =
+        fetch_swap_routes_twaps(deps, conf, denom, block_time, swap_routes, None, None).ok()?;
+
+    pools_liquidity(&reserves, &twaps).ok()?.into_iter().min()
+}
+
+/// Pool id of the pool if twap is supported for its pool type.
+fn twap_supported_pool_id(pool: &Any) -> Option<u64> {
+    match pool.type_url.as_str() {
+        BalancerPool::TYPE_URL => BalancerPool::try_from(pool.clone()).ok().map(|p| p.id),
+        StableswapPool::TYPE_URL => StableswapPool::try_from(pool.clone()).ok().map(|p| p.id),
+        ConcentratedLiquidityPool::TYPE_URL => ConcentratedLiquidityPool::try_from(pool.clone())
+            .ok()
+            .map(|p| p.id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use cosmwasm_std::{coin, Coin, ContractResult};
+    use osmosis_std::types::osmosis::{
+        cosmwasmpool::v1beta1::CosmWasmPool, twap::v1beta1::ArithmeticTwapToNowResponse,
+    };
+    use rstest::rstest;
+
+    use crate::test_helper::mock_stargate_querier::{
+        arithmetic_twap_to_now_query_handler, balancer_pool, combine_query_handlers,
+        mock_dependencies_with_stargate_querier, pools_query_handler,
+    };
+    use crate::test_helper::price::price_resolution_config;
+
+    use super::*;
+
+    #[rstest]
+    #[case::more_liquid_multi_hop_route("uatom", 100_000, None, 3, Ok(vec![(2, "uosmo"), (3, "uusdc")]))]
+    #[case::more_liquid_direct_route("uatom", 2_000_000, None, 3, Ok(vec![(1, "uusdc")]))]
+    #[case::fewer_hops_wins_tie("uatom", 1_500_000, None, 3, Ok(vec![(1, "uusdc")]))]
+    #[case::multi_hop_route_over_max_hops("uatom", 100_000, None, 1, Ok(vec![(1, "uusdc")]))]
+    #[case::zero_max_hops(
+        "uatom",
+        100_000,
+        None,
+        0,
+        Err(PriceError::InvalidMaxHops { max_hops: 0, max: 3 })
+    )]
+    #[case::max_hops_over_limit(
+        "uatom",
+        100_000,
+        None,
+        4,
+        Err(PriceError::InvalidMaxHops { max_hops: 4, max: 3 })
+    )]
+    #[case::below_min_pool_liquidity(
+        "uatom",
+        100_000,
+        Some(Uint128::new(1_500_001)),
+        3,
+        Err(PriceError::NoSwapRoutesFound {
+            denom: "uatom".to_string(),
+            quote_denom: "uusdc".to_string(),
+        })
+    )]
+    #[case::no_pools(
+        "ujuno",
+        100_000,
+        None,
+        3,
+        Err(PriceError::NoSwapRoutesFound {
+            denom: "ujuno".to_string(),
+            quote_denom: "uusdc".to_string(),
+        })
+    )]
+    fn test_discover_swap_routes(
+        #[case] denom: &str,
+        #[case] direct_pool_quote_reserve: u128,
+        #[case] min_pool_liquidity: Option<Uint128>,
+        #[case] max_hops: u32,
+        #[case] expected: Result<Vec<(u64, &str)>, PriceError>,
+    ) {
+        let conf = PriceResolutionConfig {
+            min_pool_liquidity,
+            ..price_resolution_config("uusdc")
+        };
+
+        let balancer =
+            |pool_id: u64, liquidity: Vec<Coin>| (pool_id, balancer_pool(pool_id), liquidity);
+
+        let deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                pools_query_handler(vec![
+                    balancer(
+                        1,
+                        vec![
+                            coin(10_000, "uatom"),
+                            coin(direct_pool_quote_reserve, "uusdc"),
+                        ],
+                    ),
+                    balancer(2, vec![coin(100_000, "uatom"), coin(1_000_000, "uosmo")]),
+                    balancer(3, vec![coin(1_000_000, "uosmo"), coin(1_500_000, "uusdc")]),
+                    // twap is not supported for CosmWasm pools
+                    (
+                        4,
+                        CosmWasmPool {
+                            contract_address: "contract".to_string(),
+                            pool_id: 4,
+                            code_id: 1,
+                            instantiate_msg: vec![],
+                        }
+                        .to_any(),
+                        vec![coin(1_000_000_000, "uatom"), coin(1_000_000_000, "uusdc")],
+                    ),
+                    // no twap available for pool 5
+                    balancer(
+                        5,
+                        vec![coin(1_000_000_000, "uatom"), coin(1_000_000_000, "uion")],
+                    ),
+                    balancer(
+                        6,
+                        vec![coin(1_000_000_000, "uion"), coin(1_000_000_000, "uusdc")],
+                    ),
+                ]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let arithmetic_twap = match (
+                        req.pool_id,
+                        req.base_asset.as_str(),
+                        req.quote_asset.as_str(),
+                    ) {
+                        (1, "uatom", "uusdc") => "10",
+                        (2, "uatom", "uosmo") => "10",
+                        (3, "uosmo", "uusdc") => "1.5",
+                        (6, "uion", "uusdc") => "1",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let result = discover_swap_routes(
+            deps.as_ref(),
+            &conf,
+            Timestamp::from_nanos(1_708_416_816_000_000_000),
+            denom,
+            max_hops,
+        );
+
+        assert_eq!(
+            result,
+            expected.map(|swap_routes| {
+                swap_routes
+                    .into_iter()
+                    .map(|(pool_id, token_out_denom)| SwapAmountInRoute {
+                        pool_id,
+                        token_out_denom: token_out_denom.to_string(),
+                    })
+                    .collect()
+            })
+        );
+    }
+
+    #[test]
+    fn test_find_candidate_swap_routes_is_bounded() {
+        // pools 1-7 pair uatom with uusdc directly, pools 11-17 pair uatom with intermediate denoms
+        let pools = (1..=7)
+            .map(|pool_id| {
+                (
+                    pool_id,
+                    balancer_pool(pool_id),
+                    vec![coin(1_000_000, "uatom"), coin(1_000_000, "uusdc")],
+                )
+            })
+            .chain((11..=17).map(|pool_id| {
+                (
+                    pool_id,
+                    balancer_pool(pool_id),
+                    vec![
+                        coin(1_000_000, "uatom"),
+                        coin(1_000_000, format!("u{pool_id}")),
+                    ],
+                )
+            }))
+            .collect();
+
+        let deps = mock_dependencies_with_stargate_querier(&[], pools_query_handler(pools));
+
+        let candidates = find_candidate_swap_routes(deps.as_ref(), "uatom", "uusdc", 3).unwrap();
+
+        // only the first of the equally liquid pools of uatom are searched and the search stops at the candidates limit
+        assert_eq!(
+            candidates,
+            (1..=MAX_CANDIDATE_SWAP_ROUTES as u64)
+                .map(|pool_id| vec![SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: "uusdc".to_string(),
+                }])
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_find_candidate_swap_routes_searches_most_liquid_pools() {
+        // pool 7 is listed last but holds the largest uatom reserve
+        let pools = (1..=7)
+            .map(|pool_id| {
+                let uatom_reserve = if pool_id == 7 { 9_000_000 } else { 1_000_000 };
+                (
+                    pool_id,
+                    balancer_pool(pool_id),
+                    vec![coin(uatom_reserve, "uatom"), coin(1_000_000, "uusdc")],
+                )
+            })
+            .collect();
+
+        let deps = mock_dependencies_with_stargate_querier(&[], pools_query_handler(pools));
+
+        let candidates = find_candidate_swap_routes(deps.as_ref(), "uatom", "uusdc", 3).unwrap();
+
+        assert_eq!(
+            candidates,
+            [7, 1, 2, 3, 4]
+                .into_iter()
+                .map(|pool_id| vec![SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: "uusdc".to_string(),
+                }])
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_find_candidate_swap_routes_caps_liquidity_queries() {
+        let pools_count = MAX_RANKED_POOLS_PER_DENOM as u64 + 10;
+        let pools = (1..=pools_count)
+            .map(|pool_id| {
+                (
+                    pool_id,
+                    balancer_pool(pool_id),
+                    vec![coin(1_000_000, "uatom"), coin(1_000_000, "uusdc")],
+                )
+            })
+            .collect();
+
+        let liquidity_queries = Rc::new(Cell::new(0));
+        let handler = pools_query_handler(pools);
+        let counter = liquidity_queries.clone();
+
+        let deps = mock_dependencies_with_stargate_querier(
+            &[],
+            Box::new(move |path, data| {
+                if path == "/osmosis.poolmanager.v1beta1.Query/TotalPoolLiquidity" {
+                    counter.set(counter.get() + 1);
+                }
+                handler(path, data)
+            }),
+        );
+
+        let candidates = find_candidate_swap_routes(deps.as_ref(), "uatom", "uusdc", 3).unwrap();
+
+        assert_eq!(candidates.len(), MAX_CANDIDATE_SWAP_ROUTES);
+        assert_eq!(liquidity_queries.get(), MAX_RANKED_POOLS_PER_DENOM);
+    }
+}
@@ -0,0 +1,395 @@
+use std::fmt;
+
+use cosmwasm_std::{ensure, Binary, Decimal, Deps, QueryRequest, Timestamp, WasmQuery};
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+use super::price_info::PriceInfo;
+use super::price_info_store::fetch_twap_price;
+use super::swap_routes::validate_swap_routes;
+use super::{Oracle, PriceError, PriceResolutionConfig, TrackedDenom};
+
+/// Source that the price of a tracked denom is resolved from.
+pub trait PriceSource {
+    /// Validate that the source is able to price the denom, called when the denom is tracked.
+    fn validate(
+        &self,
+        deps: Deps,
+        conf: &PriceResolutionConfig,
+        block_time: Timestamp,
+    ) -> Result<(), PriceError>;
+
+    /// Fetch the current price of the denom in quote denom.
+    fn fetch_price(
+        &self,
+        deps: Deps,
+        conf: &PriceResolutionConfig,
+        block_time: Timestamp,
+    ) -> Result<PriceInfo, PriceError>;
+}
+
+/// Price source assigned to the tracked denom, the oracle if set, otherwise the twap of its swap routes.
+pub fn price_source(tracked_denom: &TrackedDenom) -> Box<dyn PriceSource + '_> {
+    match &tracked_denom.oracle {
+        Some(oracle) => Box::new(OraclePriceSource {
+            tracked_denom,
+            oracle,
+        }),
+        None => Box::new(TwapPriceSource { tracked_denom }),
+    }
+}
+
+/// Twap of the pools along the swap routes of the tracked denom.
+pub struct TwapPriceSource<'a> {
+    tracked_denom: &'a TrackedDenom,
+}
+
+impl PriceSource for TwapPriceSource<'_> {
+    fn validate(
+        &self,
+        deps: Deps,
+        conf: &PriceResolutionConfig,
+        block_time: Timestamp,
+    ) -> Result<(), PriceError> {
+        validate_swap_routes(deps, conf, block_time, self.tracked_denom)
+    }
+
+    fn fetch_price(
+        &self,
+        deps: Deps,
+        conf: &PriceResolutionConfig,
+        block_time: Timestamp,
+    ) -> Result<PriceInfo, PriceError> {
+        fetch_twap_price(deps, conf, block_time, self.tracked_denom)
+    }
+}
+
+/// Price queried from an external oracle contract through a smart query.
+pub struct OraclePriceSource<'a> {
+    tracked_denom: &'a TrackedDenom,
+    oracle: &'a Oracle,
+}
+
+impl PriceSource for OraclePriceSource<'_> {
+    fn validate(
+        &self,
+        deps: Deps,
+        _conf: &PriceResolutionConfig,
+        _block_time: Timestamp,
+    ) -> Result<(), PriceError> {
+        ensure!(
+            self.tracked_denom.all_swap_routes().all(Vec::is_empty),
+            PriceError::OracleWithSwapRoutes {
+                denom: self.tracked_denom.denom.to_string()
+            }
+        );
+
+        deps.api.addr_validate(&self.oracle.contract)?;
+
+        Ok(())
+    }
+
+    fn fetch_price(
+        &self,
+        deps: Deps,
+        conf: &PriceResolutionConfig,
+        block_time: Timestamp,
+    ) -> Result<PriceInfo, PriceError> {
+        let Oracle {
+            contract,
+            query_template,
+            price_path,
+        } = self.oracle;
+        let denom = self.tracked_denom.denom.as_str();
+
+        let query = query_template
+            .replace("{denom}", denom)
+            .replace("{quote_denom}", conf.quote_denom.as_str());
+
+        let response: OracleResponse = deps
+            .querier
+            .query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: contract.to_string(),
+                msg: Binary::from(query.into_bytes()),
+            }))
+            .map_err(|e| PriceError::OracleQueryError {
+                denom: denom.to_string(),
+                contract: contract.to_string(),
+                error: e.to_string(),
+            })?;
+
+        let price = response
+            .get(price_path)
+            .and_then(|price| price.parse::<Decimal>().ok())
+            .filter(|price| !price.is_zero())
+            .ok_or_else(|| PriceError::InvalidOraclePrice {
+                denom: denom.to_string(),
+                contract: contract.to_string(),
+                price_path: price_path.clone(),
+            })?;
+
+        Ok(PriceInfo {
+            price,
+            last_updated_time: block_time,
+            swap_routes: vec![],
+            fallback_swap_routes: vec![],
+            route_index: 0,
+            twap_kind: self.tracked_denom.twap_kind,
+            staleness_threshold: self.tracked_denom.staleness_threshold,
+            twap_duration: self.tracked_denom.twap_duration,
+            fixed: false,
+            spot_price_fallback_twap: None,
+            oracle: Some(self.oracle.clone()),
+        })
+    }
+}
+
+/// Response of the oracle contract, only strings, integers and objects are kept
+/// since the shape of the response is only known at runtime.
+#[derive(Debug, PartialEq)]
+enum OracleResponse {
+    Value(String),
+    Object(Vec<(String, OracleResponse)>),
+    Other,
+}
+
+impl OracleResponse {
+    /// Value at the end of the path of keys of the nested objects
+    fn get(&self, path: &[String]) -> Option<&str> {
+        let value = path.iter().try_fold(self, |value, key| match value {
+            OracleResponse::Object(fields) => fields
+                .iter()
+                .find(|(field, _)| field == key)
+                .map(|(_, value)| value),
+            _ => None,
+        })?;
+
+        match value {
+            OracleResponse::Value(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OracleResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OracleResponseVisitor)
+    }
+}
+
+struct OracleResponseVisitor;
+
+impl<'de> Visitor<'de> for OracleResponseVisitor {
+    type Value = OracleResponse;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(OracleResponse::Value(v.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(OracleResponse::Value(v.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(OracleResponse::Value(v.to_string()))
+    }
+
+    fn visit_bool<E: de::Error>(self, _v: bool) -> Result<Self::Value, E> {
+        Ok(OracleResponse::Other)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(OracleResponse::Other)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(OracleResponse::Other)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = vec![];
+        while let Some((key, value)) = map.next_entry::<String, OracleResponse>()? {
+            fields.push((key, value));
+        }
+        Ok(OracleResponse::Object(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        testing::mock_dependencies, ContractResult, SystemError, SystemResult, Uint64,
+    };
+    use osmosis_std::types::osmosis::{
+        poolmanager::v1beta1::SwapAmountInRoute, twap::v1beta1::ArithmeticTwapToNowResponse,
+    };
+    use rstest::rstest;
+
+    use crate::test_helper::mock_stargate_querier::{
+        arithmetic_twap_to_now_query_handler, mock_dependencies_with_stargate_querier,
+        oracle_query_handler,
+    };
+    use crate::test_helper::price::{price_info, price_resolution_config, tracked_denom};
+
+    use super::*;
+
+    fn conf() -> PriceResolutionConfig {
+        price_resolution_config("uusdc")
+    }
+
+    fn uatom_tracked_denom(
+        swap_routes: Vec<SwapAmountInRoute>,
+        oracle: Option<Oracle>,
+    ) -> TrackedDenom {
+        TrackedDenom {
+            staleness_threshold: Some(Uint64::from(600_000_000_000u64)),
+            oracle,
+            ..tracked_denom("uatom", swap_routes)
+        }
+    }
+
+    fn oracle(price_path: &[&str]) -> Oracle {
+        Oracle {
+            contract: "oracle".to_string(),
+            query_template: r#"{"price":{"base":"{denom}","quote":"{quote_denom}"}}"#.to_string(),
+            price_path: price_path.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::nested_decimal(&["price", "rate"], r#"{"price":{"rate":"10.5","updated_at":1}}"#, Ok("10.5"))]
+    #[case::integer(&["price"], r#"{"price":10,"sources":["a","b"]}"#, Ok("10"))]
+    #[case::top_level(&[], r#""10.5""#, Ok("10.5"))]
+    #[case::missing_key(&["rate"], r#"{"price":"10.5"}"#, Err(()))]
+    #[case::not_a_decimal(&["price"], r#"{"price":"abc"}"#, Err(()))]
+    #[case::object(&["price"], r#"{"price":{"rate":"10.5"}}"#, Err(()))]
+    #[case::zero(&["price"], r#"{"price":"0"}"#, Err(()))]
+    #[case::null(&["price"], r#"{"price":null}"#, Err(()))]
+    fn test_oracle_price_source(
+        #[case] price_path: &[&str],
+        #[case] response: &'static str,
+        #[case] expected: Result<&str, ()>,
+    ) {
+        let oracle = oracle(price_path);
+        let tracked_denom = uatom_tracked_denom(vec![], Some(oracle.clone()));
+        let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(oracle_query_handler(
+            "oracle",
+            Box::new(move |query| {
+                assert_eq!(
+                    query,
+                    Binary::from(br#"{"price":{"base":"uatom","quote":"uusdc"}}"#)
+                );
+                ContractResult::Ok(Binary::from(response.as_bytes()))
+            }),
+        ));
+
+        let source = price_source(&tracked_denom);
+        source.validate(deps.as_ref(), &conf(), block_time).unwrap();
+
+        let expected = expected
+            .map(|price| PriceInfo {
+                staleness_threshold: Some(Uint64::from(600_000_000_000u64)),
+                oracle: Some(oracle.clone()),
+                ..price_info(price.parse().unwrap(), block_time, vec![])
+            })
+            .map_err(|_| PriceError::InvalidOraclePrice {
+                denom: "uatom".to_string(),
+                contract: "oracle".to_string(),
+                price_path: oracle.price_path.clone(),
+            });
+
+        assert_eq!(
+            source.fetch_price(deps.as_ref(), &conf(), block_time),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_oracle_price_source_query_error() {
+        let tracked_denom = uatom_tracked_denom(vec![], Some(oracle(&["price"])));
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(oracle_query_handler(
+            "oracle",
+            Box::new(|_| ContractResult::Err("unknown denom".to_string())),
+        ));
+
+        let err = price_source(&tracked_denom)
+            .fetch_price(deps.as_ref(), &conf(), Timestamp::from_seconds(1))
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            PriceError::OracleQueryError { denom, contract, error }
+                if denom == "uatom" && contract == "oracle" && error.contains("unknown denom")
+        ));
+    }
+
+    #[test]
+    fn test_oracle_price_source_with_swap_routes() {
+        let tracked_denom = uatom_tracked_denom(
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uusdc".to_string(),
+            }],
+            Some(oracle(&["price"])),
+        );
+        let deps = mock_dependencies();
+
+        assert_eq!(
+            price_source(&tracked_denom).validate(
+                deps.as_ref(),
+                &conf(),
+                Timestamp::from_seconds(1)
+            ),
+            Err(PriceError::OracleWithSwapRoutes {
+                denom: "uatom".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_twap_price_source() {
+        let tracked_denom = uatom_tracked_denom(
+            vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uusdc".to_string(),
+            }],
+            None,
+        );
+        let block_time = Timestamp::from_nanos(1_708_416_816_000_000_000);
+
+        let mut deps = mock_dependencies_with_stargate_querier(
+            &[],
+            arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                match (req.pool_id, req.base_asset.as_str()) {
+                    (1, "uatom") => ContractResult::Ok(ArithmeticTwapToNowResponse {
+                        arithmetic_twap: "10.5".to_string(),
+                    }),
+                    _ => ContractResult::Err("Price not found".to_string()),
+                }
+            })),
+        );
+
+        // twap source never queries the oracle contract
+        deps.querier.update_wasm(|_| {
+            SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            })
+        });
+
+        let price_info = price_source(&tracked_denom)
+            .fetch_price(deps.as_ref(), &conf(), block_time)
+            .unwrap();
+
+        assert_eq!(price_info.price, Decimal::percent(1050));
+        assert_eq!(price_info.swap_routes, tracked_denom.swap_routes);
+        assert_eq!(price_info.oracle, None);
+    }
+}
@@ -0,0 +1,295 @@
+use cosmwasm_std::{ensure, Decimal, Deps, Timestamp, Uint128};
+use osmosis_std::types::osmosis::{
+    cosmwasmpool::v1beta1::CosmWasmPool,
+    poolmanager::v1beta1::{PoolmanagerQuerier, SwapAmountInRoute},
+};
+
+use super::price_info_store::{fetch_swap_routes_twaps, valid_swap_routes};
+use super::{PriceError, PriceResolutionConfig, TrackedDenom};
+
+/// Validate all the swap routes of the tracked denom against the pools on chain.
+pub fn validate_swap_routes(
+    deps: Deps,
+    conf: &PriceResolutionConfig,
+    block_time: Timestamp,
+    tracked_denom: &TrackedDenom,
+) -> Result<(), PriceError> {
+    for swap_routes in tracked_denom.all_swap_routes() {
+        ensure!(
+            valid_swap_routes(swap_routes, conf.quote_denom.as_str()),
+            PriceError::SwapRoutesMustEndWithQuoteDenom {
+                quote_denom: conf.quote_denom.to_string(),
+                swap_routes: swap_routes.clone()
+            }
+        );
+
+        let reserves = validate_pools(deps, tracked_denom.denom.as_str(), swap_routes)?;
+
+        if let Some(min_pool_liquidity) = conf.min_pool_liquidity {
+            let twaps = fetch_swap_routes_twaps(
+                deps,
+                conf,
+                tracked_denom.denom.as_str(),
+                block_time,
+                swap_routes,
+                tracked_denom.twap_kind,
+                tracked_denom.twap_duration,
+            )?;
+
+            ensure_min_pool_liquidity(swap_routes, &reserves, &twaps, min_pool_liquidity)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Ensure that every pool along the swap routes exists, is not a CosmWasm pool
+/// and contains both the input and output denoms of the hop.
+/// Returns the reserve of the output denom of each pool.
+pub(super) fn validate_pools(
+    deps: Deps,
+    base_denom: &str,
+    swap_routes: &[SwapAmountInRoute],
+) -> Result<Vec<Uint128>, PriceError> {
+    let poolmanager_querier = PoolmanagerQuerier::new(&deps.querier);
+    let mut token_in_denom = base_denom;
+    let mut reserves = Vec::with_capacity(swap_routes.len());
+
+    for route in swap_routes {
+        let pool_id = route.pool_id;
+
+        let pool = poolmanager_querier
+            .pool(pool_id)
+            .ok()
+            .and_then(|res| res.pool)
+            .ok_or(PriceError::PoolNotFound { pool_id })?;
+
+        // twap is not supported for CosmWasm pools
+        ensure!(
+            pool.type_url != CosmWasmPool::TYPE_URL,
+            PriceError::UnsupportedPoolType {
+                pool_id,
+                pool_type: pool.type_url
+            }
+        );
+
+        let liquidity = poolmanager_querier.total_pool_liquidity(pool_id)?.liquidity;
+        let reserve_of = |denom: &str| {
+            liquidity
+                .iter()
+                .find(|coin| coin.denom == denom)
+                .map(|coin| coin.amount.parse::<Uint128>())
+                .transpose()?
+                .ok_or_else(|| PriceError::DenomNotInPool {
+                    pool_id,
+                    denom: denom.to_string(),
+                })
+        };
+
+        reserve_of(token_in_denom)?;
+        reserves.push(reserve_of(route.token_out_denom.as_str())?);
+
+        token_in_denom = route.token_out_denom.as_str();
+    }
+
+    Ok(reserves)
+}
+
+/// Ensure that the output denom reserve of every pool along the swap routes,
+/// valued in quote denom, is at least `min_pool_liquidity`.
+fn ensure_min_pool_liquidity(
+    swap_routes: &[SwapAmountInRoute],
+    reserves: &[Uint128],
+    twaps: &[Decimal],
+    min_pool_liquidity: Uint128,
+) -> Result<(), PriceError> {
+    let pools_liquidity = pools_liquidity(reserves, twaps)?;
+
+    // check from the last hop, closest to the quote denom
+    for (route, liquidity) in swap_routes.iter().zip(pools_liquidity).rev() {
+        ensure!(
+            liquidity >= min_pool_liquidity,
+            PriceError::InsufficientPoolLiquidity {
+                pool_id: route.pool_id,
+                liquidity,
+                min_pool_liquidity,
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Value the output denom reserve of every pool along the swap routes in quote denom,
+/// in the same order as the swap routes.
+pub(super) fn pools_liquidity(
+    reserves: &[Uint128],
+    twaps: &[Decimal],
+) -> Result<Vec<Uint128>, PriceError> {
+    // price of the output denom of the hop in quote denom, starting from the last hop
+    // where the output denom is the quote denom
+    let mut token_out_price = Decimal::one();
+    let mut pools_liquidity = vec![Uint128::zero(); reserves.len()];
+
+    for ((liquidity, reserve), twap) in pools_liquidity.iter_mut().zip(reserves).zip(twaps).rev() {
+        // overflow means the liquidity is beyond any representable minimum
+        *liquidity = reserve
+            .checked_mul_floor(token_out_price)
+            .unwrap_or(Uint128::MAX);
+
+        token_out_price = token_out_price.checked_mul(*twap)?;
+    }
+
+    Ok(pools_liquidity)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{coin, ContractResult};
+    use osmosis_std::types::osmosis::twap::v1beta1::ArithmeticTwapToNowResponse;
+    use rstest::rstest;
+
+    use crate::test_helper::mock_stargate_querier::{
+        arithmetic_twap_to_now_query_handler, balancer_pool, combine_query_handlers,
+        mock_dependencies_with_stargate_querier, pools_query_handler,
+    };
+    use crate::test_helper::price::{price_resolution_config, tracked_denom};
+
+    use super::*;
+
+    #[rstest]
+    #[case::valid(vec![(1, "uosmo"), (2, "uusdc")], None, Ok(()))]
+    #[case::sufficient_liquidity(
+        vec![(1, "uosmo"), (2, "uusdc")],
+        Some(Uint128::new(1_500_000)),
+        Ok(())
+    )]
+    #[case::insufficient_liquidity_last_hop(
+        vec![(1, "uosmo"), (2, "uusdc")],
+        Some(Uint128::new(2_000_001)),
+        Err(PriceError::InsufficientPoolLiquidity {
+            pool_id: 2,
+            liquidity: Uint128::new(2_000_000),
+            min_pool_liquidity: Uint128::new(2_000_001),
+        })
+    )]
+    #[case::insufficient_liquidity_valued_in_quote_denom(
+        vec![(1, "uosmo"), (2, "uusdc")],
+        Some(Uint128::new(1_500_001)),
+        Err(PriceError::InsufficientPoolLiquidity {
+            pool_id: 1,
+            liquidity: Uint128::new(1_500_000),
+            min_pool_liquidity: Uint128::new(1_500_001),
+        })
+    )]
+    #[case::pool_not_found(
+        vec![(1, "uosmo"), (99, "uusdc")],
+        None,
+        Err(PriceError::PoolNotFound { pool_id: 99 })
+    )]
+    #[case::cosmwasm_pool(
+        vec![(3, "uusdc")],
+        None,
+        Err(PriceError::UnsupportedPoolType {
+            pool_id: 3,
+            pool_type: CosmWasmPool::TYPE_URL.to_string(),
+        })
+    )]
+    #[case::token_in_denom_not_in_pool(
+        vec![(2, "uusdc")],
+        None,
+        Err(PriceError::DenomNotInPool {
+            pool_id: 2,
+            denom: "uatom".to_string(),
+        })
+    )]
+    #[case::token_out_denom_not_in_pool(
+        vec![(1, "uion"), (2, "uusdc")],
+        None,
+        Err(PriceError::DenomNotInPool {
+            pool_id: 1,
+            denom: "uion".to_string(),
+        })
+    )]
+    #[case::not_ending_with_quote_denom(
+        vec![(1, "uosmo")],
+        None,
+        Err(PriceError::SwapRoutesMustEndWithQuoteDenom {
+            quote_denom: "uusdc".to_string(),
+            swap_routes: vec![SwapAmountInRoute {
+                pool_id: 1,
+                token_out_denom: "uosmo".to_string(),
+            }],
+        })
+    )]
+    fn test_validate_swap_routes(
+        #[case] swap_routes: Vec<(u64, &str)>,
+        #[case] min_pool_liquidity: Option<Uint128>,
+        #[case] expected: Result<(), PriceError>,
+    ) {
+        let conf = PriceResolutionConfig {
+            min_pool_liquidity,
+            ..price_resolution_config("uusdc")
+        };
+
+        let deps = mock_dependencies_with_stargate_querier(
+            &[],
+            combine_query_handlers(vec![
+                pools_query_handler(vec![
+                    (
+                        1,
+                        balancer_pool(1),
+                        vec![coin(100_000, "uatom"), coin(1_000_000, "uosmo")],
+                    ),
+                    (
+                        2,
+                        balancer_pool(2),
+                        vec![coin(1_000_000, "uosmo"), coin(2_000_000, "uusdc")],
+                    ),
+                    (
+                        3,
+                        CosmWasmPool {
+                            contract_address: "contract".to_string(),
+                            pool_id: 3,
+                            code_id: 1,
+                            instantiate_msg: vec![],
+                        }
+                        .to_any(),
+                        vec![coin(1_000_000, "uatom"), coin(1_000_000, "uusdc")],
+                    ),
+                ]),
+                arithmetic_twap_to_now_query_handler(Box::new(|req| {
+                    let arithmetic_twap = match (req.base_asset.as_str(), req.quote_asset.as_str())
+                    {
+                        ("uatom", "uosmo") => "10",
+                        ("uosmo", "uusdc") => "1.5",
+                        _ => return ContractResult::Err("Price not found".to_string()),
+                    }
+                    .to_string();
+
+                    ContractResult::Ok(ArithmeticTwapToNowResponse { arithmetic_twap })
+                })),
+            ]),
+        );
+
+        let tracked_denom = tracked_denom(
+            "uatom",
+            swap_routes
+                .into_iter()
+                .map(|(pool_id, token_out_denom)| SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom: token_out_denom.to_string(),
+                })
+                .collect(),
+        );
+
+        let result = validate_swap_routes(
+            deps.as_ref(),
+            &conf,
+            Timestamp::from_nanos(1_708_416_816_000_000_000),
+            &tracked_denom,
+        );
+
+        assert_eq!(result, expected);
+    }
+}
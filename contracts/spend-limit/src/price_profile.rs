@@ -0,0 +1,352 @@
+use cosmwasm_std::{ensure, Decimal, Order, StdError, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::{
+    price::{PriceInfoStore, PriceResolutionConfig},
+    spend_limit::Spending,
+    state::{
+        PRICE_INFOS_NAMESPACE, PRICE_PROFILES, PRICE_RESOLUTION_CONFIG,
+        QUOTE_DENOM_MIGRATIONS_NAMESPACE, QUOTE_DENOM_MIGRATION_COUNT_NAMESPACE,
+    },
+    ContractError,
+};
+
+/// Store for the conversion rates of the quote denom migrations, keyed by their sequence number.
+pub type QuoteDenomMigrationStore<'a> = Map<'a, u64, Decimal>;
+
+/// Store for the number of quote denom migrations, which is the sequence number of the next one.
+pub type QuoteDenomMigrationCountStore<'a> = Item<'a, u64>;
+
+/// Maximum length of a price profile name.
+pub const MAX_PRICE_PROFILE_NAME_LENGTH: usize = 32;
+
+/// Price profile selected by an authenticator, which has its own price resolution config
+/// and tracked denoms, e.g. to budget in OSMO instead of USDC.
+///
+/// The default profile, selected when no name is given, uses [`PRICE_RESOLUTION_CONFIG`]
+/// and [`crate::state::PRICE_INFOS`]. Named profiles keep their config in [`PRICE_PROFILES`]
+/// and their price infos in a separate namespace.
+pub struct PriceProfile {
+    name: Option<String>,
+    price_infos_namespace: String,
+    quote_denom_migrations_namespace: String,
+    quote_denom_migration_count_namespace: String,
+}
+
+impl PriceProfile {
+    pub fn new(name: Option<String>) -> Self {
+        let namespace = |prefix: &str| match &name {
+            Some(name) => format!("{prefix}:{name}"),
+            None => prefix.to_string(),
+        };
+        let price_infos_namespace = namespace(PRICE_INFOS_NAMESPACE);
+        let quote_denom_migrations_namespace = namespace(QUOTE_DENOM_MIGRATIONS_NAMESPACE);
+        let quote_denom_migration_count_namespace =
+            namespace(QUOTE_DENOM_MIGRATION_COUNT_NAMESPACE);
+
+        Self {
+            name,
+            price_infos_namespace,
+            quote_denom_migrations_namespace,
+            quote_denom_migration_count_namespace,
+        }
+    }
+
+    /// Name of the profile, `None` for the default profile
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Store for the price info of the denoms tracked by this profile
+    pub fn price_infos(&self) -> PriceInfoStore<'_> {
+        Map::new(&self.price_infos_namespace)
+    }
+
+    /// Store for the conversion rates of the quote denom migrations of this profile.
+    /// Spendings are rescaled with them lazily, see [`PriceProfile::migrate_spending`].
+    pub fn quote_denom_migrations(&self) -> QuoteDenomMigrationStore<'_> {
+        Map::new(&self.quote_denom_migrations_namespace)
+    }
+
+    /// Number of quote denom migrations of this profile,
+    /// spendings initialized now are already valued in the current quote denom.
+    /// It keeps counting across removals of the profile, see [`PriceProfile::clear`].
+    pub fn quote_denom_migration_count(&self, storage: &dyn Storage) -> StdResult<u64> {
+        QuoteDenomMigrationCountStore::new(&self.quote_denom_migration_count_namespace)
+            .may_load(storage)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Record the quote denom migration with the next sequence number
+    pub fn save_quote_denom_migration(
+        &self,
+        storage: &mut dyn Storage,
+        conversion_rate: Decimal,
+    ) -> StdResult<u64> {
+        let id = self.quote_denom_migration_count(storage)?;
+        self.quote_denom_migrations()
+            .save(storage, id, &conversion_rate)?;
+        QuoteDenomMigrationCountStore::new(&self.quote_denom_migration_count_namespace)
+            .save(storage, &(id + 1))?;
+        Ok(id)
+    }
+
+    /// Rescale the spending to the current quote denom of this profile
+    /// with the migrations recorded since it was last rescaled.
+    pub fn migrate_spending(
+        &self,
+        storage: &dyn Storage,
+        spending: &mut Spending,
+    ) -> Result<(), ContractError> {
+        let min = Some(Bound::inclusive(spending.quote_denom_migrations_applied));
+        let migrations = self
+            .quote_denom_migrations()
+            .range(storage, min, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for (id, conversion_rate) in migrations {
+            spending.migrate_quote_denom(conversion_rate)?;
+            spending.quote_denom_migrations_applied = id + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the config, price infos and quote denom migrations of this profile,
+    /// so that a profile created later with the same name starts from a clean state.
+    /// The migration count is kept, otherwise the spendings that already applied migrations
+    /// would skip the ones of the profile created later with the same name.
+    /// Denom aliases and exponents are shared by all the profiles and are kept.
+    pub fn clear(&self, storage: &mut dyn Storage) {
+        match &self.name {
+            Some(name) => PRICE_PROFILES.remove(storage, name),
+            None => PRICE_RESOLUTION_CONFIG.remove(storage),
+        }
+        self.price_infos().clear(storage);
+        self.quote_denom_migrations().clear(storage);
+    }
+
+    pub fn load_config(&self, storage: &dyn Storage) -> StdResult<PriceResolutionConfig> {
+        match &self.name {
+            Some(name) => PRICE_PROFILES
+                .may_load(storage, name)?
+                .ok_or_else(|| StdError::not_found(format!("Price profile {name}"))),
+            None => PRICE_RESOLUTION_CONFIG.load(storage),
+        }
+    }
+
+    pub fn may_load_config(
+        &self,
+        storage: &dyn Storage,
+    ) -> StdResult<Option<PriceResolutionConfig>> {
+        match &self.name {
+            Some(name) => PRICE_PROFILES.may_load(storage, name),
+            None => PRICE_RESOLUTION_CONFIG.may_load(storage),
+        }
+    }
+
+    pub fn save_config(
+        &self,
+        storage: &mut dyn Storage,
+        conf: &PriceResolutionConfig,
+    ) -> StdResult<()> {
+        match &self.name {
+            Some(name) => PRICE_PROFILES.save(storage, name, conf),
+            None => PRICE_RESOLUTION_CONFIG.save(storage, conf),
+        }
+    }
+}
+
+/// Price profile names must be non-empty, at most [`MAX_PRICE_PROFILE_NAME_LENGTH`] long
+/// and only contain ascii alphanumerics, `-` or `_`.
+pub fn validate_price_profile_name(name: &str) -> Result<(), ContractError> {
+    ensure!(
+        !name.is_empty()
+            && name.len() <= MAX_PRICE_PROFILE_NAME_LENGTH
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        ContractError::InvalidPriceProfileName {
+            name: name.to_string()
+        }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::mock_dependencies, Decimal, Timestamp, Uint64};
+    use rstest::rstest;
+
+    use crate::state::PRICE_INFOS;
+
+    use super::*;
+    use crate::test_helper::price::{price_info, price_resolution_config};
+
+    #[rstest]
+    #[case::alphanumeric("osmo", true)]
+    #[case::with_separators("usd-stable_1", true)]
+    #[case::max_length(&"a".repeat(MAX_PRICE_PROFILE_NAME_LENGTH), true)]
+    #[case::empty("", false)]
+    #[case::too_long(&"a".repeat(MAX_PRICE_PROFILE_NAME_LENGTH + 1), false)]
+    #[case::namespace_separator("osmo:usd", false)]
+    #[case::whitespace("os mo", false)]
+    fn test_validate_price_profile_name(#[case] name: &str, #[case] valid: bool) {
+        let expected = if valid {
+            Ok(())
+        } else {
+            Err(ContractError::InvalidPriceProfileName {
+                name: name.to_string(),
+            })
+        };
+
+        assert_eq!(validate_price_profile_name(name), expected);
+    }
+
+    #[test]
+    fn test_price_profiles_are_isolated() {
+        let mut deps = mock_dependencies();
+
+        let conf = |quote_denom: &str| PriceResolutionConfig {
+            staleness_threshold: Uint64::from(3_600_000_000u64),
+            twap_duration: Uint64::from(3_600_000_000u64),
+            ..price_resolution_config(quote_denom)
+        };
+
+        let price_info =
+            |price: u64| price_info(Decimal::percent(price), Timestamp::from_seconds(1), vec![]);
+
+        let default_profile = PriceProfile::new(None);
+        let osmo_profile = PriceProfile::new(Some("osmo".to_string()));
+
+        // named profile doesn't exist until its config is saved
+        assert_eq!(
+            osmo_profile.load_config(&deps.storage).unwrap_err(),
+            StdError::not_found("Price profile osmo")
+        );
+
+        default_profile
+            .save_config(&mut deps.storage, &conf("uusdc"))
+            .unwrap();
+        osmo_profile
+            .save_config(&mut deps.storage, &conf("uosmo"))
+            .unwrap();
+
+        assert_eq!(
+            default_profile.load_config(&deps.storage).unwrap(),
+            conf("uusdc")
+        );
+        assert_eq!(
+            PRICE_RESOLUTION_CONFIG.load(&deps.storage).unwrap(),
+            conf("uusdc")
+        );
+        assert_eq!(
+            osmo_profile.load_config(&deps.storage).unwrap(),
+            conf("uosmo")
+        );
+
+        default_profile
+            .price_infos()
+            .save(&mut deps.storage, "uatom", &price_info(1000))
+            .unwrap();
+        osmo_profile
+            .price_infos()
+            .save(&mut deps.storage, "uatom", &price_info(500))
+            .unwrap();
+
+        // default profile shares the store with `PRICE_INFOS`
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uatom").unwrap(),
+            price_info(1000)
+        );
+        assert_eq!(
+            osmo_profile
+                .price_infos()
+                .load(&deps.storage, "uatom")
+                .unwrap(),
+            price_info(500)
+        );
+
+        osmo_profile.price_infos().clear(&mut deps.storage);
+        assert_eq!(
+            PRICE_INFOS.load(&deps.storage, "uatom").unwrap(),
+            price_info(1000)
+        );
+    }
+
+    #[test]
+    fn test_migrate_spending() {
+        let mut deps = mock_dependencies();
+        let default_profile = PriceProfile::new(None);
+        let osmo_profile = PriceProfile::new(Some("osmo".to_string()));
+
+        assert_eq!(
+            default_profile
+                .quote_denom_migration_count(&deps.storage)
+                .unwrap(),
+            0
+        );
+
+        for (id, rate) in [(0, Decimal::percent(50)), (1, Decimal::percent(300))] {
+            assert_eq!(
+                default_profile
+                    .save_quote_denom_migration(&mut deps.storage, rate)
+                    .unwrap(),
+                id
+            );
+        }
+
+        assert_eq!(
+            default_profile
+                .quote_denom_migration_count(&deps.storage)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            osmo_profile
+                .quote_denom_migration_count(&deps.storage)
+                .unwrap(),
+            0
+        );
+
+        let spending = |value_spent: u128, applied: u64| Spending {
+            value_spent_in_period: value_spent.into(),
+            quote_denom_migrations_applied: applied,
+            ..Spending::default()
+        };
+
+        for (mut migrated, expected) in [
+            // rescaled with all the migrations
+            (
+                spending(1_000, 0),
+                Spending {
+                    limit_conversion_rate: Some(Decimal::percent(150)),
+                    ..spending(1_500, 2)
+                },
+            ),
+            // rescaled with the migrations after it was last rescaled
+            (
+                spending(1_000, 1),
+                Spending {
+                    limit_conversion_rate: Some(Decimal::percent(300)),
+                    ..spending(3_000, 2)
+                },
+            ),
+            // already rescaled with all the migrations
+            (spending(1_000, 2), spending(1_000, 2)),
+        ] {
+            default_profile
+                .migrate_spending(&deps.storage, &mut migrated)
+                .unwrap();
+            assert_eq!(migrated, expected);
+        }
+
+        // migrations are isolated by the price profile
+        let mut migrated = spending(1_000, 0);
+        osmo_profile
+            .migrate_spending(&deps.storage, &mut migrated)
+            .unwrap();
+        assert_eq!(migrated, spending(1_000, 0));
+    }
+}
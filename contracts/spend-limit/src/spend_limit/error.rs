@@ -22,6 +22,15 @@ pub enum SpendLimitError {
 
     #[error("Overspend: {spent} has been spent but limit is {limit}")]
     Overspend { limit: Uint128, spent: Uint128 },
+
+    #[error("Fee overspend: {spent} has been spent on fees but fee limit is {fee_limit}")]
+    FeeOverspend { fee_limit: Uint128, spent: Uint128 },
+
+    #[error("Spending untracked denom {denom} is not allowed while the contract is paused in strict mode")]
+    UntrackedDenomSpent { denom: String },
+
+    #[error("Exponent of the quote denom was not recorded for the display limit")]
+    MissingDisplayLimitExponent {},
 }
 
 impl SpendLimitError {
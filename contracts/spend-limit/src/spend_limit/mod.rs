@@ -6,18 +6,20 @@ use crate::{
     authenticator::{
         AuthenticatorError, CompositeAuthenticator, CompositeId, CosmwasmAuthenticatorData,
     },
-    fee::UntrackedSpentFeeStore,
+    fee::{FeePolicy, UntrackedSpentFeeStore},
     period::Period,
-    price::{get_and_cache_price, get_price, PriceInfoStore, PriceResolutionConfig},
+    price::{
+        get_and_cache_price, get_price, DenomAliasStore, PriceInfoStore, PriceResolutionConfig,
+    },
     ContractError,
 };
-use cosmwasm_std::{from_json, Coin, Deps};
+use cosmwasm_std::{ensure, from_json, Coin, Deps, Event};
 use cosmwasm_std::{DepsMut, StdError, Timestamp, Uint128};
 pub use error::SpendLimitError;
 use osmosis_std::types::osmosis::smartaccount::v1beta1::SmartaccountQuerier;
 pub use params::{SpendLimitParams, TimeLimit};
 pub use spending::{calculate_received_coins, calculate_spent_coins, Spending};
-use std::{cmp::max, str::FromStr};
+use std::{borrow::Cow, cmp::max, str::FromStr};
 
 use cosmwasm_std::Addr;
 use cw_storage_plus::Map;
@@ -32,10 +34,15 @@ pub type PreExecBalance<'a> = Map<'a, SpendingKey<'a>, Vec<Coin>>;
 /// allows multiple spend limits per account.
 pub type SpendingKey<'a> = (&'a Addr, &'a str);
 
+/// Update the spending with the spent and received coins and check it against the limit.
+/// If `strict`, spending untracked denoms is rejected and expired cached prices are not used,
+/// see [`crate::pause::PauseMode::Strict`].
+/// Returns events from the price resolution to be emitted by the caller.
 #[allow(clippy::too_many_arguments)]
 pub fn update_and_check_spend_limit(
     mut deps: DepsMut,
     price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
     spending: &mut Spending,
     spent_coins: impl IntoIterator<Item = Coin>,
     received_coins: impl IntoIterator<Item = Coin>,
@@ -43,29 +50,38 @@ pub fn update_and_check_spend_limit(
     limit: Uint128,
     reset_period: &Period,
     time: Timestamp,
-) -> Result<(), ContractError> {
-    let prev_value_spent = spending.get_or_reset_value_spent(reset_period, time)?;
-    let mut value_spent = prev_value_spent;
-
-    for spent in spent_coins.into_iter() {
-        // If the coin is not tracked (hence quoted_value = None), we don't count it towards the spending limit
-        // ceil spent to ensure that total value spent is always greater than or equal to the actual value spent
-        let Some(spent_coin_value) =
-            get_value_ceil(deps.branch(), price_info_store, conf, time, spent)?
-        else {
-            continue;
-        };
+    strict: bool,
+) -> Result<Vec<Event>, ContractError> {
+    let conf = strict_config(conf, strict);
 
-        value_spent = value_spent
-            .checked_add(spent_coin_value)
-            .map_err(StdError::from)?;
-    }
+    let prev_value_spent = spending.get_or_reset_value_spent(reset_period, time)?;
+    let fee_spent = spending.get_or_reset_fee_spent(reset_period, time)?;
+    let mut events = vec![];
+
+    let mut value_spent = add_spent_value(
+        deps.branch(),
+        price_info_store,
+        denom_alias_store,
+        &conf,
+        time,
+        prev_value_spent,
+        spent_coins,
+        strict,
+        &mut events,
+    )?;
 
     for received in received_coins.into_iter() {
         // If the coin is not tracked (hence quoted_value = None), we don't count it towards the spending limit
         // floor received to ensure that total value spent is always greater than or equal to the actual value spent
-        let Some(received_coin_value) =
-            get_value_floor(deps.branch(), price_info_store, conf, time, received)?
+        let Some(received_coin_value) = get_value_floor(
+            deps.branch(),
+            price_info_store,
+            denom_alias_store,
+            &conf,
+            time,
+            received,
+            &mut events,
+        )?
         else {
             continue;
         };
@@ -77,27 +93,131 @@ pub fn update_and_check_spend_limit(
     let value_spent = max(prev_value_spent, value_spent);
 
     spending
-        .update(value_spent, time)
+        .update(value_spent, fee_spent, time)
         .ensure_within_limit(limit)?;
 
-    Ok(())
+    Ok(events)
+}
+
+/// Update the fee spending with the spent fee and check it against the separate fee limit,
+/// see [`SpendLimitParams::fee_limit`]. Fees of untracked denoms are not counted, unless `strict`.
+/// Returns events from the price resolution to be emitted by the caller.
+#[allow(clippy::too_many_arguments)]
+pub fn update_and_check_fee_limit(
+    deps: DepsMut,
+    price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
+    spending: &mut Spending,
+    spent_fee: impl IntoIterator<Item = Coin>,
+    conf: &PriceResolutionConfig,
+    fee_limit: Uint128,
+    reset_period: &Period,
+    time: Timestamp,
+    strict: bool,
+) -> Result<Vec<Event>, ContractError> {
+    let conf = strict_config(conf, strict);
+
+    let value_spent = spending.get_or_reset_value_spent(reset_period, time)?;
+    let prev_fee_spent = spending.get_or_reset_fee_spent(reset_period, time)?;
+    let mut events = vec![];
+
+    let fee_spent = add_spent_value(
+        deps,
+        price_info_store,
+        denom_alias_store,
+        &conf,
+        time,
+        prev_fee_spent,
+        spent_fee,
+        strict,
+        &mut events,
+    )?;
+
+    spending
+        .update(value_spent, fee_spent, time)
+        .ensure_fee_within_limit(fee_limit)?;
+
+    Ok(events)
+}
+
+/// Config to value the spendings with, expired cached prices are not used if `strict`.
+fn strict_config(conf: &PriceResolutionConfig, strict: bool) -> Cow<'_, PriceResolutionConfig> {
+    if strict {
+        Cow::Owned(PriceResolutionConfig {
+            max_price_age: None,
+            ..conf.clone()
+        })
+    } else {
+        Cow::Borrowed(conf)
+    }
+}
+
+/// Add the value of the spent coins to `value_spent`.
+#[allow(clippy::too_many_arguments)]
+fn add_spent_value(
+    mut deps: DepsMut,
+    price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
+    conf: &PriceResolutionConfig,
+    time: Timestamp,
+    mut value_spent: Uint128,
+    spent_coins: impl IntoIterator<Item = Coin>,
+    strict: bool,
+    events: &mut Vec<Event>,
+) -> Result<Uint128, ContractError> {
+    for spent in spent_coins.into_iter() {
+        let denom = spent.denom.clone();
+
+        // If the coin is not tracked (hence quoted_value = None), we don't count it towards the spending limit
+        // ceil spent to ensure that total value spent is always greater than or equal to the actual value spent
+        let Some(spent_coin_value) = get_value_ceil(
+            deps.branch(),
+            price_info_store,
+            denom_alias_store,
+            conf,
+            time,
+            spent,
+            events,
+        )?
+        else {
+            ensure!(!strict, SpendLimitError::UntrackedDenomSpent { denom });
+            continue;
+        };
+
+        value_spent = value_spent
+            .checked_add(spent_coin_value)
+            .map_err(StdError::from)?;
+    }
+
+    Ok(value_spent)
 }
 
 fn get_value_ceil(
     deps: DepsMut,
     price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
     conf: &PriceResolutionConfig,
     time: Timestamp,
     coin: Coin,
+    events: &mut Vec<Event>,
 ) -> Result<Option<Uint128>, ContractError> {
-    let Some(price_info) = get_and_cache_price(price_info_store, deps, conf, time, &coin.denom)?
+    let Some(price_info) = get_and_cache_price(
+        price_info_store,
+        denom_alias_store,
+        deps,
+        conf,
+        time,
+        &coin.denom,
+    )?
     else {
         return Ok(None);
     };
 
+    events.extend(price_info.events(&coin.denom, time, conf.staleness_threshold));
+
     let value = coin
         .amount
-        .checked_mul_ceil(price_info.price)
+        .checked_mul_ceil(price_info.spent_price(conf)?)
         .map_err(std_err_from_checked_mul_frac)?;
 
     Ok(Some(value))
@@ -106,15 +226,26 @@ fn get_value_ceil(
 fn get_value_floor(
     deps: DepsMut,
     price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
     conf: &PriceResolutionConfig,
     time: Timestamp,
     coin: Coin,
+    events: &mut Vec<Event>,
 ) -> Result<Option<Uint128>, ContractError> {
-    let Some(price_info) = get_and_cache_price(price_info_store, deps, conf, time, &coin.denom)?
+    let Some(price_info) = get_and_cache_price(
+        price_info_store,
+        denom_alias_store,
+        deps,
+        conf,
+        time,
+        &coin.denom,
+    )?
     else {
         return Ok(None);
     };
 
+    events.extend(price_info.events(&coin.denom, time, conf.staleness_threshold));
+
     let value = coin
         .amount
         .checked_mul_floor(price_info.price)
@@ -130,23 +261,59 @@ pub fn get_spend_limit_params(
     account: &Addr,
     authenticator_id: &str,
 ) -> Result<SpendLimitParams, ContractError> {
+    may_get_spend_limit_params(deps, account, authenticator_id)?.ok_or_else(|| {
+        StdError::not_found(format!(
+            "Authenticator with account = {}, authenticator_id = {}",
+            account, authenticator_id
+        ))
+        .into()
+    })
+}
+
+/// Get spend limit params from the authenticator data, `None` if the authenticator doesn't exist.
+/// Querying a removed authenticator can either fail or respond with an empty authenticator,
+/// so a failed query is only treated as removal if the account no longer lists the authenticator.
+pub fn may_get_spend_limit_params(
+    deps: Deps,
+    account: &Addr,
+    authenticator_id: &str,
+) -> Result<Option<SpendLimitParams>, ContractError> {
     let smart_account_querier = SmartaccountQuerier::new(&deps.querier);
 
     let composite_id = CompositeId::from_str(authenticator_id).map_err(AuthenticatorError::from)?;
 
     let response =
-        smart_account_querier.get_authenticator(account.to_string(), composite_id.root)?;
+        match smart_account_querier.get_authenticator(account.to_string(), composite_id.root) {
+            Ok(response) => response,
+            Err(err) => {
+                let is_removed = smart_account_querier
+                    .get_authenticators(account.to_string())?
+                    .account_authenticators
+                    .iter()
+                    .all(|authenticator| authenticator.id != composite_id.root);
+
+                if is_removed {
+                    return Ok(None);
+                }
+
+                return Err(err.into());
+            }
+        };
 
-    let spend_limit_auth_data = response
+    let Some(account_authenticator) = response
         .account_authenticator
-        .ok_or(StdError::not_found(format!(
-            "Authenticator with account = {}, authenticator_id = {}",
-            account, authenticator_id
-        )))?
+        .filter(|authenticator| !authenticator.r#type.is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let spend_limit_auth_data = account_authenticator
         .child_authenticator_data::<CosmwasmAuthenticatorData>(&composite_id.path)
         .map_err(AuthenticatorError::from)?;
 
-    from_json::<SpendLimitParams>(&spend_limit_auth_data.params).map_err(ContractError::from)
+    from_json::<SpendLimitParams>(&spend_limit_auth_data.params)
+        .map(Some)
+        .map_err(ContractError::from)
 }
 
 /// Update stored spending with updated information such as reset period, untracked spent fee
@@ -154,6 +321,7 @@ pub fn get_spend_limit_params(
 pub fn updated_spending(
     deps: Deps,
     price_info_store: &PriceInfoStore,
+    denom_alias_store: &DenomAliasStore,
     untracked_spent_fee_store: &UntrackedSpentFeeStore,
     conf: &PriceResolutionConfig,
     account: &Addr,
@@ -163,24 +331,41 @@ pub fn updated_spending(
 ) -> Result<Spending, ContractError> {
     let params = get_spend_limit_params(deps, account, authenticator_id)?;
     let mut value_spent_in_period = spending.get_or_reset_value_spent(&params.reset_period, at)?;
+    let mut fee_spent_in_period = spending.get_or_reset_fee_spent(&params.reset_period, at)?;
 
-    // add untracked spent fee as part of value spent
+    // add untracked spent fee as part of value spent, or fee spent if it has a separate limit
     let untracked_spent_fee = untracked_spent_fee_store
         .may_load(deps.storage, (account, authenticator_id))?
         .unwrap_or_default();
 
     let last_spent_at = spending.last_spent_at.max(untracked_spent_fee.updated_at);
 
-    let accumulated_fee = untracked_spent_fee.get_or_reset_accum_fee(&params.reset_period, at)?;
+    let accumulated_fee = match params.fee_policy {
+        FeePolicy::Exclude => vec![],
+        _ => untracked_spent_fee.get_or_reset_accum_fee(&params.reset_period, at)?,
+    };
 
     for fee in accumulated_fee {
-        if let Some(price) = get_price(price_info_store, deps, conf, at, &fee.denom)? {
+        if let Some(price) = get_price(
+            price_info_store,
+            denom_alias_store,
+            deps,
+            conf,
+            at,
+            &fee.denom,
+        )? {
             let fee_spent = fee
                 .amount
                 .checked_mul_ceil(price.price)
                 .map_err(std_err_from_checked_mul_frac)?;
 
-            value_spent_in_period = value_spent_in_period
+            let spent_in_period = if params.fee_limit.is_some() {
+                &mut fee_spent_in_period
+            } else {
+                &mut value_spent_in_period
+            };
+
+            *spent_in_period = spent_in_period
                 .checked_add(fee_spent)
                 .map_err(StdError::overflow)?;
         };
@@ -188,7 +373,12 @@ pub fn updated_spending(
 
     Ok(Spending {
         value_spent_in_period,
+        fee_spent_in_period,
         last_spent_at,
+        limit_conversion_rate: spending.limit_conversion_rate,
+        display_limit_exponent: spending.display_limit_exponent,
+        price_profile: spending.price_profile,
+        quote_denom_migrations_applied: spending.quote_denom_migrations_applied,
     })
 }
 
@@ -207,10 +397,12 @@ mod tests {
     use std::str::FromStr;
 
     use crate::spend_limit::SpendLimitError;
-    use crate::state::PRICE_INFOS;
+    use crate::state::{DENOM_ALIASES, PRICE_INFOS};
 
     use super::*;
-    use crate::price::PriceInfo;
+    use crate::price::{DenomAlias, PriceInfo, SpotPriceFallback};
+    use crate::test_helper;
+    use crate::test_helper::price::price_info;
     use cosmwasm_std::testing::{MockApi, MockQuerier};
     use cosmwasm_std::{testing::mock_dependencies, Uint64};
     use cosmwasm_std::{Decimal, MemoryStorage, OwnedDeps};
@@ -222,9 +414,9 @@ mod tests {
     #[fixture]
     fn price_resolution_config() -> PriceResolutionConfig {
         PriceResolutionConfig {
-            quote_denom: "uusdc".to_string(),
             staleness_threshold: Uint64::from(3_600_000_000u64),
             twap_duration: Uint64::from(3_600_000_000u64),
+            ..test_helper::price::price_resolution_config("uusdc")
         }
     }
 
@@ -234,18 +426,39 @@ mod tests {
     }
 
     fn setup_price_infos(deps: DepsMut) {
-        let uosmo_price_info = PriceInfo {
-            price: Decimal::from_str("1.5").unwrap(),
-            last_updated_time: Timestamp::from_seconds(1_625_702_410),
-            swap_routes: vec![SwapAmountInRoute {
+        let uosmo_price_info = price_info(
+            Decimal::from_str("1.5").unwrap(),
+            Timestamp::from_seconds(1_625_702_410),
+            vec![SwapAmountInRoute {
                 pool_id: 555,
                 token_out_denom: "uusdc".to_string(),
             }],
-        };
+        );
 
         PRICE_INFOS
             .save(deps.storage, "uosmo", &uosmo_price_info)
             .unwrap();
+
+        DENOM_ALIASES
+            .save(
+                deps.storage,
+                "uosmo.axl",
+                &DenomAlias {
+                    canonical_denom: "uosmo".to_string(),
+                    ratio: Some(Decimal::percent(50)),
+                },
+            )
+            .unwrap();
+        DENOM_ALIASES
+            .save(
+                deps.storage,
+                "uusdc.axl",
+                &DenomAlias {
+                    canonical_denom: "uusdc".to_string(),
+                    ratio: None,
+                },
+            )
+            .unwrap();
     }
 
     #[rstest]
@@ -256,6 +469,8 @@ mod tests {
     #[case::spent_and_received(vec![Coin::new(100, "uosmo")], vec![Coin::new(50, "uusdc")], 150, 250, Ok(()))]
     #[case::untracked_coin(vec![Coin::new(100, "unknown")], vec![], 0, 0, Ok(()))]
     #[case::untracked_coin(vec![Coin::new(100, "uusdc")], vec![Coin::new(100, "unknown")], 0, 100, Ok(()))]
+    #[case::aliased_coin(vec![Coin::new(100, "uosmo.axl")], vec![], 0, 75, Ok(()))]
+    #[case::aliased_quote_denom(vec![Coin::new(100, "uusdc.axl")], vec![Coin::new(100, "uosmo.axl")], 0, 25, Ok(()))]
     #[case::exceed_spend_limit(vec![Coin::new(1_000_000, "uosmo")], vec![], 0, 1_500_000, Err(SpendLimitError::overspend(1_000_000, 1_500_000).into()))]
     #[case::at_spend_limit(vec![Coin::new(1_000_000, "uusdc")], vec![], 0, 1_000_000, Ok(()))]
     #[case::at_spend_limit(vec![Coin::new(1_000_000, "uosmo")], vec![Coin::new(500_000, "uusdc")], 0, 1_000_000, Ok(()))]
@@ -273,7 +488,12 @@ mod tests {
 
         let mut spending = Spending {
             value_spent_in_period: Uint128::from(initial_spending),
+            fee_spent_in_period: Uint128::zero(),
             last_spent_at: time.minus_seconds(5),
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: None,
+            quote_denom_migrations_applied: 0,
         };
 
         let limit = Uint128::from(1_000_000u128);
@@ -281,6 +501,7 @@ mod tests {
         let result = update_and_check_spend_limit(
             deps.as_mut(),
             &PRICE_INFOS,
+            &DENOM_ALIASES,
             &mut spending,
             spent_coins,
             received_coins,
@@ -288,19 +509,249 @@ mod tests {
             limit,
             &Period::Day,
             time,
+            false,
         );
 
-        assert_eq!(result, expected_result);
+        assert_eq!(result.map(|_| ()), expected_result);
 
         assert_eq!(
             spending,
             Spending {
                 value_spent_in_period: Uint128::from(expected_spending),
+                fee_spent_in_period: Uint128::zero(),
                 last_spent_at: time,
+                limit_conversion_rate: None,
+                display_limit_exponent: None,
+                price_profile: None,
+                quote_denom_migrations_applied: 0,
             }
         );
     }
 
+    #[rstest]
+    #[case::spent_marked_up(vec![Coin::new(100, "uosmo")], vec![], 500, 665)]
+    #[case::spent_and_received(
+        vec![Coin::new(100, "uosmo")],
+        vec![Coin::new(100, "uosmo")],
+        500,
+        515
+    )]
+    fn test_update_and_check_spend_limit_with_spot_price_fallback(
+        mut deps: OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
+        #[case] spent_coins: Vec<Coin>,
+        #[case] received_coins: Vec<Coin>,
+        #[case] initial_spending: u128,
+        #[case] expected_spending: u128,
+    ) {
+        let time = Timestamp::from_seconds(1_625_702_410);
+        let conf = PriceResolutionConfig {
+            spot_price_fallback: Some(SpotPriceFallback {
+                max_deviation: Decimal::percent(10),
+                markup: Decimal::percent(10),
+            }),
+            ..test_helper::price::price_resolution_config("uusdc")
+        };
+
+        // spot price that the fallback bounded around the last twap price
+        PRICE_INFOS
+            .save(
+                deps.as_mut().storage,
+                "uosmo",
+                &PriceInfo {
+                    spot_price_fallback_twap: Some(Decimal::from_str("1.5").unwrap()),
+                    ..price_info(Decimal::from_str("1.5").unwrap(), time, vec![])
+                },
+            )
+            .unwrap();
+
+        let mut spending = Spending {
+            value_spent_in_period: Uint128::new(initial_spending),
+            last_spent_at: time,
+            ..Spending::default()
+        };
+
+        update_and_check_spend_limit(
+            deps.as_mut(),
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            &mut spending,
+            spent_coins,
+            received_coins,
+            &conf,
+            Uint128::new(1_000),
+            &Period::Day,
+            time,
+            false,
+        )
+        .unwrap();
+
+        // spent coins are valued above the spot price and received coins at the spot price,
+        // so that receiving the denom can't be credited more than it's worth
+        assert_eq!(
+            spending.value_spent_in_period,
+            Uint128::new(expected_spending)
+        );
+    }
+
+    #[rstest]
+    #[case::tracked_coin(vec![Coin::new(100, "uosmo")], Ok(150))]
+    #[case::aliased_coin(vec![Coin::new(100, "uosmo.axl")], Ok(75))]
+    #[case::untracked_coin(
+        vec![Coin::new(100, "uosmo"), Coin::new(100, "unknown")],
+        Err(SpendLimitError::UntrackedDenomSpent { denom: "unknown".to_string() }.into())
+    )]
+    fn test_update_and_check_spend_limit_strict(
+        mut deps: OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
+        price_resolution_config: PriceResolutionConfig,
+        #[case] spent_coins: Vec<Coin>,
+        #[case] expected: Result<u128, ContractError>,
+    ) {
+        setup_price_infos(deps.as_mut());
+        let time = Timestamp::from_seconds(1_625_702_410);
+
+        let mut spending = Spending::default();
+
+        let result = update_and_check_spend_limit(
+            deps.as_mut(),
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            &mut spending,
+            spent_coins,
+            vec![],
+            &price_resolution_config,
+            Uint128::from(1_000_000u128),
+            &Period::Day,
+            time,
+            true,
+        );
+
+        assert_eq!(
+            result.map(|_| spending.value_spent_in_period.u128()),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::within_fee_limit(vec![Coin::new(100, "uosmo")], 0, 0, (500, 150), Ok(()))]
+    #[case::accumulate_fee(vec![Coin::new(100, "uosmo")], 100, 0, (500, 250), Ok(()))]
+    #[case::untracked_fee(vec![Coin::new(100, "unknown")], 100, 0, (500, 100), Ok(()))]
+    #[case::at_fee_limit(vec![Coin::new(900, "uusdc")], 100, 0, (500, 1_000), Ok(()))]
+    #[case::exceed_fee_limit(
+        vec![Coin::new(1_000, "uosmo")],
+        0,
+        0,
+        (500, 1_500),
+        Err(SpendLimitError::FeeOverspend { fee_limit: Uint128::new(1_000), spent: Uint128::new(1_500) }.into())
+    )]
+    #[case::period_changed(vec![Coin::new(100, "uosmo")], 900, 1, (0, 150), Ok(()))]
+    fn test_update_and_check_fee_limit(
+        mut deps: OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
+        price_resolution_config: PriceResolutionConfig,
+        #[case] spent_fee: Vec<Coin>,
+        #[case] initial_fee_spent: u128,
+        #[case] days_since_last_spent: u64,
+        #[case] expected_spending: (u128, u128),
+        #[case] expected_result: Result<(), ContractError>,
+    ) {
+        setup_price_infos(deps.as_mut());
+        let time = Timestamp::from_seconds(1_625_702_410);
+
+        let mut spending = Spending {
+            value_spent_in_period: Uint128::new(500),
+            fee_spent_in_period: Uint128::new(initial_fee_spent),
+            last_spent_at: time.minus_days(days_since_last_spent),
+            ..Spending::default()
+        };
+
+        let result = update_and_check_fee_limit(
+            deps.as_mut(),
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            &mut spending,
+            spent_fee,
+            &price_resolution_config,
+            Uint128::new(1_000),
+            &Period::Day,
+            time,
+            false,
+        );
+
+        assert_eq!(result.map(|_| ()), expected_result);
+        assert_eq!(
+            (
+                spending.value_spent_in_period.u128(),
+                spending.fee_spent_in_period.u128()
+            ),
+            expected_spending
+        );
+        assert_eq!(spending.last_spent_at, time);
+    }
+
+    #[test]
+    fn test_update_and_check_spend_limit_resets_fee_spent() {
+        let mut deps = mock_dependencies();
+        setup_price_infos(deps.as_mut());
+        let time = Timestamp::from_seconds(1_625_702_410);
+
+        let mut spending = Spending {
+            value_spent_in_period: Uint128::new(500),
+            fee_spent_in_period: Uint128::new(900),
+            last_spent_at: time.minus_days(1),
+            ..Spending::default()
+        };
+
+        update_and_check_spend_limit(
+            deps.as_mut(),
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            &mut spending,
+            vec![Coin::new(100, "uosmo")],
+            vec![],
+            &price_resolution_config(),
+            Uint128::new(1_000),
+            &Period::Day,
+            time,
+            false,
+        )
+        .unwrap();
+
+        // fee spent is reset along with the value spent in the new period
+        assert_eq!(spending.value_spent_in_period, Uint128::new(150));
+        assert_eq!(spending.fee_spent_in_period, Uint128::zero());
+    }
+
+    #[test]
+    fn test_update_and_check_spend_limit_strict_ignores_max_price_age() {
+        let mut deps = mock_dependencies();
+        setup_price_infos(deps.as_mut());
+
+        // cached price is stale and can't be refreshed since there is no pool to query
+        let time = Timestamp::from_seconds(1_625_702_410).plus_hours(2);
+        let conf = PriceResolutionConfig {
+            max_price_age: Some(Uint64::from(86_400_000_000_000u64)),
+            ..test_helper::price::price_resolution_config("uusdc")
+        };
+
+        for (strict, expected_ok) in [(false, true), (true, false)] {
+            let mut spending = Spending::default();
+            let result = update_and_check_spend_limit(
+                deps.as_mut(),
+                &PRICE_INFOS,
+                &DENOM_ALIASES,
+                &mut spending,
+                vec![Coin::new(100, "uosmo")],
+                vec![],
+                &conf,
+                Uint128::from(1_000_000u128),
+                &Period::Day,
+                time,
+                strict,
+            );
+
+            assert_eq!(result.is_ok(), expected_ok, "strict: {strict}");
+        }
+    }
+
     /// ensure that get value rounds up the multiplication result
     /// This is important because if we can spend 0.x repeatedly,
     /// it can be spent without limit as it rounds down to 0
@@ -309,20 +760,24 @@ mod tests {
         let mut deps = mock_dependencies();
         setup_price_infos(deps.as_mut());
 
-        let conf = PriceResolutionConfig {
-            quote_denom: "uusdc".to_string(),
-            staleness_threshold: Uint64::from(3_600_000_000u64),
-            twap_duration: Uint64::from(3_600_000_000u64),
-        };
+        let conf = price_resolution_config();
 
         let time = Timestamp::from_seconds(1_625_702_410); // Arbitrary fixed timestamp
 
         let coin = Coin::new(1, "uosmo");
 
-        let value = get_value_ceil(deps.as_mut(), &PRICE_INFOS, &conf, time, coin)
-            .unwrap()
-            .unwrap()
-            .u128();
+        let value = get_value_ceil(
+            deps.as_mut(),
+            &PRICE_INFOS,
+            &DENOM_ALIASES,
+            &conf,
+            time,
+            coin,
+            &mut vec![],
+        )
+        .unwrap()
+        .unwrap()
+        .u128();
 
         assert_eq!(value, 2);
     }
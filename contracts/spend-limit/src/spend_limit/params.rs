@@ -1,18 +1,58 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Timestamp, Uint128};
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
 
-use crate::period::Period;
+use crate::{denom_metadata::from_display_amount, fee::FeePolicy, period::Period};
+
+use super::error::{SpendLimitError, SpendLimitResult};
 
 #[cw_serde]
 pub struct SpendLimitParams {
-    /// Limit per period, used to enforce spend limit with this given amount in quote denom
+    /// Limit per period, used to enforce spend limit with this given amount in quote denom.
+    /// The amount is in base units of the quote denom, e.g. `1000000` for 1 USDC in `uusdc`.
+    #[serde(default)]
     pub limit: Uint128,
 
+    /// Limit per period in display units of the quote denom, e.g. `1.5` for 1.5 USDC,
+    /// converted to base units with the exponent of the quote denom when the authenticator is added.
+    /// This can't be set along with a non-zero `limit`.
+    pub display_limit: Option<Decimal>,
+
     /// Period to reset spend limit quota
     pub reset_period: Period,
 
     /// Time limit for the spend limit
     pub time_limit: Option<TimeLimit>,
+
+    /// Name of the price profile that values the spendings and the limit in its quote denom.
+    /// If not set, the default price resolution config and tracked denoms are used.
+    pub price_profile: Option<String>,
+
+    /// How the transaction fee counts towards the spending,
+    /// defaults to counting the fee only if the account pays for it
+    #[serde(default)]
+    pub fee_policy: FeePolicy,
+
+    /// Separate limit per period for the transaction fees, in base units of the quote denom.
+    /// If set, fees count towards this limit instead of `limit`,
+    /// so that a fee spike doesn't use up the spending of the period.
+    pub fee_limit: Option<Uint128>,
+}
+
+impl SpendLimitParams {
+    /// Limit per period in base units of the quote denom,
+    /// given the exponent recorded when the authenticator was added.
+    pub fn limit_in_base_units(
+        &self,
+        display_limit_exponent: Option<u32>,
+    ) -> SpendLimitResult<Uint128> {
+        match (self.display_limit, display_limit_exponent) {
+            (None, _) => Ok(self.limit),
+            (Some(display_limit), Some(exponent)) => {
+                Ok(from_display_amount(display_limit, exponent)?)
+            }
+            (Some(_), None) => Err(SpendLimitError::MissingDisplayLimitExponent {}),
+        }
+    }
 }
 
 #[cw_serde]
@@ -1,12 +1,12 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Coins, Timestamp, Uint128};
+use cosmwasm_std::{Coin, Coins, Decimal, Timestamp, Uint128};
 
 use crate::{
     period::{to_offset_datetime, Period},
     spend_limit::error::SpendLimitError,
 };
 
-use super::error::SpendLimitResult;
+use super::{error::SpendLimitResult, std_err_from_checked_mul_frac};
 
 /// State for tracking spend limit.
 #[cw_serde]
@@ -16,32 +16,100 @@ pub struct Spending {
     /// This is reset when the period changes
     pub value_spent_in_period: Uint128,
 
+    /// The value of the transaction fees spent in the current period,
+    /// only counted if the authenticator has a separate fee limit.
+    /// This is reset along with the value spent in the period
+    #[serde(default)]
+    pub fee_spent_in_period: Uint128,
+
     /// The last time the account spent
     /// This is used to check if we are in a new period
     pub last_spent_at: Timestamp,
+
+    /// Rate to convert the authenticator's limit to the current quote denom.
+    /// This is set when the quote denom is migrated after the authenticator was added,
+    /// since its limit is still expressed in the previous quote denom.
+    pub limit_conversion_rate: Option<Decimal>,
+
+    /// Exponent of the quote denom at the time the authenticator was added.
+    /// This is only set if the authenticator's limit is expressed in display units,
+    /// and used to convert it to base units of that quote denom.
+    pub display_limit_exponent: Option<u32>,
+
+    /// Price profile selected by the authenticator, recorded when the authenticator is added
+    /// so that the spending can be valued without looking up the authenticator params.
+    pub price_profile: Option<String>,
+
+    /// Number of quote denom migrations of the price profile that the spending has been rescaled with,
+    /// see [`crate::price_profile::PriceProfile::migrate_spending`].
+    #[serde(default)]
+    pub quote_denom_migrations_applied: u64,
 }
 
 impl Spending {
     pub fn new(last_spent: Timestamp) -> Self {
         Self {
             value_spent_in_period: Uint128::zero(),
+            fee_spent_in_period: Uint128::zero(),
             last_spent_at: last_spent,
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: None,
+            quote_denom_migrations_applied: 0,
         }
     }
 
     pub fn update(
         &mut self,
         value_spent_in_period: Uint128,
+        fee_spent_in_period: Uint128,
         last_spent_at: Timestamp,
     ) -> &mut Self {
         self.value_spent_in_period = value_spent_in_period;
+        self.fee_spent_in_period = fee_spent_in_period;
         self.last_spent_at = last_spent_at;
 
         self
     }
 
+    /// Convert the limit, which is expressed in the quote denom at the time the authenticator was added,
+    /// to the current quote denom. Rounds down to never allow more than the original limit.
+    pub fn limit_in_quote_denom(&self, limit: Uint128) -> SpendLimitResult<Uint128> {
+        match self.limit_conversion_rate {
+            Some(rate) => Ok(limit
+                .checked_mul_floor(rate)
+                .map_err(std_err_from_checked_mul_frac)?),
+            None => Ok(limit),
+        }
+    }
+
+    /// Rescale the spending to a new quote denom, where 1 unit of the previous quote denom
+    /// is worth `conversion_rate` units of the new quote denom.
+    /// Value spent is rounded up to never undercount the spending.
+    pub fn migrate_quote_denom(&mut self, conversion_rate: Decimal) -> SpendLimitResult<&mut Self> {
+        self.value_spent_in_period = self
+            .value_spent_in_period
+            .checked_mul_ceil(conversion_rate)
+            .map_err(std_err_from_checked_mul_frac)?;
+
+        self.fee_spent_in_period = self
+            .fee_spent_in_period
+            .checked_mul_ceil(conversion_rate)
+            .map_err(std_err_from_checked_mul_frac)?;
+
+        self.limit_conversion_rate = Some(
+            self.limit_conversion_rate
+                .unwrap_or(Decimal::one())
+                .checked_mul(conversion_rate)?,
+        );
+
+        Ok(self)
+    }
+
     /// ensure that the value spent in the period is not over the limit
     pub fn ensure_within_limit(&self, limit: Uint128) -> SpendLimitResult<()> {
+        let limit = self.limit_in_quote_denom(limit)?;
+
         if self.value_spent_in_period > limit {
             Err(SpendLimitError::Overspend {
                 limit,
@@ -52,6 +120,20 @@ impl Spending {
         }
     }
 
+    /// ensure that the fee spent in the period is not over the fee limit
+    pub fn ensure_fee_within_limit(&self, fee_limit: Uint128) -> SpendLimitResult<()> {
+        let fee_limit = self.limit_in_quote_denom(fee_limit)?;
+
+        if self.fee_spent_in_period > fee_limit {
+            Err(SpendLimitError::FeeOverspend {
+                fee_limit,
+                spent: self.fee_spent_in_period,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     /// Get the value spent in the period.
     /// If the period has changed, the value spent in the period is reset to zero.
     pub fn get_or_reset_value_spent(
@@ -59,15 +141,33 @@ impl Spending {
         period: &Period,
         at: Timestamp,
     ) -> SpendLimitResult<Uint128> {
-        let previous = to_offset_datetime(&self.last_spent_at)?;
-        let current = to_offset_datetime(&at)?;
-
-        if period.has_changed(previous, current)? {
+        if self.has_period_changed(period, at)? {
             Ok(Uint128::zero())
         } else {
             Ok(self.value_spent_in_period)
         }
     }
+
+    /// Get the fee spent in the period.
+    /// If the period has changed, the fee spent in the period is reset to zero.
+    pub fn get_or_reset_fee_spent(
+        &self,
+        period: &Period,
+        at: Timestamp,
+    ) -> SpendLimitResult<Uint128> {
+        if self.has_period_changed(period, at)? {
+            Ok(Uint128::zero())
+        } else {
+            Ok(self.fee_spent_in_period)
+        }
+    }
+
+    fn has_period_changed(&self, period: &Period, at: Timestamp) -> SpendLimitResult<bool> {
+        let previous = to_offset_datetime(&self.last_spent_at)?;
+        let current = to_offset_datetime(&at)?;
+
+        Ok(period.has_changed(previous, current)?)
+    }
 }
 
 /// Calculate the spendings from the pre-execution balances and the post-execution balances.
@@ -224,4 +324,47 @@ mod tests {
         let expected = Coins::try_from(expected).unwrap();
         assert_eq!(expected, deltas);
     }
+
+    #[rstest]
+    #[case::no_migration(vec![], 1_000, 1_000, 1_000, Ok(()))]
+    #[case::no_migration(vec![], 1_001, 1_001, 1_000, Err(SpendLimitError::overspend(1_000, 1_001)))]
+    #[case::same_value(vec!["1"], 1_000, 1_000, 1_000, Ok(()))]
+    #[case::scale_up(vec!["1.5"], 1_000, 1_500, 1_500, Ok(()))]
+    #[case::scale_down(vec!["0.999"], 1_000, 999, 999, Ok(()))]
+    #[case::round_spent_up_and_limit_down(vec!["0.9999"], 1_000, 1_000, 999, Err(SpendLimitError::overspend(999, 1_000)))]
+    #[case::multiple_migrations(vec!["2", "0.25"], 1_000, 500, 500, Ok(()))]
+    fn test_migrate_quote_denom(
+        #[case] conversion_rates: Vec<&str>,
+        #[case] value_spent_in_period: u128,
+        #[case] expected_value_spent_in_period: u128,
+        #[case] expected_limit: u128,
+        #[case] expected: SpendLimitResult<()>,
+    ) {
+        let limit = Uint128::new(1_000);
+        let mut spending = Spending {
+            value_spent_in_period: Uint128::new(value_spent_in_period),
+            fee_spent_in_period: Uint128::zero(),
+            last_spent_at: Timestamp::from_seconds(1),
+            limit_conversion_rate: None,
+            display_limit_exponent: None,
+            price_profile: None,
+            quote_denom_migrations_applied: 0,
+        };
+
+        for conversion_rate in conversion_rates {
+            spending
+                .migrate_quote_denom(conversion_rate.parse().unwrap())
+                .unwrap();
+        }
+
+        assert_eq!(
+            spending.value_spent_in_period,
+            Uint128::new(expected_value_spent_in_period)
+        );
+        assert_eq!(
+            spending.limit_in_quote_denom(limit).unwrap(),
+            Uint128::new(expected_limit)
+        );
+        assert_eq!(spending.ensure_within_limit(limit), expected);
+    }
 }
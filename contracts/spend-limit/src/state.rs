@@ -1,10 +1,15 @@
+use cosmwasm_std::{Addr, Empty, Uint64};
 use cw_storage_plus::{Item, Map};
 
 use crate::{
     admin::Admin,
+    audit::AuditLogStore,
+    denom_metadata::DenomExponentStore,
     fee::UntrackedSpentFeeStore,
-    price::{PriceInfoStore, PriceResolutionConfig},
+    pause::PauseStateStore,
+    price::{CanonicalDenomAliasStore, DenomAliasStore, PriceInfoStore, PriceResolutionConfig},
     spend_limit::{PreExecBalance, SpendingStore},
+    timelock::{NextAdminActionIdStore, PendingAdminActionStore},
 };
 
 pub const SPENDINGS: SpendingStore<'_> = Map::new("spendings");
@@ -27,8 +32,53 @@ pub const UNTRACKED_SPENT_FEES: UntrackedSpentFeeStore<'_> = Map::new("untracked
 pub const PRICE_RESOLUTION_CONFIG: Item<PriceResolutionConfig> =
     Item::new("price_resolution_config");
 
+/// Namespace of [`PRICE_INFOS`], price infos of the named price profiles
+/// are stored under this namespace suffixed with the profile name.
+pub const PRICE_INFOS_NAMESPACE: &str = "price_infos";
+
+/// Namespace of the conversion rates of the quote denom migrations,
+/// see [`crate::price_profile::PriceProfile::quote_denom_migrations`].
+pub const QUOTE_DENOM_MIGRATIONS_NAMESPACE: &str = "quote_denom_migrations";
+
+/// Namespace of the number of quote denom migrations,
+/// see [`crate::price_profile::PriceProfile::quote_denom_migration_count`].
+pub const QUOTE_DENOM_MIGRATION_COUNT_NAMESPACE: &str = "quote_denom_migration_count";
+
 /// Store for the price info of the tracked denoms.
-pub const PRICE_INFOS: PriceInfoStore<'_> = Map::new("price_infos");
+pub const PRICE_INFOS: PriceInfoStore<'_> = Map::new(PRICE_INFOS_NAMESPACE);
+
+/// Configuration for the price resolution of the named price profiles,
+/// see [`crate::price_profile::PriceProfile`].
+pub const PRICE_PROFILES: Map<&str, PriceResolutionConfig> = Map::new("price_profiles");
+
+/// Aliases of the denoms that share the price of their canonical denom, set by the admin.
+/// Shared by all price profiles since they are about the denoms, not how they are priced.
+pub const DENOM_ALIASES: DenomAliasStore<'_> = Map::new("denom_aliases");
+
+/// Index of [`DENOM_ALIASES`] by their canonical denom, kept in sync with it.
+pub const CANONICAL_DENOM_ALIASES: CanonicalDenomAliasStore<'_> =
+    Map::new("denom_aliases_by_canonical_denom");
+
+/// Exponents of the display units of the denoms, set by the admin.
+pub const DENOM_EXPONENTS: DenomExponentStore<'_> = Map::new("denom_exponents");
 
 /// Admin address, Optional.
 pub const ADMIN: Item<Admin> = Item::new("admin");
+
+/// Pause state set by the guardians to restrict authentication, not paused if not set.
+pub const PAUSE_STATE: PauseStateStore<'_> = Item::new("pause_state");
+
+/// Append-only log of the admin operations, see [`crate::audit::AuditLogEntry`].
+pub const AUDIT_LOG: AuditLogStore<'_> = Map::new("audit_log");
+
+/// Holders of the roles granted by the admin, keyed by the name of the role and the holder.
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("roles");
+
+/// Delay in nanoseconds before the proposed admin actions can be executed,
+/// see [`crate::timelock::is_timelocked`]. Admin actions are not timelocked if not set.
+pub const ADMIN_ACTION_DELAY: Item<Uint64> = Item::new("admin_action_delay");
+
+/// Admin actions proposed and waiting for [`ADMIN_ACTION_DELAY`] to pass.
+pub const PENDING_ADMIN_ACTIONS: PendingAdminActionStore<'_> = Map::new("pending_admin_actions");
+
+pub const NEXT_ADMIN_ACTION_ID: NextAdminActionIdStore<'_> = Item::new("next_admin_action_id");
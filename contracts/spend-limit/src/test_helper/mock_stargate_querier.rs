@@ -6,11 +6,27 @@ use cosmwasm_std::{
     from_json,
     testing::{MockApi, MockQuerier, MockStorage},
     to_json_binary, Binary, Coin, ContractResult, CustomQuery, Empty, OwnedDeps, Querier,
-    QuerierResult, QuerierWrapper, QueryRequest, SystemError, SystemResult,
+    QuerierResult, QuerierWrapper, QueryRequest, SystemError, SystemResult, WasmQuery,
 };
+use osmosis_std::shim::Any;
+use osmosis_std::types::cosmos::bank::v1beta1::{
+    QueryDenomMetadataRequest, QueryDenomMetadataResponse,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 use osmosis_std::types::osmosis::{
-    smartaccount::v1beta1::{GetAuthenticatorRequest, GetAuthenticatorResponse},
-    twap::v1beta1::{ArithmeticTwapToNowRequest, ArithmeticTwapToNowResponse, TwapQuerier},
+    gamm::v1beta1::Pool as BalancerPool,
+    poolmanager::v1beta1::{
+        ListPoolsByDenomRequest, ListPoolsByDenomResponse, PoolRequest, PoolResponse,
+        SpotPriceRequest, SpotPriceResponse, TotalPoolLiquidityRequest, TotalPoolLiquidityResponse,
+    },
+    smartaccount::v1beta1::{
+        GetAuthenticatorRequest, GetAuthenticatorResponse, GetAuthenticatorsRequest,
+        GetAuthenticatorsResponse,
+    },
+    twap::v1beta1::{
+        ArithmeticTwapToNowRequest, ArithmeticTwapToNowResponse, GeometricTwapToNowRequest,
+        GeometricTwapToNowResponse, TwapQuerier,
+    },
 };
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -37,6 +53,13 @@ impl<C: DeserializeOwned> MockStargateQuerier<C> {
     pub fn update_balance(&mut self, addr: impl Into<String>, balance: Vec<Coin>) {
         self.mock_querier.update_balance(addr, balance);
     }
+
+    pub fn update_wasm<WH>(&mut self, handler: WH)
+    where
+        WH: Fn(&WasmQuery) -> QuerierResult + 'static,
+    {
+        self.mock_querier.update_wasm(handler);
+    }
 }
 
 impl<C: CustomQuery + DeserializeOwned> Querier for MockStargateQuerier<C> {
@@ -89,6 +112,14 @@ pub fn arithmetic_twap_to_now_query_handler(
     )
 }
 
+pub fn geometric_twap_to_now_query_handler(
+    req_mapper: Box<
+        dyn Fn(GeometricTwapToNowRequest) -> ContractResult<GeometricTwapToNowResponse>,
+    >,
+) -> QueryHandler {
+    make_query_handler("/osmosis.twap.v1beta1.Query/GeometricTwapToNow", req_mapper)
+}
+
 pub fn get_authenticator_query_handler(
     req_mapper: Box<dyn Fn(GetAuthenticatorRequest) -> ContractResult<GetAuthenticatorResponse>>,
 ) -> QueryHandler {
@@ -98,6 +129,153 @@ pub fn get_authenticator_query_handler(
     )
 }
 
+pub fn get_authenticators_query_handler(
+    req_mapper: Box<dyn Fn(GetAuthenticatorsRequest) -> ContractResult<GetAuthenticatorsResponse>>,
+) -> QueryHandler {
+    make_query_handler(
+        "/osmosis.smartaccount.v1beta1.Query/GetAuthenticators",
+        req_mapper,
+    )
+}
+
+pub fn pool_query_handler(
+    req_mapper: Box<dyn Fn(PoolRequest) -> ContractResult<PoolResponse>>,
+) -> QueryHandler {
+    make_query_handler("/osmosis.poolmanager.v1beta1.Query/Pool", req_mapper)
+}
+
+pub fn total_pool_liquidity_query_handler(
+    req_mapper: Box<
+        dyn Fn(TotalPoolLiquidityRequest) -> ContractResult<TotalPoolLiquidityResponse>,
+    >,
+) -> QueryHandler {
+    make_query_handler(
+        "/osmosis.poolmanager.v1beta1.Query/TotalPoolLiquidity",
+        req_mapper,
+    )
+}
+
+pub fn spot_price_query_handler(
+    req_mapper: Box<dyn Fn(SpotPriceRequest) -> ContractResult<SpotPriceResponse>>,
+) -> QueryHandler {
+    make_query_handler("/osmosis.poolmanager.v1beta1.Query/SpotPrice", req_mapper)
+}
+
+pub fn list_pools_by_denom_query_handler(
+    req_mapper: Box<dyn Fn(ListPoolsByDenomRequest) -> ContractResult<ListPoolsByDenomResponse>>,
+) -> QueryHandler {
+    make_query_handler(
+        "/osmosis.poolmanager.v1beta1.Query/ListPoolsByDenom",
+        req_mapper,
+    )
+}
+
+pub fn denom_metadata_query_handler(
+    req_mapper: Box<
+        dyn Fn(QueryDenomMetadataRequest) -> ContractResult<QueryDenomMetadataResponse>,
+    >,
+) -> QueryHandler {
+    make_query_handler("/cosmos.bank.v1beta1.Query/DenomMetadata", req_mapper)
+}
+
+/// Handle pool, total pool liquidity and list pools by denom queries
+/// for the given pools and their liquidity.
+pub fn pools_query_handler(pools: Vec<(u64, Any, Vec<Coin>)>) -> QueryHandler {
+    let pools_for_liquidity = pools.clone();
+    let pools_for_listing = pools.clone();
+
+    combine_query_handlers(vec![
+        pool_query_handler(Box::new(move |req| {
+            match pools.iter().find(|(pool_id, _, _)| *pool_id == req.pool_id) {
+                Some((_, pool, _)) => ContractResult::Ok(PoolResponse {
+                    pool: Some(pool.clone()),
+                }),
+                None => ContractResult::Err(format!("pool {} not found", req.pool_id)),
+            }
+        })),
+        total_pool_liquidity_query_handler(Box::new(move |req| {
+            match pools_for_liquidity
+                .iter()
+                .find(|(pool_id, _, _)| *pool_id == req.pool_id)
+            {
+                Some((_, _, liquidity)) => ContractResult::Ok(TotalPoolLiquidityResponse {
+                    liquidity: liquidity
+                        .iter()
+                        .map(|coin| ProtoCoin {
+                            denom: coin.denom.clone(),
+                            amount: coin.amount.to_string(),
+                        })
+                        .collect(),
+                }),
+                None => ContractResult::Err(format!("pool {} not found", req.pool_id)),
+            }
+        })),
+        list_pools_by_denom_query_handler(Box::new(move |req| {
+            ContractResult::Ok(ListPoolsByDenomResponse {
+                pools: pools_for_listing
+                    .iter()
+                    .filter(|(_, _, liquidity)| {
+                        liquidity.iter().any(|coin| coin.denom == req.denom)
+                    })
+                    .map(|(_, pool, _)| pool.clone())
+                    .collect(),
+            })
+        })),
+    ])
+}
+
+/// Handle pool and total pool liquidity queries for balancer pools with the given liquidity.
+pub fn balancer_pools_query_handler(pools: Vec<(u64, Vec<Coin>)>) -> QueryHandler {
+    pools_query_handler(
+        pools
+            .into_iter()
+            .map(|(pool_id, liquidity)| (pool_id, balancer_pool(pool_id), liquidity))
+            .collect(),
+    )
+}
+
+pub fn balancer_pool(pool_id: u64) -> Any {
+    BalancerPool {
+        id: pool_id,
+        ..Default::default()
+    }
+    .to_any()
+}
+
+/// Handle smart queries to the oracle contract at `oracle` with the raw query message,
+/// queries to other contracts are rejected as if the contract doesn't exist.
+pub fn oracle_query_handler(
+    oracle: &'static str,
+    query_mapper: Box<dyn Fn(Binary) -> ContractResult<Binary>>,
+) -> impl Fn(&WasmQuery) -> QuerierResult {
+    move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == oracle => {
+            SystemResult::Ok(query_mapper(msg.clone()))
+        }
+        WasmQuery::Smart { contract_addr, .. } => SystemResult::Err(SystemError::NoSuchContract {
+            addr: contract_addr.to_string(),
+        }),
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    }
+}
+
+/// Combine multiple query handlers into one, the first handler that supports the request path
+/// will be used to handle the request.
+pub fn combine_query_handlers(handlers: Vec<QueryHandler>) -> QueryHandler {
+    Box::new(move |path: String, data: Binary| {
+        for handler in handlers.iter() {
+            match handler(path.clone(), data.clone()) {
+                SystemResult::Err(SystemError::UnsupportedRequest { .. }) => continue,
+                res => return res,
+            }
+        }
+
+        SystemResult::Err(SystemError::UnsupportedRequest { kind: path })
+    })
+}
+
 fn make_query_handler<Req, Res>(
     target_path: &'static str,
     req_mapper: Box<dyn Fn(Req) -> ContractResult<Res>>,
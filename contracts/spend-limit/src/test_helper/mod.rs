@@ -1,3 +1,4 @@
 pub mod assert;
 pub mod authenticator_setup;
 pub mod mock_stargate_querier;
+pub mod price;
@@ -0,0 +1,48 @@
+use cosmwasm_std::{Decimal, Timestamp, Uint64};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
+
+use crate::price::{
+    BlockTimeBehindLastUpdate, PriceInfo, PriceResolutionConfig, TrackedDenom, TwapKind,
+};
+
+/// Price resolution config with 1h staleness threshold and twap duration,
+/// override the fields that the test cares about with struct update syntax.
+pub fn price_resolution_config(quote_denom: &str) -> PriceResolutionConfig {
+    PriceResolutionConfig {
+        quote_denom: quote_denom.to_string(),
+        staleness_threshold: Uint64::from(3_600_000_000_000u64),
+        twap_duration: Uint64::from(3_600_000_000_000u64),
+        twap_kind: TwapKind::Arithmetic,
+        max_price_change_per_refresh: None,
+        block_time_behind_last_update: BlockTimeBehindLastUpdate::TreatAsFresh,
+        min_pool_liquidity: None,
+        spot_price_fallback: None,
+        max_price_age: None,
+    }
+}
+
+/// Tracked denom priced through `swap_routes` without any per-denom override.
+pub fn tracked_denom(denom: &str, swap_routes: Vec<SwapAmountInRoute>) -> TrackedDenom {
+    TrackedDenom::twap(denom, swap_routes)
+}
+
+/// Price info calculated through `swap_routes` without any per-denom override.
+pub fn price_info(
+    price: Decimal,
+    last_updated_time: Timestamp,
+    swap_routes: Vec<SwapAmountInRoute>,
+) -> PriceInfo {
+    PriceInfo {
+        price,
+        last_updated_time,
+        swap_routes,
+        fallback_swap_routes: vec![],
+        route_index: 0,
+        twap_kind: None,
+        staleness_threshold: None,
+        twap_duration: None,
+        fixed: false,
+        spot_price_fallback_twap: None,
+        oracle: None,
+    }
+}
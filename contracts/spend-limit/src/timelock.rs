@@ -0,0 +1,153 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Timestamp, Uint64};
+use cw_storage_plus::{Item, Map};
+
+use crate::{admin::Role, msg::ExecuteMsg};
+
+/// Maximum admin action delay in nanoseconds (30 days), so that the timelocked actions
+/// including decreasing the delay itself can't be postponed indefinitely.
+pub const MAX_ADMIN_ACTION_DELAY: Uint64 = Uint64::new(30 * 24 * 3_600_000_000_000);
+
+/// Store for the admin actions waiting for their delay to pass, keyed by the id of the action.
+pub type PendingAdminActionStore<'a> = Map<'a, u64, PendingAdminAction>;
+
+/// Store for the id of the next proposed admin action.
+pub type NextAdminActionIdStore<'a> = Item<'a, u64>;
+
+/// Admin action proposed while the admin action delay is set,
+/// which can only be executed once the delay has passed.
+#[cw_serde]
+pub struct PendingAdminAction {
+    pub id: u64,
+
+    /// Message to execute on behalf of the proposer
+    pub action: ExecuteMsg,
+
+    pub proposer: Addr,
+
+    pub proposed_at: Timestamp,
+
+    /// Time after which the action can be executed
+    pub executable_at: Timestamp,
+}
+
+/// Whether the action needs to be proposed and wait for `delay` before it can be executed.
+///
+/// These are the actions that can loosen the spend limits of the accounts,
+/// e.g. removing tracked denoms makes the spending of those denoms unlimited,
+/// auto tracking a denom overwrites its swap routes and accepting prices
+/// pushes through the price changes rejected by the circuit breaker. A price change can loosen
+/// the limits whichever way it moves, since received coins are credited at the same price.
+/// Granting roles and transferring the admin are timelocked as well,
+/// since the new role holder or admin could loosen the spend limits right away,
+/// while revoking roles or the admin only takes permissions away and takes effect immediately.
+/// Nothing is timelocked while the delay is zero. Decreasing the delay is timelocked
+/// so that it can't be used to bypass the delay, increasing it takes effect immediately.
+pub fn is_timelocked(action: &ExecuteMsg, delay: Uint64) -> bool {
+    if delay.is_zero() {
+        return false;
+    }
+
+    match action {
+        ExecuteMsg::SetPriceResolutionParams { .. }
+        | ExecuteMsg::MigrateQuoteDenom { .. }
+        | ExecuteMsg::RemovePriceProfile { .. }
+        | ExecuteMsg::RemoveTrackedDenoms { .. }
+        | ExecuteMsg::SetTrackedDenoms { .. }
+        | ExecuteMsg::AutoTrackDenom { .. }
+        | ExecuteMsg::SetFixedPrices { .. }
+        | ExecuteMsg::AcceptPrices { .. }
+        | ExecuteMsg::SetDenomExponents { .. }
+        | ExecuteMsg::SetDenomAliases { .. }
+        | ExecuteMsg::RemoveDenomAliases { .. }
+        | ExecuteMsg::GrantRole { .. }
+        | ExecuteMsg::TransferAdmin { .. } => true,
+        ExecuteMsg::SetAdminActionDelay { delay: new_delay } => *new_delay < delay,
+        _ => false,
+    }
+}
+
+/// Role required to propose the timelocked action, `None` if only the admin can propose it.
+pub fn proposer_role(action: &ExecuteMsg) -> Option<Role> {
+    match action {
+        ExecuteMsg::SetPriceResolutionParams { .. }
+        | ExecuteMsg::RemovePriceProfile { .. }
+        | ExecuteMsg::SetFixedPrices { .. }
+        | ExecuteMsg::AcceptPrices { .. } => Some(Role::PriceManager),
+        ExecuteMsg::RemoveTrackedDenoms { .. }
+        | ExecuteMsg::SetTrackedDenoms { .. }
+        | ExecuteMsg::AutoTrackDenom { .. }
+        | ExecuteMsg::SetDenomExponents { .. }
+        | ExecuteMsg::SetDenomAliases { .. }
+        | ExecuteMsg::RemoveDenomAliases { .. } => Some(Role::DenomManager),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::msg::DenomRemovalTarget;
+
+    use super::*;
+
+    #[rstest]
+    #[case::no_delay(
+        ExecuteMsg::RemoveTrackedDenoms { price_profile: None, target: DenomRemovalTarget::All },
+        0,
+        false
+    )]
+    #[case::remove_tracked_denoms(
+        ExecuteMsg::RemoveTrackedDenoms { price_profile: None, target: DenomRemovalTarget::All },
+        100,
+        true
+    )]
+    #[case::set_fixed_prices(
+        ExecuteMsg::SetFixedPrices { price_profile: None, prices: vec![] },
+        100,
+        true
+    )]
+    #[case::remove_denom_aliases(ExecuteMsg::RemoveDenomAliases { denoms: vec![] }, 100, true)]
+    #[case::auto_track_denom(
+        ExecuteMsg::AutoTrackDenom { price_profile: None, denom: "uatom".to_string(), max_hops: None },
+        100,
+        true
+    )]
+    #[case::accept_prices(
+        ExecuteMsg::AcceptPrices { price_profile: None, denoms: vec![] },
+        100,
+        true
+    )]
+    #[case::refresh_prices(
+        ExecuteMsg::RefreshPrices { price_profile: None, denoms: None, start_after: None, limit: None },
+        100,
+        false
+    )]
+    #[case::grant_role(
+        ExecuteMsg::GrantRole { role: Role::PriceManager, address: "addr".to_string() },
+        100,
+        true
+    )]
+    #[case::revoke_role(
+        ExecuteMsg::RevokeRole { role: Role::PriceManager, address: "addr".to_string() },
+        100,
+        false
+    )]
+    #[case::transfer_admin(
+        ExecuteMsg::TransferAdmin { address: "addr".to_string(), expires_at: None },
+        100,
+        true
+    )]
+    #[case::cancel_admin_transfer(ExecuteMsg::CancelAdminTransfer {}, 100, false)]
+    #[case::decrease_delay(ExecuteMsg::SetAdminActionDelay { delay: Uint64::new(99) }, 100, true)]
+    #[case::keep_delay(ExecuteMsg::SetAdminActionDelay { delay: Uint64::new(100) }, 100, false)]
+    #[case::increase_delay(
+        ExecuteMsg::SetAdminActionDelay { delay: Uint64::new(101) },
+        100,
+        false
+    )]
+    fn test_is_timelocked(#[case] action: ExecuteMsg, #[case] delay: u64, #[case] expected: bool) {
+        assert_eq!(is_timelocked(&action, Uint64::new(delay)), expected);
+    }
+}
@@ -511,6 +511,12 @@ async fn select_routes(
             let res = TrackedDenom {
                 denom: denom.to_string(),
                 swap_routes: route_choice.routes,
+                twap_kind: None,
+                fallback_swap_routes: vec![],
+                fixed_price: None,
+                oracle: None,
+                staleness_threshold: None,
+                twap_duration: None,
             };
 
             msg.tracked_denoms.push(res);
@@ -6,24 +6,96 @@
 
 import { CosmWasmClient } from "@cosmjs/cosmwasm-stargate";
 import {
+  AdminActionDelayResponse,
+  AdminCandidateResponse,
+  AdminResponse,
+  AuditLogResponse,
+  DenomAliasesResponse,
+  DenomExponentResponse,
+  PauseStateResponse,
+  PendingAdminActionsResponse,
+  PriceProfilesResponse,
+  PriceResolutionConfigResponse,
+  Role,
+  RoleHoldersResponse,
   SpendingResponse,
   SpendingsByAccountResponse,
-  Uint64,
+  TrackedDenomsResponse,
 } from "./SpendLimit.types";
 export interface SpendLimitReadOnlyInterface {
   contractAddress: string;
+  priceResolutionConfig: ({
+    priceProfile,
+  }: {
+    priceProfile?: string;
+  }) => Promise<PriceResolutionConfigResponse>;
+  trackedDenoms: ({
+    limit,
+    priceProfile,
+    startAfter,
+  }: {
+    limit?: number;
+    priceProfile?: string;
+    startAfter?: string;
+  }) => Promise<TrackedDenomsResponse>;
+  denomAliases: ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }) => Promise<DenomAliasesResponse>;
+  priceProfiles: ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }) => Promise<PriceProfilesResponse>;
   spending: ({
     account,
     authenticatorId,
   }: {
     account: string;
-    authenticatorId: Uint64;
+    authenticatorId: string;
   }) => Promise<SpendingResponse>;
   spendingsByAccount: ({
     account,
   }: {
     account: string;
   }) => Promise<SpendingsByAccountResponse>;
+  denomExponent: ({
+    denom,
+  }: {
+    denom: string;
+  }) => Promise<DenomExponentResponse>;
+  admin: () => Promise<AdminResponse>;
+  adminCandidate: () => Promise<AdminCandidateResponse>;
+  adminActionDelay: () => Promise<AdminActionDelayResponse>;
+  pendingAdminActions: ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<PendingAdminActionsResponse>;
+  pauseState: () => Promise<PauseStateResponse>;
+  auditLog: ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }) => Promise<AuditLogResponse>;
+  roleHolders: ({
+    limit,
+    role,
+    startAfter,
+  }: {
+    limit?: number;
+    role: Role;
+    startAfter?: string;
+  }) => Promise<RoleHoldersResponse>;
 }
 export class SpendLimitQueryClient implements SpendLimitReadOnlyInterface {
   client: CosmWasmClient;
@@ -32,16 +104,84 @@ export class SpendLimitQueryClient implements SpendLimitReadOnlyInterface {
   constructor(client: CosmWasmClient, contractAddress: string) {
     this.client = client;
     this.contractAddress = contractAddress;
+    this.priceResolutionConfig = this.priceResolutionConfig.bind(this);
+    this.trackedDenoms = this.trackedDenoms.bind(this);
+    this.denomAliases = this.denomAliases.bind(this);
+    this.priceProfiles = this.priceProfiles.bind(this);
     this.spending = this.spending.bind(this);
     this.spendingsByAccount = this.spendingsByAccount.bind(this);
+    this.denomExponent = this.denomExponent.bind(this);
+    this.admin = this.admin.bind(this);
+    this.adminCandidate = this.adminCandidate.bind(this);
+    this.adminActionDelay = this.adminActionDelay.bind(this);
+    this.pendingAdminActions = this.pendingAdminActions.bind(this);
+    this.pauseState = this.pauseState.bind(this);
+    this.auditLog = this.auditLog.bind(this);
+    this.roleHolders = this.roleHolders.bind(this);
   }
 
+  priceResolutionConfig = async ({
+    priceProfile,
+  }: {
+    priceProfile?: string;
+  }): Promise<PriceResolutionConfigResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      price_resolution_config: {
+        price_profile: priceProfile,
+      },
+    });
+  };
+  trackedDenoms = async ({
+    limit,
+    priceProfile,
+    startAfter,
+  }: {
+    limit?: number;
+    priceProfile?: string;
+    startAfter?: string;
+  }): Promise<TrackedDenomsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      tracked_denoms: {
+        limit,
+        price_profile: priceProfile,
+        start_after: startAfter,
+      },
+    });
+  };
+  denomAliases = async ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }): Promise<DenomAliasesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      denom_aliases: {
+        limit,
+        start_after: startAfter,
+      },
+    });
+  };
+  priceProfiles = async ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }): Promise<PriceProfilesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      price_profiles: {
+        limit,
+        start_after: startAfter,
+      },
+    });
+  };
   spending = async ({
     account,
     authenticatorId,
   }: {
     account: string;
-    authenticatorId: Uint64;
+    authenticatorId: string;
   }): Promise<SpendingResponse> => {
     return this.client.queryContractSmart(this.contractAddress, {
       spending: {
@@ -61,4 +201,80 @@ export class SpendLimitQueryClient implements SpendLimitReadOnlyInterface {
       },
     });
   };
+  denomExponent = async ({
+    denom,
+  }: {
+    denom: string;
+  }): Promise<DenomExponentResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      denom_exponent: {
+        denom,
+      },
+    });
+  };
+  admin = async (): Promise<AdminResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      admin: {},
+    });
+  };
+  adminCandidate = async (): Promise<AdminCandidateResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      admin_candidate: {},
+    });
+  };
+  adminActionDelay = async (): Promise<AdminActionDelayResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      admin_action_delay: {},
+    });
+  };
+  pendingAdminActions = async ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }): Promise<PendingAdminActionsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      pending_admin_actions: {
+        limit,
+        start_after: startAfter,
+      },
+    });
+  };
+  pauseState = async (): Promise<PauseStateResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      pause_state: {},
+    });
+  };
+  auditLog = async ({
+    limit,
+    startAfter,
+  }: {
+    limit?: number;
+    startAfter?: number;
+  }): Promise<AuditLogResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      audit_log: {
+        limit,
+        start_after: startAfter,
+      },
+    });
+  };
+  roleHolders = async ({
+    limit,
+    role,
+    startAfter,
+  }: {
+    limit?: number;
+    role: Role;
+    startAfter?: string;
+  }): Promise<RoleHoldersResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      role_holders: {
+        limit,
+        role,
+        start_after: startAfter,
+      },
+    });
+  };
 }
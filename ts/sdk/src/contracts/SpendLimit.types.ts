@@ -4,44 +4,356 @@
 * and run the @cosmwasm/ts-codegen generate command to regenerate this file.
 */
 
+export type BlockTimeBehindLastUpdate = "treat_as_fresh" | "reject";
 export type Uint64 = string;
+export type Decimal = string;
+export type Uint128 = string;
+export type TwapKind = "arithmetic" | "geometric";
 export interface InstantiateMsg {
+  admin?: string | null;
   price_resolution_config: PriceResolutionConfig;
   tracked_denoms: TrackedDenom[];
 }
 export interface PriceResolutionConfig {
+  block_time_behind_last_update?: BlockTimeBehindLastUpdate;
+  max_price_age?: Uint64 | null;
+  max_price_change_per_refresh?: Decimal | null;
+  min_pool_liquidity?: Uint128 | null;
   quote_denom: string;
+  spot_price_fallback?: SpotPriceFallback | null;
   staleness_threshold: Uint64;
   twap_duration: Uint64;
+  twap_kind?: TwapKind;
+}
+export interface SpotPriceFallback {
+  markup: Decimal;
+  max_deviation: Decimal;
 }
 export interface TrackedDenom {
   denom: string;
+  fallback_swap_routes?: SwapAmountInRoute[][];
+  fixed_price?: Decimal | null;
+  oracle?: Oracle | null;
+  staleness_threshold?: Uint64 | null;
   swap_routes: SwapAmountInRoute[];
+  twap_duration?: Uint64 | null;
+  twap_kind?: TwapKind | null;
 }
 export interface SwapAmountInRoute {
   pool_id: number;
   token_out_denom: string;
   [k: string]: unknown;
 }
+export interface Oracle {
+  contract: string;
+  price_path: string[];
+  query_template: string;
+}
 export type QueryMsg = {
+  price_resolution_config: {
+    price_profile?: string | null;
+  };
+} | {
+  tracked_denoms: {
+    limit?: number | null;
+    price_profile?: string | null;
+    start_after?: string | null;
+  };
+} | {
+  denom_aliases: {
+    limit?: number | null;
+    start_after?: string | null;
+  };
+} | {
+  price_profiles: {
+    limit?: number | null;
+    start_after?: string | null;
+  };
+} | {
   spending: {
     account: string;
-    authenticator_id: Uint64;
+    authenticator_id: string;
   };
 } | {
   spendings_by_account: {
     account: string;
   };
+} | {
+  denom_exponent: {
+    denom: string;
+  };
+} | {
+  admin: {};
+} | {
+  admin_candidate: {};
+} | {
+  admin_action_delay: {};
+} | {
+  pending_admin_actions: {
+    limit?: number | null;
+    start_after?: number | null;
+  };
+} | {
+  pause_state: {};
+} | {
+  audit_log: {
+    limit?: number | null;
+    start_after?: number | null;
+  };
+} | {
+  role_holders: {
+    limit?: number | null;
+    role: Role;
+    start_after?: string | null;
+  };
 };
+export type Role = "price_manager" | "denom_manager" | "guardian";
+export interface AdminResponse {
+  admin?: string | null;
+}
+export interface AdminActionDelayResponse {
+  delay: Uint64;
+}
 export type Timestamp = Uint64;
-export type Uint128 = string;
+export interface AdminCandidateResponse {
+  candidate?: string | null;
+  expires_at?: Timestamp | null;
+}
+export type AuditChange = {
+  price_resolution_config: {
+    new?: PriceResolutionConfig | null;
+    previous?: PriceResolutionConfig | null;
+    price_profile?: string | null;
+  };
+} | {
+  tracked_denom: {
+    denom: string;
+    new?: TrackedDenom | null;
+    previous?: TrackedDenom | null;
+    price_profile?: string | null;
+  };
+} | {
+  price: {
+    denom: string;
+    new?: Decimal | null;
+    previous?: Decimal | null;
+    price_profile?: string | null;
+  };
+} | {
+  quote_denom_migration: {
+    conversion_rate: Decimal;
+    price_profile?: string | null;
+  };
+};
+export type Addr = string;
+export interface AuditLogResponse {
+  entries: AuditLogEntry[];
+}
+export interface AuditLogEntry {
+  action: string;
+  block_time: Timestamp;
+  changes: AuditChange[];
+  id: number;
+  sender?: Addr | null;
+}
+export interface DenomAliasesResponse {
+  aliases: [string, DenomAlias][];
+}
+export interface DenomAlias {
+  canonical_denom: string;
+  ratio?: Decimal | null;
+}
+export interface DenomExponentResponse {
+  exponent?: number | null;
+}
+export type PauseMode = "frozen" | "strict";
+export interface PauseStateResponse {
+  pause_state?: PauseState | null;
+}
+export interface PauseState {
+  mode: PauseMode;
+  paused_at: Timestamp;
+  paused_by: Addr;
+  reason: string;
+}
+export type ExecuteMsg = {
+  set_price_resolution_params: {
+    block_time_behind_last_update?: BlockTimeBehindLastUpdate | null;
+    max_price_age?: OptionalUpdate_for_Uint64 | null;
+    max_price_change_per_refresh?: OptionalUpdate_for_Decimal | null;
+    min_pool_liquidity?: OptionalUpdate_for_Uint128 | null;
+    price_profile?: string | null;
+    spot_price_fallback?: OptionalUpdate_for_SpotPriceFallback | null;
+    staleness_threshold?: Uint64 | null;
+    twap_duration?: Uint64 | null;
+    twap_kind?: TwapKind | null;
+  };
+} | {
+  migrate_quote_denom: {
+    conversion_rate: Decimal;
+    price_profile?: string | null;
+    quote_denom: string;
+    tracked_denoms?: TrackedDenom[];
+  };
+} | {
+  create_price_profile: {
+    name: string;
+    price_resolution_config: PriceResolutionConfig;
+    tracked_denoms: TrackedDenom[];
+  };
+} | {
+  remove_price_profile: {
+    name: string;
+  };
+} | {
+  remove_tracked_denoms: {
+    price_profile?: string | null;
+    target: DenomRemovalTarget;
+  };
+} | {
+  set_tracked_denoms: {
+    price_profile?: string | null;
+    tracked_denoms: TrackedDenom[];
+  };
+} | {
+  auto_track_denom: {
+    denom: string;
+    max_hops?: number | null;
+    price_profile?: string | null;
+  };
+} | {
+  set_fixed_prices: {
+    price_profile?: string | null;
+    prices: [string, Decimal][];
+  };
+} | {
+  set_denom_exponents: {
+    exponents: [string, number][];
+  };
+} | {
+  set_denom_aliases: {
+    aliases: [string, DenomAlias][];
+  };
+} | {
+  remove_denom_aliases: {
+    denoms: string[];
+  };
+} | {
+  accept_prices: {
+    denoms: string[];
+    price_profile?: string | null;
+  };
+} | {
+  refresh_prices: {
+    denoms?: string[] | null;
+    limit?: number | null;
+    price_profile?: string | null;
+    start_after?: string | null;
+  };
+} | {
+  prune: {
+    limit?: number | null;
+    start_after?: [string, string] | null;
+  };
+} | {
+  set_admin_action_delay: {
+    delay: Uint64;
+  };
+} | {
+  propose_admin_action: {
+    action: ExecuteMsg;
+  };
+} | {
+  execute_admin_action: {
+    id: number;
+  };
+} | {
+  cancel_admin_action: {
+    id: number;
+  };
+} | {
+  pause: {
+    mode?: PauseMode;
+    reason: string;
+  };
+} | {
+  unpause: {};
+} | {
+  grant_role: {
+    address: string;
+    role: Role;
+  };
+} | {
+  revoke_role: {
+    address: string;
+    role: Role;
+  };
+} | {
+  transfer_admin: {
+    address: string;
+    expires_at?: Timestamp | null;
+  };
+} | {
+  claim_admin_transfer: {};
+} | {
+  reject_admin_transfer: {};
+} | {
+  cancel_admin_transfer: {};
+} | {
+  revoke_admin: {};
+};
+export type OptionalUpdate_for_Uint64 = "unset" | {
+  set: Uint64;
+};
+export type OptionalUpdate_for_Decimal = "unset" | {
+  set: Decimal;
+};
+export type OptionalUpdate_for_Uint128 = "unset" | {
+  set: Uint128;
+};
+export type OptionalUpdate_for_SpotPriceFallback = "unset" | {
+  set: SpotPriceFallback;
+};
+export type DenomRemovalTarget = "all" | {
+  partial: string[];
+};
+export interface PendingAdminActionsResponse {
+  actions: PendingAdminAction[];
+}
+export interface PendingAdminAction {
+  action: ExecuteMsg;
+  executable_at: Timestamp;
+  id: number;
+  proposed_at: Timestamp;
+  proposer: Addr;
+}
+export interface PriceProfilesResponse {
+  price_profiles: [string, PriceResolutionConfig][];
+}
+export interface PriceResolutionConfigResponse {
+  price_resolution_config: PriceResolutionConfig;
+}
+export interface RoleHoldersResponse {
+  holders: string[];
+}
 export interface SpendingResponse {
   spending: Spending;
+  value_spent_in_period_display?: Decimal | null;
 }
 export interface Spending {
+  display_limit_exponent?: number | null;
+  fee_spent_in_period?: Uint128;
   last_spent_at: Timestamp;
+  limit_conversion_rate?: Decimal | null;
+  price_profile?: string | null;
+  quote_denom_migrations_applied?: number;
   value_spent_in_period: Uint128;
 }
 export interface SpendingsByAccountResponse {
-  spendings: [Uint64, Spending][];
+  spendings: [string, Spending][];
+  values_spent_in_period_display: [string, Decimal][];
+}
+export interface TrackedDenomsResponse {
+  aliases: [string, DenomAlias][];
+  tracked_denoms: TrackedDenom[];
 }
\ No newline at end of file